@@ -9,8 +9,7 @@ use std::f32::consts::PI;
 use ctru::linear::LinearAllocator;
 use ctru::prelude::*;
 use ctru::services::ndsp::{
-    wave::{Status, Wave},
-    AudioFormat, AudioMix, InterpolationType, Ndsp, OutputMode,
+    wave::Wave, AudioFormat, AudioMix, ChannelQueue, InterpolationType, Ndsp, OutputMode,
 };
 
 // Configuration for the NDSP process and channels.
@@ -73,8 +72,8 @@ fn main() {
     let audio_data2 = audio_data1.clone();
 
     // Setup two wave info objects with the correct configuration and ownership of the audio data.
-    let mut wave_info1 = Wave::new(audio_data1, AudioFormat::PCM16Stereo, false);
-    let mut wave_info2 = Wave::new(audio_data2, AudioFormat::PCM16Stereo, false);
+    let wave_info1 = Wave::new(audio_data1, AudioFormat::PCM16Stereo, false);
+    let wave_info2 = Wave::new(audio_data2, AudioFormat::PCM16Stereo, false);
 
     // Setup the NDSP service and its configuration.
 
@@ -82,7 +81,8 @@ fn main() {
     ndsp.set_output_mode(OutputMode::Stereo);
 
     // Channel configuration. We use channel zero but any channel would do just fine.
-    let mut channel_zero = ndsp.channel(0).unwrap();
+    // The queue takes ownership of the waves while they are being played.
+    let mut channel_zero = ChannelQueue::new(ndsp.channel(0).unwrap());
     channel_zero.set_interpolation(InterpolationType::Linear);
     channel_zero.set_sample_rate(SAMPLE_RATE as f32);
     channel_zero.set_format(AudioFormat::PCM16Stereo);
@@ -92,8 +92,8 @@ fn main() {
     channel_zero.set_mix(&mix);
 
     // First set of queueing for the two buffers. The second one will only play after the first one has ended.
-    channel_zero.queue_wave(&mut wave_info1).unwrap();
-    channel_zero.queue_wave(&mut wave_info2).unwrap();
+    channel_zero.queue(wave_info1).unwrap();
+    channel_zero.queue(wave_info2).unwrap();
 
     println!("\x1b[1;1HPress up/down to change tone frequency");
     println!("\x1b[2;1HPress left/right to change filter");
@@ -105,7 +105,7 @@ fn main() {
 
     println!("\x1b[29;16HPress Start to exit");
 
-    let mut altern = true; // true is wave_info1, false is wave_info2
+    let mut frame: usize = 0;
 
     while apt.main_loop() {
         hid.scan_input();
@@ -143,32 +143,40 @@ fn main() {
         );
 
         if update_params {
-            match filter {
-                1 => channel_zero.iir_biquad_set_params_low_pass_filter(1760., 0.707),
-                2 => channel_zero.iir_biquad_set_params_high_pass_filter(1760., 0.707),
-                3 => channel_zero.iir_biquad_set_params_band_pass_filter(1760., 0.707),
-                4 => channel_zero.iir_biquad_set_params_notch_filter(1760., 0.707),
-                5 => channel_zero.iir_biquad_set_params_peaking_equalizer(1760., 0.707, 3.),
-                _ => channel_zero.iir_biquad_set_enabled(false),
-            }
+            // Setting the parameters of a filter also enables it.
+            let result = match filter {
+                1 => channel_zero.set_lowpass(1760., 0.707),
+                2 => channel_zero.set_highpass(1760., 0.707),
+                3 => channel_zero.set_bandpass(1760., 0.707),
+                4 => channel_zero.set_notch(1760., 0.707),
+                5 => channel_zero.set_peaking_eq(1760., 0.707, 3.),
+                _ => {
+                    channel_zero.set_filter_enabled(false);
+                    Ok(())
+                }
+            };
+
+            result.expect("Couldn't set the filter parameters");
         }
 
-        // Double buffer alternation depending on the one used.
-        let current: &mut Wave<_> = if altern {
-            &mut wave_info1
-        } else {
-            &mut wave_info2
-        };
+        // If the oldest buffer has finished playing, we can refill it with new data and re-queue it.
+        // Since the two buffers are always re-queued in order, this alternates between them.
+        if let Some(mut current) = channel_zero.retrieve_done() {
+            // The new data is flushed once the guard is dropped.
+            fill_buffer(&mut current.edit_buffer().unwrap(), NOTEFREQ[note]);
 
-        // If the current buffer has finished playing, we can refill it with new data and re-queue it.
-        let status = current.status();
-        if let Status::Done = status {
-            fill_buffer(current.get_buffer_mut().unwrap(), NOTEFREQ[note]);
-
-            channel_zero.queue_wave(current).unwrap();
+            channel_zero.queue(current).unwrap();
+        }
 
-            altern = !altern;
+        // Print the DSP statistics once per second, to help spotting audio starvation.
+        if frame % 60 == 0 {
+            println!(
+                "\x1b[7;1Hdsp frames = {} (dropped = {})        ",
+                ndsp.frame_count(),
+                ndsp.dropped_frames()
+            );
         }
+        frame += 1;
 
         gfx.wait_for_vblank();
     }
@@ -0,0 +1,92 @@
+//! Audio Stream example.
+//!
+//! This example showcases how to stream audio in small chunks using a [`ChannelQueue`],
+//! generating ("decoding") a new chunk whenever too little audio is left in the queue.
+
+#![feature(allocator_api)]
+
+use std::f32::consts::PI;
+
+use ctru::prelude::*;
+use ctru::services::ndsp::{wave::Wave, AudioFormat, ChannelQueue, InterpolationType, Ndsp};
+
+// Configuration of the stream.
+const SAMPLE_RATE: usize = 22050;
+const SAMPLES_PER_CHUNK: usize = SAMPLE_RATE / 20; // 1102
+const MIN_QUEUED_CHUNKS: usize = 3;
+
+// Frequency of the generated tone.
+const FREQUENCY: f32 = 440.;
+
+/// Source of audio which produces a continuous sine wave, chunk by chunk.
+struct ToneSource {
+    // Amount of samples produced so far, used to keep the wave continuous between chunks.
+    position: usize,
+}
+
+impl ToneSource {
+    fn fill(&mut self, samples: &mut [i16]) {
+        for sample in samples {
+            let t = self.position as f32 / SAMPLE_RATE as f32;
+            *sample = ((FREQUENCY * t * 2. * PI).sin() * 0.3 * i16::MAX as f32) as i16;
+
+            self.position = (self.position + 1) % SAMPLE_RATE;
+        }
+    }
+}
+
+fn main() {
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let _console = Console::new(gfx.top_screen.borrow_mut());
+
+    let ndsp = Ndsp::new().expect("Couldn't obtain NDSP controller");
+
+    // The queue takes ownership of the chunks while they are being played.
+    let mut queue = ChannelQueue::new(ndsp.channel(0).unwrap());
+    queue.set_interpolation(InterpolationType::Linear);
+    queue.set_sample_rate(SAMPLE_RATE as f32);
+    queue.set_format(AudioFormat::PCM16Mono);
+
+    let mut source = ToneSource { position: 0 };
+
+    // Chunks which finished playing, ready to be refilled.
+    let mut free_chunks: Vec<_> = (0..MIN_QUEUED_CHUNKS + 1)
+        .map(|_| Wave::zeroed(SAMPLES_PER_CHUNK, AudioFormat::PCM16Mono, false))
+        .collect();
+
+    println!("\x1b[29;16HPress Start to exit");
+
+    while apt.main_loop() {
+        hid.scan_input();
+
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        while let Some(chunk) = queue.retrieve_done() {
+            free_chunks.push(chunk);
+        }
+
+        // Keep enough chunks in flight to avoid gaps in the playback.
+        while queue.queued_count() < MIN_QUEUED_CHUNKS {
+            let Some(mut chunk) = free_chunks.pop() else {
+                break;
+            };
+
+            source.fill(chunk.samples_mut::<i16>().unwrap());
+            let _ = chunk.flush();
+
+            queue.queue(chunk).unwrap();
+        }
+
+        println!("\x1b[1;1Hqueued chunks = {}        ", queue.queued_count());
+        println!(
+            "\x1b[2;1Hbuffered samples = {}        ",
+            queue.buffered_samples()
+        );
+
+        gfx.wait_for_vblank();
+    }
+}
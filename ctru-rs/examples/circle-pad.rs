@@ -0,0 +1,67 @@
+//! Circle Pad example.
+//!
+//! This example moves a square on the top screen with the circle pad, faster the further the pad is pushed.
+
+use ctru::prelude::*;
+use ctru::services::gfx::{Color, Screen, Swap};
+
+const SQUARE_SIZE: usize = 16;
+const SQUARE_COLOR: Color = Color::rgb(0xFF, 0x80, 0x00);
+
+// Speed of the square when the circle pad is pushed to the edge, in pixels per frame.
+const MAX_SPEED: f32 = 4.;
+
+// Size of the top screen, in pixels.
+const SCREEN_WIDTH: f32 = 400.;
+const SCREEN_HEIGHT: f32 = 240.;
+
+fn main() {
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let _console = Console::new(gfx.bottom_screen.borrow_mut());
+
+    println!("Move the square with the circle pad.");
+    println!("\x1b[29;12HPress Start to exit");
+
+    let mut top_screen = gfx.top_screen.borrow_mut();
+
+    let (mut x, mut y) = (
+        (SCREEN_WIDTH - SQUARE_SIZE as f32) / 2.,
+        (SCREEN_HEIGHT - SQUARE_SIZE as f32) / 2.,
+    );
+
+    while apt.main_loop() {
+        hid.scan_input();
+
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        let position = hid.circle_position();
+        let (speed_x, speed_y) = position.as_f32();
+
+        // The y axis of the circle pad points up, while the one of the screen points down.
+        x = (x + speed_x * MAX_SPEED).clamp(0., SCREEN_WIDTH - SQUARE_SIZE as f32);
+        y = (y - speed_y * MAX_SPEED).clamp(0., SCREEN_HEIGHT - SQUARE_SIZE as f32);
+
+        print!("\x1b[3;0HCircle pad: {:4} {:4}", position.x(), position.y());
+
+        let mut frame_buffer = top_screen.raw_framebuffer();
+
+        frame_buffer.as_mut_slice().fill(0);
+        frame_buffer.fill_rect(
+            x as isize,
+            y as isize,
+            SQUARE_SIZE,
+            SQUARE_SIZE,
+            SQUARE_COLOR,
+        );
+
+        frame_buffer.flush();
+
+        top_screen.swap_buffers();
+
+        gfx.wait_for_vblank();
+    }
+}
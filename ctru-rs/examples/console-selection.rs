@@ -0,0 +1,44 @@
+//! Console Selection example.
+//!
+//! This example keeps a console on each screen, printing a frame counter on the top one
+//! and log lines on the bottom one by switching which console is selected.
+
+use ctru::prelude::*;
+
+fn main() {
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+
+    let top_console = Console::new(gfx.top_screen.borrow_mut());
+    // The most recently initialized console is selected, so the log goes to the bottom screen by default.
+    let _bottom_console = Console::new(gfx.bottom_screen.borrow_mut());
+
+    println!("Press A to log a line.");
+    println!("Press Start to exit.");
+
+    let mut frame: u32 = 0;
+
+    while apt.main_loop() {
+        hid.scan_input();
+
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        if hid.keys_down().contains(KeyPad::A) {
+            println!("A pressed on frame {frame}");
+        }
+
+        {
+            // Print on the top screen, then go back to the bottom console when the guard is dropped.
+            let _selection = top_console.select_scoped();
+            // The text is flushed to the top console before switching back, even without a newline.
+            print!("\x1b[15;18HFrame: {frame}");
+        }
+
+        frame += 1;
+
+        gfx.wait_for_vblank();
+    }
+}
@@ -0,0 +1,68 @@
+//! Embedded Graphics example.
+//!
+//! This example draws styled text and shapes on the bottom screen using the `embedded-graphics` crate.
+//! It requires the `embedded-graphics` feature of `ctru-rs`.
+
+use ctru::prelude::*;
+use ctru::services::gfx::{Flush, FrameBufferTarget, Screen, Swap};
+
+use embedded_graphics::mono_font::{ascii::FONT_10X20, MonoTextStyle};
+use embedded_graphics::pixelcolor::Rgb888;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyleBuilder, RoundedRectangle};
+use embedded_graphics::text::{Alignment, Text};
+
+fn main() {
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let _console = Console::new(gfx.top_screen.borrow_mut());
+
+    println!("\x1b[29;16HPress Start to exit");
+
+    let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+
+    // We draw the scene only once, so we don't need double buffering.
+    bottom_screen.set_double_buffering(false);
+    // Swapping buffers commits the change from the line above.
+    bottom_screen.swap_buffers();
+
+    let mut target = FrameBufferTarget::new(bottom_screen.raw_framebuffer());
+
+    // Drawing on the framebuffer never fails.
+    let _ = target.clear(Rgb888::new(0x20, 0x20, 0x30));
+
+    let frame_style = PrimitiveStyleBuilder::new()
+        .stroke_color(Rgb888::WHITE)
+        .stroke_width(3)
+        .fill_color(Rgb888::new(0x30, 0x60, 0xC0))
+        .build();
+
+    let _ =
+        RoundedRectangle::with_equal_corners(target.bounding_box().offset(-20), Size::new(16, 16))
+            .into_styled(frame_style)
+            .draw(&mut target);
+
+    let text_style = MonoTextStyle::new(&FONT_10X20, Rgb888::YELLOW);
+
+    let _ = Text::with_alignment(
+        "Hello from\nembedded-graphics!",
+        target.bounding_box().center(),
+        text_style,
+        Alignment::Center,
+    )
+    .draw(&mut target);
+
+    // Since we're not using double buffering, this will render the pixels immediately.
+    bottom_screen.flush_buffers();
+
+    while apt.main_loop() {
+        hid.scan_input();
+
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        gfx.wait_for_vblank();
+    }
+}
@@ -35,7 +35,8 @@ struct FileExplorer<'a> {
 impl<'a> FileExplorer<'a> {
     fn new(apt: &'a Apt, hid: &'a mut Hid, gfx: &'a Gfx) -> Self {
         let mut top_screen = gfx.top_screen.borrow_mut();
-        top_screen.set_wide_mode(true);
+        // The explorer works without wide mode too (e.g. on Old 2DS consoles), it just fits less text.
+        let _ = top_screen.set_wide_mode(true);
         let console = Console::new(top_screen);
 
         FileExplorer {
@@ -0,0 +1,64 @@
+//! 3D Parallax example.
+//!
+//! This example draws rectangles at different depths on the stereoscopic 3D top screen,
+//! offsetting them between the two eyes according to the position of the 3D slider.
+
+use ctru::prelude::*;
+use ctru::services::gfx::{Color, Side, Swap};
+
+// Rectangles to draw as `(x, y, size, depth, color)`.
+// Positive depths pop out of the screen, negative ones sink into it.
+const RECTANGLES: [(isize, isize, usize, f32, Color); 3] = [
+    (60, 60, 120, -1.0, Color::rgb(0x40, 0x40, 0xFF)),
+    (160, 80, 80, 0.0, Color::rgb(0x40, 0xFF, 0x40)),
+    (240, 100, 60, 1.0, Color::rgb(0xFF, 0x40, 0x40)),
+];
+
+// Maximum horizontal offset between the two eyes, in pixels.
+const MAX_DISPARITY: f32 = 10.;
+
+fn main() {
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let _console = Console::new(gfx.bottom_screen.borrow_mut());
+
+    println!("Move the 3D slider to change the depth effect.");
+    println!("\x1b[29;12HPress Start to exit");
+
+    let mut top_screen = gfx.top_screen.borrow_mut();
+    top_screen.set_3d_enabled(true);
+
+    while apt.main_loop() {
+        hid.scan_input();
+
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        let disparity = ctru::os::current_3d_slider_state() * MAX_DISPARITY;
+
+        for (side, direction) in [(Side::Left, 1.), (Side::Right, -1.)] {
+            let mut frame_buffer = top_screen
+                .side_framebuffer(side)
+                .expect("3D mode is enabled, so both sides are available");
+
+            frame_buffer.as_mut_slice().fill(0);
+
+            for (x, y, size, depth, color) in RECTANGLES {
+                // Objects popping out of the screen are shifted right for the left eye, and left for the right eye.
+                let offset = (depth * disparity * direction) as isize;
+
+                frame_buffer.fill_rect(x + offset, y, size, size, color);
+            }
+
+            frame_buffer.flush();
+        }
+
+        top_screen.swap_buffers();
+
+        gfx.wait_for_vblank();
+    }
+
+    top_screen.set_3d_enabled(false);
+}
@@ -0,0 +1,55 @@
+//! Gradient Graphics example.
+//!
+//! This example fills the top screen with a color gradient, writing to the framebuffer using only safe code.
+
+use ctru::prelude::*;
+use ctru::services::gfx::{Flush, Screen, Swap};
+
+fn main() {
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let _console = Console::new(gfx.bottom_screen.borrow_mut());
+
+    println!("\x1b[29;12HPress Start to exit");
+
+    let mut top_screen = gfx.top_screen.borrow_mut();
+
+    // We draw the gradient only once, so we don't need double buffering.
+    top_screen.set_double_buffering(false);
+    // Swapping buffers commits the change from the line above.
+    top_screen.swap_buffers();
+
+    let mut frame_buffer = top_screen.raw_framebuffer();
+
+    let (width, height) = (frame_buffer.width, frame_buffer.height);
+    let bytes_per_pixel = frame_buffer.format.pixel_depth_bytes();
+
+    // The framebuffer is rotated: each `width` pixels long column of data is a row on the screen, starting from the bottom.
+    for (i, pixel) in frame_buffer
+        .as_mut_slice()
+        .chunks_exact_mut(bytes_per_pixel)
+        .enumerate()
+    {
+        let x = i / width;
+        let y = width - 1 - i % width;
+
+        // The default framebuffer format is `Bgr8`.
+        pixel[0] = (255 * y / width) as u8;
+        pixel[1] = 0x40;
+        pixel[2] = (255 * x / height) as u8;
+    }
+
+    // Since we're not using double buffering, this will render the pixels immediately.
+    top_screen.flush_buffers();
+
+    while apt.main_loop() {
+        hid.scan_input();
+
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        gfx.wait_for_vblank();
+    }
+}
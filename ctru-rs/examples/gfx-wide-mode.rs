@@ -30,11 +30,15 @@ fn main() {
 
             // Switch the state of the wide-mode.
             let wide_mode = gfx.top_screen.borrow().is_wide();
-            gfx.top_screen.borrow_mut().set_wide_mode(!wide_mode);
+            let result = gfx.top_screen.borrow_mut().set_wide_mode(!wide_mode);
 
             console = Console::new(gfx.top_screen.borrow_mut());
             println!("Press A to enable/disable wide screen mode.");
             println!("\x1b[29;16HPress Start to exit");
+
+            if let Err(e) = result {
+                println!("\x1b[3;1HCouldn't switch mode: {e}");
+            }
         }
 
         gfx.wait_for_vblank();
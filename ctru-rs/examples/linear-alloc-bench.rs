@@ -0,0 +1,80 @@
+//! LINEAR memory allocation benchmark.
+//!
+//! This example compares the time taken to allocate and fill a big buffer on the LINEAR memory,
+//! when the buffer is zeroed on allocation and when it's left uninitialized.
+
+// You will need to activate this unstable feature to use custom allocators.
+#![feature(allocator_api)]
+
+use std::time::{Duration, Instant};
+
+use ctru::linear::LinearAllocator;
+use ctru::prelude::*;
+
+const BUFFER_SIZE: usize = 4 * 1024 * 1024;
+const ROUNDS: u32 = 10;
+
+// Fill the buffer like a decoder would, writing each byte once.
+fn fill(buffer: &mut [u8]) {
+    for (i, byte) in buffer.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+}
+
+fn bench_zeroed() -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..ROUNDS {
+        let mut buffer = LinearAllocator::try_alloc_slice_zeroed(BUFFER_SIZE)
+            .expect("Couldn't allocate the buffer");
+        fill(&mut buffer);
+        std::hint::black_box(buffer);
+    }
+
+    start.elapsed() / ROUNDS
+}
+
+fn bench_uninit() -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..ROUNDS {
+        let mut buffer = LinearAllocator::alloc_uninit_slice(BUFFER_SIZE);
+
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            byte.write(i as u8);
+        }
+
+        // Safety: every byte has been initialized by the loop above.
+        let buffer = unsafe { buffer.assume_init() };
+        std::hint::black_box(buffer);
+    }
+
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let _console = Console::new(gfx.top_screen.borrow_mut());
+
+    println!(
+        "Allocating and filling {} MB buffers...\n",
+        BUFFER_SIZE >> 20
+    );
+
+    println!("Zeroed, then written: {:?}", bench_zeroed());
+    println!("Uninitialized, then written: {:?}", bench_uninit());
+
+    println!("\x1b[29;16HPress Start to exit");
+
+    while apt.main_loop() {
+        hid.scan_input();
+
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        gfx.wait_for_vblank();
+    }
+}
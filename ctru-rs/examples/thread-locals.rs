@@ -11,7 +11,8 @@ std::thread_local! {
 
 fn main() {
     let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
-    gfx.top_screen.borrow_mut().set_wide_mode(true);
+    // The example works without wide mode too (e.g. on Old 2DS consoles).
+    let _ = gfx.top_screen.borrow_mut().set_wide_mode(true);
     let mut hid = Hid::new().expect("Couldn't obtain HID controller");
     let apt = Apt::new().expect("Couldn't obtain APT controller");
     let _console = Console::new(gfx.top_screen.borrow_mut());
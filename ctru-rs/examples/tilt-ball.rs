@@ -0,0 +1,87 @@
+//! Tilt Ball example.
+//!
+//! This example rolls a ball around the top screen according to how the console is tilted, using the accelerometer.
+
+use ctru::prelude::*;
+use ctru::services::gfx::{Color, Screen, Swap};
+
+const BALL_SIZE: usize = 12;
+const BALL_COLOR: Color = Color::rgb(0x40, 0xC0, 0xFF);
+
+// Size of the top screen, in pixels.
+const SCREEN_WIDTH: f32 = 400.;
+const SCREEN_HEIGHT: f32 = 240.;
+
+// Acceleration reading of 1 g, as read by the accelerometer.
+const GRAVITY: f32 = 512.;
+// Speed gained by the ball each frame, in pixels per frame, when the console is tilted vertically.
+const BALL_ACCELERATION: f32 = 0.5;
+// Part of the speed kept by the ball each frame and after bouncing on an edge.
+const FRICTION: f32 = 0.98;
+const BOUNCINESS: f32 = 0.5;
+
+fn main() {
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let _console = Console::new(gfx.bottom_screen.borrow_mut());
+
+    println!("Tilt the console to roll the ball!");
+    println!("\x1b[29;12HPress Start to exit");
+
+    hid.set_accelerometer(true)
+        .expect("Couldn't activate accelerometer");
+
+    let mut top_screen = gfx.top_screen.borrow_mut();
+
+    let max_x = SCREEN_WIDTH - BALL_SIZE as f32;
+    let max_y = SCREEN_HEIGHT - BALL_SIZE as f32;
+
+    let (mut x, mut y) = (max_x / 2., max_y / 2.);
+    let (mut speed_x, mut speed_y) = (0., 0.);
+
+    while apt.main_loop() {
+        // The accelerometer readings are updated along with the buttons.
+        hid.scan_input();
+
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        let acceleration = hid
+            .accelerometer_vector()
+            .expect("The accelerometer was activated before the loop");
+
+        // Tilting the console to the side rolls the ball horizontally,
+        // while tilting it towards the player (or away) rolls the ball vertically.
+        speed_x = (speed_x + f32::from(acceleration.x()) / GRAVITY * BALL_ACCELERATION) * FRICTION;
+        speed_y = (speed_y + f32::from(acceleration.z()) / GRAVITY * BALL_ACCELERATION) * FRICTION;
+
+        x += speed_x;
+        y += speed_y;
+
+        // Bounce on the edges of the screen.
+        if !(0. ..=max_x).contains(&x) {
+            x = x.clamp(0., max_x);
+            speed_x *= -BOUNCINESS;
+        }
+        if !(0. ..=max_y).contains(&y) {
+            y = y.clamp(0., max_y);
+            speed_y *= -BOUNCINESS;
+        }
+
+        let mut frame_buffer = top_screen.raw_framebuffer();
+
+        frame_buffer.as_mut_slice().fill(0);
+        frame_buffer.fill_rect(x as isize, y as isize, BALL_SIZE, BALL_SIZE, BALL_COLOR);
+        frame_buffer.flush();
+
+        top_screen.swap_buffers();
+
+        gfx.wait_for_vblank();
+    }
+
+    // Save battery by turning the sensor off once it isn't needed anymore.
+    hid.set_accelerometer(false)
+        .expect("Couldn't deactivate accelerometer");
+}
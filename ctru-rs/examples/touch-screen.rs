@@ -1,21 +1,35 @@
 //! Touch Screen example.
 //!
-//! This example showcases how to retrieve the touch screen's touch information via the HID service.
+//! This example showcases how to retrieve the touch screen's touch information via the HID service,
+//! by painting the pixels touched on the bottom screen.
 
 use ctru::prelude::*;
+use ctru::services::gfx::{Color, Screen, Swap};
+
+const BRUSH_SIZE: usize = 3;
+const BRUSH_COLOR: Color = Color::rgb(0xFF, 0xFF, 0xFF);
 
 fn main() {
     let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
     let mut hid = Hid::new().expect("Couldn't obtain HID controller");
     let apt = Apt::new().expect("Couldn't obtain APT controller");
 
-    let console = Console::new(gfx.top_screen.borrow_mut());
-
-    // We'll save the previous touch position for comparison.
-    let mut old_touch: (u16, u16) = (0, 0);
+    let _console = Console::new(gfx.top_screen.borrow_mut());
 
+    println!("Draw on the touch screen!");
+    println!("\x1b[28;16HPress A to clear");
     println!("\x1b[29;16HPress Start to exit");
 
+    let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+
+    // The drawing is kept from one frame to the next, so a single framebuffer is used.
+    bottom_screen.set_double_buffering(false);
+    // Swapping buffers commits the change from the line above.
+    bottom_screen.swap_buffers();
+
+    // We'll save the previous touch position for comparison.
+    let mut old_touch = None;
+
     while apt.main_loop() {
         hid.scan_input();
 
@@ -23,23 +37,42 @@ fn main() {
             break;
         }
 
-        // Get X and Y coordinates of the touch point.
+        let mut frame_buffer = bottom_screen.raw_framebuffer();
+
+        if hid.keys_down().contains(KeyPad::A) {
+            frame_buffer.as_mut_slice().fill(0);
+        }
+
+        // Get X and Y coordinates of the touch point, if the screen is being touched.
         // The touch screen is 320x240.
-        let touch: (u16, u16) = hid.touch_position();
+        let touch = hid.touch_position();
+
+        if let Some(position) = touch {
+            let offset = (BRUSH_SIZE / 2) as isize;
+
+            frame_buffer.fill_rect(
+                position.x() as isize - offset,
+                position.y() as isize - offset,
+                BRUSH_SIZE,
+                BRUSH_SIZE,
+                BRUSH_COLOR,
+            );
+        }
+
+        frame_buffer.flush();
 
         // We only want to print the position when it's different
         // from what it was on the previous frame.
         if touch != old_touch {
             // Move the cursor back to the top of the screen and print the coordinates.
-            print!("\x1b[1;1HTouch Screen position: {:#?}", touch);
-
-            // Special case for when the user lifts the stylus/finger from the screen.
-            // This is done to avoid some screen tearing.
-            if touch == (0, 0) {
-                console.clear();
-
-                // Print again because we just cleared the screen.
-                println!("\x1b[29;16HPress Start to exit");
+            // The trailing spaces erase what was left by longer lines.
+            match touch {
+                Some(position) => println!(
+                    "\x1b[3;1HTouch Screen position: ({}, {})    ",
+                    position.x(),
+                    position.y()
+                ),
+                None => println!("\x1b[3;1HTouch Screen position: none        "),
             }
         }
 
@@ -0,0 +1,61 @@
+//! VRAM example.
+//!
+//! This example showcases simple allocation on the VRAM, by uploading an image to it and then showing it on screen.
+
+// You will need to activate this unstable feature to use custom allocators.
+#![feature(allocator_api)]
+
+use ctru::prelude::*;
+use ctru::services::gfx::{Flush, Screen, Swap};
+use ctru::vram::VramAllocator;
+
+/// Ferris image, in the same format as the one used by the `gfx-bitmap` example.
+static IMAGE: &[u8] = include_bytes!("assets/ferris.rgb");
+
+fn main() {
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let _console = Console::new(gfx.top_screen.borrow_mut());
+
+    let vram_space_before = VramAllocator::free_space();
+
+    // A framebuffer-sized buffer living on the VRAM.
+    let mut vram_image = Vec::with_capacity_in(IMAGE.len(), VramAllocator);
+    vram_image.extend_from_slice(IMAGE);
+
+    println!("VRAM space free before allocation: {vram_space_before}");
+    println!(
+        "VRAM space free after allocation: {}",
+        VramAllocator::free_space()
+    );
+
+    println!("\x1b[29;16HPress Start to exit");
+
+    let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+
+    // We don't need double buffering in this example.
+    bottom_screen.set_double_buffering(false);
+    bottom_screen.swap_buffers();
+
+    // Copy the image from the VRAM to the framebuffer.
+    let frame_buffer = bottom_screen.raw_framebuffer();
+
+    unsafe {
+        frame_buffer
+            .ptr
+            .copy_from(vram_image.as_ptr(), vram_image.len());
+    }
+
+    while apt.main_loop() {
+        hid.scan_input();
+
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        bottom_screen.flush_buffers();
+
+        gfx.wait_for_vblank();
+    }
+}
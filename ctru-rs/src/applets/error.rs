@@ -2,8 +2,11 @@
 //!
 //! This applet displays error text as a pop-up message on the lower screen.
 
+use crate::error::ResultCode;
 use crate::services::{apt::Apt, gfx::Gfx};
 
+use std::fmt::Display;
+
 use ctru_sys::errorConf;
 
 /// Configuration struct to set up the Error applet.
@@ -51,6 +54,29 @@ impl PopUp {
         Self { state }
     }
 
+    /// Initializes the error applet to display the provided result code, formatted as a system error code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use ctru::applets::error::PopUp;
+    /// use ctru::error::ResultCode;
+    ///
+    /// let popup = PopUp::with_code(ResultCode(0xC8804478_u32 as i32));
+    /// ```
+    #[doc(alias = "errorCode")]
+    pub fn with_code(code: ResultCode) -> Self {
+        let mut state = Box::<errorConf>::default();
+
+        unsafe {
+            ctru_sys::errorInit(state.as_mut(), ctru_sys::ERROR_CODE as _, 0);
+            ctru_sys::errorCode(state.as_mut(), code.0);
+        }
+
+        Self { state }
+    }
+
     /// Sets the error text to display.
     ///
     /// # Notes
@@ -94,6 +120,40 @@ impl PopUp {
     }
 }
 
+/// Displays the provided message with the error applet, blocking until the user dismisses it.
+///
+/// This is a shortcut for creating a [`PopUp`] with word wrapping and launching it.
+///
+/// # Errors
+///
+/// The applet needs the [`Apt`] and [`Gfx`] services to be running. An error is returned (instead of panicking)
+/// if the applet couldn't be displayed, so that this function can be used safely while handling other errors.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::applets::error;
+/// use ctru::services::{apt::Apt, gfx::Gfx};
+///
+/// let gfx = Gfx::new()?;
+/// let apt = Apt::new()?;
+///
+/// error::show("The SD card is full. Delete some data and try again.", &apt, &gfx)?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub fn show(message: impl Display, apt: &Apt, gfx: &Gfx) -> Result<(), Error> {
+    let mut popup = PopUp::new(WordWrap::Enabled);
+
+    popup.set_text(&message.to_string());
+
+    popup.launch(apt, gfx)
+}
+
 /// Sets a custom [panic hook](https://doc.rust-lang.org/std/panic/fn.set_hook.html) that uses the error applet to display panic messages.
 ///
 /// You can also choose to have the previously registered panic hook called along with the error applet popup, which can be useful
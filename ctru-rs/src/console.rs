@@ -6,13 +6,59 @@
 //! Have a look at [`Soc::redirect_to_3dslink()`](crate::services::soc::Soc::redirect_to_3dslink) for a better alternative when debugging applications.
 
 use std::cell::{RefMut, UnsafeCell};
+use std::ffi::CStr;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::sync::{Mutex, OnceLock, PoisonError};
 
 use ctru_sys::{consoleClear, consoleInit, consoleSelect, consoleSetWindow, PrintConsole};
 
-use crate::services::gfx::{Flush, Screen, Swap};
+use crate::services::gfx::{self, Flush, Screen, Swap};
+use crate::services::gspgpu::{self, Event};
+use crate::services::hid::{InputSource, KeyPad};
+
+mod scrollback;
+
+use scrollback::{lock_scrollbacks, scrollback_print_char, Scrollback};
+
+// The beginning of newlib's `devoptab_t`, which isn't part of the `libctru` headers.
+#[repr(C)]
+struct DevoptabHead {
+    name: *const libc::c_char,
+    _struct_size: libc::size_t,
+    _open_r: *const libc::c_void,
+    _close_r: *const libc::c_void,
+    write_r: Option<
+        unsafe extern "C" fn(
+            r: *mut libc::c_void,
+            fd: *mut libc::c_void,
+            ptr: *const libc::c_char,
+            len: libc::size_t,
+        ) -> libc::ssize_t,
+    >,
+}
+
+// Name of the device registered by `consoleInit`.
+const CONSOLE_DEVICE_NAME: &CStr = c"con";
+
+extern "C" {
+    // Devices behind the standard streams. `consoleInit` points the `stdout` one to the console device.
+    static mut devoptab_list: [*const DevoptabHead; 3];
+
+    fn __getreent() -> *mut libc::c_void;
+}
 
 static mut EMPTY_CONSOLE: PrintConsole = unsafe { std::mem::zeroed::<PrintConsole>() };
 
+/// Number of lines kept by [`Console::enable_scrollback()`] when a program doesn't need a specific amount.
+pub const DEFAULT_SCROLLBACK_LINES: usize = 300;
+
+// Addresses of the `PrintConsole`s of all living `Console`s, to know which ones can be selected again.
+static LIVE_CONSOLES: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+// Debug output chosen with `set_debug_output()`, which must be set again whenever a console is initialized.
+static DEBUG_OUTPUT: Mutex<Option<DebugOutput>> = Mutex::new(None);
+
 /// Error enum for generic errors within [`Console`].
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Error {
@@ -38,6 +84,141 @@ pub enum Dimension {
     Height,
 }
 
+/// Text colors supported by the [`Console`].
+///
+/// The bright variants of these colors can be obtained for the text by enabling bold text with [`Console::set_bold()`].
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Color {
+    Black = 0,
+    Red = 1,
+    Green = 2,
+    Yellow = 3,
+    Blue = 4,
+    Magenta = 5,
+    Cyan = 6,
+    White = 7,
+}
+
+/// Font used by a [`Console`] to draw its text, see [`Console::set_font()`].
+///
+/// A font is made of 8x8 pixels glyphs, one for each character from the first character up to the last one.
+/// Every glyph takes 8 bytes, one per row of pixels from top to bottom, in which the most significant bit is the leftmost pixel.
+///
+/// # Notes
+///
+/// The console looks glyphs up by byte, not by character. Text printed with `println!` is encoded as UTF-8,
+/// so every non-ASCII character (such as the Latin-1 letters) is drawn as the glyphs of its 2 to 4 bytes, whatever the font.
+/// For this reason no font with extended Latin coverage is bundled with `ctru-rs`: fonts covering other characters
+/// must be used by writing the glyphs' indices as raw bytes, through the [`Write`](std::io::Write) implementation of [`Console`].
+#[doc(alias = "ConsoleFont")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ConsoleFont {
+    data: &'static [u8],
+    first_char: u8,
+    char_count: u16,
+}
+
+impl ConsoleFont {
+    /// Returns a font using the glyphs in `data` for `char_count` characters, starting from `first_char`.
+    ///
+    /// Characters outside of the font aren't drawn.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `data` doesn't contain the 8 bytes of every glyph,
+    /// or if the font has more characters than can be printed (the characters after `first_char` up to 255).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::console::ConsoleFont;
+    ///
+    /// // A font drawing the digits as filled squares.
+    /// static SQUARES: [u8; 80] = [0xFF; 80];
+    ///
+    /// let font = ConsoleFont::new(&SQUARES, b'0', 10)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(data: &'static [u8], first_char: u8, char_count: u16) -> crate::Result<Self> {
+        let wanted = usize::from(char_count) * 8;
+
+        if data.len() < wanted {
+            return Err(crate::Error::BufferTooShort {
+                provided: data.len(),
+                wanted,
+            });
+        }
+        if usize::from(first_char) + usize::from(char_count) > 256 {
+            return Err(crate::Error::Other(format!(
+                "font with {char_count} characters starting from {first_char} goes past the last character"
+            )));
+        }
+
+        Ok(Self {
+            data,
+            first_char,
+            char_count,
+        })
+    }
+
+    /// Returns the font used by default by [`Console`]s, covering the 256 characters.
+    #[doc(alias = "consoleGetDefault")]
+    pub fn default_font() -> Self {
+        let font = unsafe { (*ctru_sys::consoleGetDefault()).font };
+
+        Self {
+            // Safety: the default font is part of the program's data.
+            data: unsafe { std::slice::from_raw_parts(font.gfx, usize::from(font.numChars) * 8) },
+            first_char: font.asciiOffset as u8,
+            char_count: font.numChars,
+        }
+    }
+
+    /// Returns a bolder version of the default font, in which every glyph is one pixel wider.
+    ///
+    /// The font is built the first time this function is called.
+    pub fn default_bold() -> Self {
+        static BOLD_GLYPHS: OnceLock<Vec<u8>> = OnceLock::new();
+
+        let default = Self::default_font();
+        let glyphs = BOLD_GLYPHS.get_or_init(|| bold_glyphs(default.data));
+
+        Self {
+            data: glyphs,
+            ..default
+        }
+    }
+
+    fn as_raw(&self) -> ctru_sys::ConsoleFont {
+        ctru_sys::ConsoleFont {
+            // `libctru` only reads the glyphs.
+            gfx: self.data.as_ptr().cast_mut(),
+            asciiOffset: self.first_char.into(),
+            numChars: self.char_count,
+        }
+    }
+}
+
+/// Destinations for the debug output (`stderr`), see [`set_debug_output()`].
+#[doc(alias = "debugDevice")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DebugOutput {
+    /// Discard all debug output.
+    Null = ctru_sys::debugDevice_NULL,
+    /// Send the debug output to the attached debugger (or the emulator's log) with `svcOutputDebugString`.
+    Svc = ctru_sys::debugDevice_SVC,
+    /// Print the debug output on the selected [`Console`], like the standard output.
+    Console3ds = ctru_sys::debugDevice_CONSOLE,
+}
+
 /// A [`Screen`] that can be used as a target for [`Console`].
 pub trait ConsoleScreen: Screen + Swap + Flush {}
 impl<S: Screen + Swap + Flush> ConsoleScreen for S {}
@@ -55,6 +236,36 @@ impl<S: Screen + Swap + Flush> ConsoleScreen for S {}
 /// - 50x30 on the normal [`TopScreen`](crate::services::gfx::TopScreen).
 /// - 100x30 on the [`TopScreen`](crate::services::gfx::TopScreen) when wide mode is enabled.
 ///
+/// # Screen ownership
+///
+/// The screens of [`Gfx`](crate::services::gfx::Gfx) can only be mutably borrowed once at a time, and the [`Console`] keeps the borrow of its screen.
+/// This prevents drawing on the framebuffer of the screen (or creating a second [`Console`] on it) while the [`Console`] is alive,
+/// since both would fight over the screen's contents. Dropping the [`Console`] gives the screen back.
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::console::Console;
+/// use ctru::services::gfx::Gfx;
+///
+/// let gfx = Gfx::new()?;
+///
+/// let bottom_console = Console::new(gfx.bottom_screen.borrow_mut());
+///
+/// // The screen is taken by the console, so it can't be used to draw.
+/// assert!(gfx.bottom_screen.try_borrow_mut().is_err());
+///
+/// drop(bottom_console);
+///
+/// // The screen can now be used freely again.
+/// assert!(gfx.bottom_screen.try_borrow_mut().is_ok());
+/// #
+/// # Ok(())
+/// # }
+/// ```
+///
 /// # Alternatives
 ///
 /// If you'd like to see live standard output while running the application but cannot or do not want to show the text on the 3DS itself,
@@ -63,7 +274,20 @@ impl<S: Screen + Swap + Flush> ConsoleScreen for S {}
 #[doc(alias = "PrintConsole")]
 pub struct Console<'screen> {
     context: Box<UnsafeCell<PrintConsole>>,
-    screen: RefMut<'screen, dyn ConsoleScreen>,
+    screen: ConsoleTarget<'screen>,
+}
+
+// Where a `Console` prints its text.
+enum ConsoleTarget<'screen> {
+    // The whole screen, owned by the console.
+    Screen(RefMut<'screen, dyn ConsoleScreen>),
+    // A window on the screen of another console, see `Console::new_window()`.
+    // The parent console is borrowed for `'screen`, so its context outlives this console.
+    Window {
+        parent: *mut PrintConsole,
+        screen: ctru_sys::gfxScreen_t,
+        _parent: PhantomData<&'screen ()>,
+    },
 }
 
 impl<'screen> Console<'screen> {
@@ -108,9 +332,72 @@ impl<'screen> Console<'screen> {
     pub fn new<S: ConsoleScreen>(screen: RefMut<'screen, S>) -> Self {
         let context = Box::<UnsafeCell<PrintConsole>>::default();
 
-        unsafe { consoleInit(screen.as_raw(), context.get()) };
+        unsafe { init_context(screen.as_raw(), context.get()) };
 
-        Console { context, screen }
+        Console::register(context, ConsoleTarget::Screen(screen))
+    }
+
+    /// Initialize a new console printing to a window of the same screen as this one.
+    ///
+    /// The window is placed like with [`Console::set_window()`], and the new console is automatically selected for printing.
+    /// This is useful to show different kinds of text on separate parts of the screen, such as a log below a status panel.
+    ///
+    /// # Notes
+    ///
+    /// The new console only shares the screen: it doesn't own it, so swapping or flushing its buffers has no effect.
+    /// These operations should be done through the console which owns the screen instead.
+    ///
+    /// Consoles with overlapping windows will overwrite each other's text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::console::Console;
+    /// use ctru::services::gfx::Gfx;
+    ///
+    /// let gfx = Gfx::new()?;
+    ///
+    /// // The top half of the screen shows a status line...
+    /// let mut status = Console::new(gfx.bottom_screen.borrow_mut());
+    /// status.set_window(0, 0, 40, 15)?;
+    ///
+    /// // ...while the bottom half shows a log.
+    /// let log = status.new_window(0, 15, 40, 15)?;
+    ///
+    /// println!("Log line");
+    /// log.clear();
+    ///
+    /// status.select();
+    /// println!("Status: OK");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "consoleSetWindow")]
+    pub fn new_window(&self, x: u8, y: u8, width: u8, height: u8) -> Result<Console<'_>, Error> {
+        // The new console starts with the same configuration (such as the font and framebuffer) as this one.
+        let context = Box::new(UnsafeCell::new(unsafe { self.context.get().read() }));
+
+        // The scrollback of this console isn't shared with the new one.
+        unsafe { (*context.get()).PrintChar = None };
+
+        let target = ConsoleTarget::Window {
+            parent: self.context.get(),
+            screen: self.raw_screen(),
+            _parent: PhantomData,
+        };
+
+        let mut console = Console::register(context, target);
+
+        console.set_window(x, y, width, height)?;
+        console.select();
+        console.clear();
+
+        Ok(console)
     }
 
     /// Returns `true` if a valid [`Console`] to print on is currently selected.
@@ -157,6 +444,7 @@ impl<'screen> Console<'screen> {
     /// # Notes
     ///
     /// Any previously selected console will be unhooked and will not show the `stdout` and `stderr` output.
+    /// The text already printed to `stdout` is flushed to the previous console beforehand.
     ///
     /// # Example
     ///
@@ -188,15 +476,160 @@ impl<'screen> Console<'screen> {
     /// ```
     #[doc(alias = "consoleSelect")]
     pub fn select(&self) {
+        flush_stdout();
+
         unsafe {
             consoleSelect(self.context.get());
         }
     }
 
+    /// Select this console as the current target for standard output, until the returned guard is dropped.
+    ///
+    /// When the guard is dropped, the previously selected console is selected again (if it still exists).
+    /// Like with [`Console::select()`], the text already printed to `stdout` is flushed before switching consoles.
+    /// This is useful to print a few lines on another console without keeping track of which one was selected before.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::Console;
+    ///
+    /// let top_console = Console::new(gfx.top_screen.borrow_mut());
+    /// let bottom_console = Console::new(gfx.bottom_screen.borrow_mut());
+    ///
+    /// {
+    ///     let _selection = top_console.select_scoped();
+    ///     println!("I'm on the top screen!");
+    /// }
+    ///
+    /// println!("I'm back on the bottom screen.");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "consoleSelect")]
+    pub fn select_scoped(&self) -> ConsoleSelection<'_> {
+        flush_stdout();
+
+        let previous = unsafe { consoleSelect(self.context.get()) };
+
+        ConsoleSelection {
+            previous,
+            _console: PhantomData,
+        }
+    }
+
     /// Clear all text from the console.
+    ///
+    /// Only the console's window (see [`Console::set_window()`]) is cleared, even if this console isn't selected.
     #[doc(alias = "consoleClear")]
     pub fn clear(&self) {
-        unsafe { consoleClear() }
+        unsafe {
+            let previous_console = consoleSelect(self.context.get());
+
+            consoleClear();
+
+            consoleSelect(previous_console);
+        }
+    }
+
+    /// Clear the text of row `y` of the console's window, without moving the cursor.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `y` is out of the console's window (see [`Console::height()`]).
+    pub fn clear_line(&self, y: u8) -> Result<(), Error> {
+        if y >= self.height() {
+            return Err(Error::CoordinateOutOfBounds(Axis::Y));
+        }
+
+        let (previous_x, previous_y) = self.cursor();
+
+        self.set_cursor(0, y)?;
+        let _ = self.write_raw(b"\x1b[2K");
+        self.set_cursor(previous_x, previous_y)?;
+
+        Ok(())
+    }
+
+    /// Move the cursor of the console, where the next character will be printed.
+    ///
+    /// The coordinates are the column and the row of the cursor, relative to the top-left corner of the console's window.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the coordinates are out of the console's window (see [`Console::width()`] and [`Console::height()`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::Console;
+    ///
+    /// let top_console = Console::new(gfx.top_screen.borrow_mut());
+    ///
+    /// // Show the battery level in the bottom-right corner.
+    /// top_console.set_cursor(top_console.width() - 10, top_console.height() - 1)?;
+    /// assert_eq!(top_console.cursor(), (40, 29));
+    ///
+    /// print!("Battery: 5");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_cursor(&self, x: u8, y: u8) -> Result<(), Error> {
+        if x >= self.width() {
+            return Err(Error::CoordinateOutOfBounds(Axis::X));
+        }
+        if y >= self.height() {
+            return Err(Error::CoordinateOutOfBounds(Axis::Y));
+        }
+
+        // Text printed before moving the cursor must still be shown where it was printed.
+        flush_stdout();
+
+        unsafe {
+            let context = self.context.get();
+
+            (*context).cursorX = x.into();
+            (*context).cursorY = y.into();
+        }
+
+        Ok(())
+    }
+
+    /// Returns the column and the row of the cursor, relative to the top-left corner of the console's window.
+    ///
+    /// # Notes
+    ///
+    /// After printing on the last column of a row, the column is equal to [`Console::width()`] until the next character is printed.
+    pub fn cursor(&self) -> (u8, u8) {
+        let context = unsafe { &*self.context.get() };
+
+        (context.cursorX as u8, context.cursorY as u8)
+    }
+
+    /// Returns the width of the console's window, in characters.
+    pub fn width(&self) -> u8 {
+        unsafe { (*self.context.get()).windowWidth as u8 }
+    }
+
+    /// Returns the height of the console's window, in characters.
+    pub fn height(&self) -> u8 {
+        unsafe { (*self.context.get()).windowHeight as u8 }
     }
 
     /// Resize the console to fit in a smaller portion of the screen.
@@ -238,10 +671,10 @@ impl<'screen> Console<'screen> {
             return Err(Error::CoordinateOutOfBounds(Axis::Y));
         }
 
-        if (x + width) > length_limit {
+        if u16::from(x) + u16::from(width) > length_limit.into() {
             return Err(Error::DimensionOutOfBounds(Dimension::Width));
         }
-        if (y + height) > height_limit {
+        if u16::from(y) + u16::from(height) > height_limit.into() {
             return Err(Error::DimensionOutOfBounds(Dimension::Height));
         }
 
@@ -313,7 +746,7 @@ impl<'screen> Console<'screen> {
     /// # }
     /// ```
     pub fn max_width(&self) -> u8 {
-        match self.screen.as_raw() {
+        match self.raw_screen() {
             ctru_sys::GFX_TOP => {
                 if unsafe { ctru_sys::gfxIsWide() } {
                     100
@@ -325,74 +758,905 @@ impl<'screen> Console<'screen> {
             _ => unreachable!(),
         }
     }
-}
 
-impl Swap for Console<'_> {
-    /// Swaps the video buffers. Note: The console's cursor position is not reset, only the framebuffer is changed.
+    /// Update the console after a change to the configuration of its screen, such as the wide mode of the top screen.
     ///
-    /// Even if double buffering is disabled, "swapping" the buffers has the side effect
-    /// of committing any configuration changes to the buffers (e.g. [`TopScreen::set_wide_mode()`],
-    /// [`Screen::set_framebuffer_format()`], [`Swap::set_double_buffering()`]), so it should still be used.
+    /// The size of the console is updated to fill the screen again, while the window ([`Console::set_window()`]) is kept if it still fits.
+    /// The colors, the font and the position of the cursor are kept as well.
     ///
-    /// This should be called once per frame at most.
-    fn swap_buffers(&mut self) {
-        self.screen.swap_buffers();
+    /// # Notes
+    ///
+    /// Since the layout of the framebuffer changes, the text already on screen is cleared.
+    /// If the scrollback is enabled (see [`Console::enable_scrollback()`]), the kept lines are drawn again.
+    ///
+    /// Consoles printing to a window of another console ([`Console::new_window()`]) only follow the framebuffer of that console:
+    /// [`Console::update_screen_mode()`] must be called on the console owning the screen first.
+    #[doc(alias = "consoleInit")]
+    pub fn update_screen_mode(&mut self) {
+        flush_stdout();
 
-        unsafe {
-            (*self.context.get()).frameBuffer = self.screen.raw_framebuffer().ptr as *mut u16
-        };
-    }
+        let context = self.context.get();
+        let previous = unsafe { context.read() };
 
-    fn set_double_buffering(&mut self, enabled: bool) {
-        self.screen.set_double_buffering(enabled);
-    }
-}
+        match &self.screen {
+            ConsoleTarget::Screen(screen) => unsafe {
+                let previous_console = consoleSelect(context);
 
-impl Flush for Console<'_> {
-    fn flush_buffers(&mut self) {
-        self.screen.flush_buffers();
-    }
-}
+                init_context(screen.as_raw(), context);
 
-impl Drop for Console<'_> {
-    fn drop(&mut self) {
-        unsafe {
-            // Safety: We are about to deallocate the PrintConsole data pointed
-            // to by libctru. Without this drop code libctru would have a
-            // dangling pointer that it writes to on every print. To prevent
-            // this we replace the console with an empty one if it was selected.
-            // This is the same state that libctru starts up in, before
-            // initializing a console. Writes to the console will not show up on
-            // the screen, but it won't crash either.
+                consoleSelect(previous_console);
+            },
+            ConsoleTarget::Window { parent, .. } => unsafe {
+                let parent = &**parent;
 
-            // Get the current console by replacing it with an empty one.
-            let current_console = ctru_sys::consoleSelect(std::ptr::addr_of_mut!(EMPTY_CONSOLE));
+                (*context).frameBuffer = parent.frameBuffer;
+                (*context).consoleWidth = parent.consoleWidth;
+                (*context).consoleHeight = parent.consoleHeight;
+            },
+        }
 
-            if std::ptr::eq(current_console, self.context.get()) {
-                // Console dropped while selected. We just replaced it with the
-                // empty console so nothing more to do.
-            } else {
-                // Console dropped while a different console was selected. Put back
-                // the console that was selected.
-                ctru_sys::consoleSelect(current_console);
+        unsafe {
+            let context = &mut *context;
+
+            if previous.windowX + previous.windowWidth <= context.consoleWidth
+                && previous.windowY + previous.windowHeight <= context.consoleHeight
+            {
+                context.windowX = previous.windowX;
+                context.windowY = previous.windowY;
+                context.windowWidth = previous.windowWidth;
+                context.windowHeight = previous.windowHeight;
             }
-        }
-    }
-}
 
-impl std::fmt::Display for Axis {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::X => write!(f, "x"),
-            Self::Y => write!(f, "y"),
+            context.cursorX = previous.cursorX.min(context.windowWidth - 1);
+            context.cursorY = previous.cursorY.min(context.windowHeight - 1);
+
+            context.font = previous.font;
+            context.fg = previous.fg;
+            context.bg = previous.bg;
+            context.flags = previous.flags;
+            context.tabSize = previous.tabSize;
+            context.PrintChar = previous.PrintChar;
         }
+
+        self.render_scrollback();
     }
-}
 
-impl std::fmt::Display for Dimension {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Width => write!(f, "width"),
+    /// Enable or disable wide mode on the top screen used by this console, then update the console to the new size.
+    ///
+    /// In wide mode, the console is twice as wide (100 characters instead of 50). See [`TopScreen::set_wide_mode()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [`TopScreen::set_wide_mode()`].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the console doesn't own the top screen (such as consoles created with [`Console::new_window()`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::Console;
+    ///
+    /// let mut top_console = Console::new(gfx.top_screen.borrow_mut());
+    ///
+    /// if top_console.set_wide_mode(true).is_ok() {
+    ///     assert_eq!(top_console.width(), 100);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`TopScreen::set_wide_mode()`]: crate::services::gfx::TopScreen::set_wide_mode
+    #[doc(alias = "gfxSetWide")]
+    pub fn set_wide_mode(&mut self, enable: bool) -> Result<(), gfx::Error> {
+        let ConsoleTarget::Screen(screen) = &mut self.screen else {
+            panic!("wide mode can only be changed by the console owning the top screen");
+        };
+
+        assert_eq!(
+            screen.as_raw(),
+            ctru_sys::GFX_TOP,
+            "wide mode can only be changed by the console owning the top screen"
+        );
+
+        gfx::set_wide_mode(enable)?;
+
+        // Commit the change to the screen before reading its new configuration.
+        screen.swap_buffers();
+
+        self.update_screen_mode();
+
+        Ok(())
+    }
+
+    /// Change the font used to draw the text printed from now on to this console.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::{Console, ConsoleFont};
+    ///
+    /// let mut top_console = Console::new(gfx.top_screen.borrow_mut());
+    ///
+    /// top_console.set_font(ConsoleFont::default_bold());
+    /// println!("Easier to read!");
+    ///
+    /// top_console.set_font(ConsoleFont::default_font());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "consoleSetFont")]
+    pub fn set_font(&mut self, font: ConsoleFont) {
+        // Text printed before changing the font must still be drawn with the previous one.
+        flush_stdout();
+
+        unsafe { ctru_sys::consoleSetFont(self.context.get(), &mut font.as_raw()) };
+    }
+
+    /// Set the color of the text printed from now on to this console.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::{Color, Console};
+    ///
+    /// let top_console = Console::new(gfx.top_screen.borrow_mut());
+    ///
+    /// top_console.set_text_color(Color::Red);
+    /// println!("Something went wrong!");
+    ///
+    /// top_console.reset_style();
+    /// println!("Back to normal.");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_text_color(&self, color: Color) {
+        self.write_style(&[30 + color as u8]);
+    }
+
+    /// Set the background color of the text printed from now on to this console.
+    pub fn set_background_color(&self, color: Color) {
+        self.write_style(&[40 + color as u8]);
+    }
+
+    /// Enable or disable bold text for the text printed from now on to this console.
+    ///
+    /// Bold text is drawn with the bright variant of the text color.
+    pub fn set_bold(&self, bold: bool) {
+        self.write_style(&[if bold { 1 } else { 22 }]);
+    }
+
+    /// Reset the text color, background color and bold text of this console to their defaults.
+    pub fn reset_style(&self) {
+        self.write_style(&[0]);
+    }
+
+    /// Select this console and print with the chosen text color while running `f`.
+    ///
+    /// Once `f` returns, the previous text color is restored and the previously selected console is selected again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::{Color, Console};
+    ///
+    /// let top_console = Console::new(gfx.top_screen.borrow_mut());
+    ///
+    /// print!("Status: ");
+    /// top_console.with_color(Color::Green, || println!("OK"));
+    /// println!("This text isn't green.");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_color<T>(&self, color: Color, f: impl FnOnce() -> T) -> T {
+        let _selection = self.select_scoped();
+
+        let previous = (unsafe { (*self.context.get()).fg } % 8) as u8;
+
+        self.set_text_color(color);
+        let result = f();
+        self.write_style(&[30 + previous]);
+
+        result
+    }
+
+    /// Keep the last `lines` lines printed to this console, to be able to scroll back to them.
+    ///
+    /// Once enabled, [`Console::scroll_up()`] and [`Console::scroll_down()`] move the view through the kept lines.
+    /// Text printed while scrolled up is kept without moving the view, until [`Console::scroll_to_bottom()`] is called.
+    /// Enabling the scrollback again replaces the lines kept so far.
+    ///
+    /// # Notes
+    ///
+    /// The buffer keeps at least as many lines as the console's window is tall. It only keeps the printed characters:
+    /// colors and cursor movements done with escape sequences (or [`Console::set_cursor()`]) aren't remembered.
+    ///
+    /// The scrollback is disabled by default, so consoles which don't need it don't keep any text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::{self, Console};
+    ///
+    /// let mut top_console = Console::new(gfx.top_screen.borrow_mut());
+    /// top_console.enable_scrollback(console::DEFAULT_SCROLLBACK_LINES);
+    ///
+    /// for i in 0..100 {
+    ///     println!("Log line {i}");
+    /// }
+    ///
+    /// // Show the lines which scrolled off the screen.
+    /// top_console.scroll_up(30);
+    ///
+    /// // Go back to the latest lines.
+    /// top_console.scroll_to_bottom();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn enable_scrollback(&mut self, lines: usize) {
+        // The text already printed goes to the screen, since it isn't kept.
+        flush_stdout();
+
+        let address = self.context.get() as usize;
+        let (cursor_x, cursor_y) = self.cursor();
+
+        // Start from the current position, so that the kept lines match the screen.
+        let mut scrollback = Scrollback::new(lines.max(self.height().into()));
+        let width = self.width().into();
+
+        for _ in 0..cursor_y {
+            scrollback.push(b'\n', width, 1);
+        }
+        for _ in 0..cursor_x {
+            scrollback.push(b' ', width, 1);
+        }
+
+        {
+            let mut scrollbacks = lock_scrollbacks();
+            scrollbacks.retain(|(console, _)| *console != address);
+            scrollbacks.push((address, scrollback));
+        }
+
+        unsafe { (*self.context.get()).PrintChar = Some(scrollback_print_char) };
+    }
+
+    /// Stop keeping the lines printed to this console, freeing the scrollback buffer.
+    ///
+    /// If the console is scrolled up, the latest lines are shown again first.
+    pub fn disable_scrollback(&mut self) {
+        self.scroll_to_bottom();
+
+        unsafe { (*self.context.get()).PrintChar = None };
+
+        let address = self.context.get() as usize;
+        lock_scrollbacks().retain(|(console, _)| *console != address);
+    }
+
+    /// Scroll the view up by `lines` lines, showing older lines kept by the scrollback buffer.
+    ///
+    /// This does nothing if the scrollback isn't enabled (see [`Console::enable_scrollback()`]).
+    pub fn scroll_up(&self, lines: usize) {
+        self.scroll(lines.try_into().unwrap_or(isize::MAX));
+    }
+
+    /// Scroll the view down by `lines` lines, towards the latest lines.
+    ///
+    /// This does nothing if the scrollback isn't enabled (see [`Console::enable_scrollback()`]).
+    pub fn scroll_down(&self, lines: usize) {
+        self.scroll(-lines.try_into().unwrap_or(isize::MAX));
+    }
+
+    /// Scroll the view down to the latest lines, where the new text is printed.
+    ///
+    /// This does nothing if the scrollback isn't enabled (see [`Console::enable_scrollback()`]).
+    pub fn scroll_to_bottom(&self) {
+        self.scroll(isize::MIN);
+    }
+
+    // Move the scrollback view, then draw the lines in view if it moved.
+    fn scroll(&self, lines: isize) {
+        // Text printed before scrolling must be kept before rendering the buffer.
+        flush_stdout();
+
+        let address = self.context.get() as usize;
+        let height = self.height().into();
+
+        {
+            let mut scrollbacks = lock_scrollbacks();
+
+            let Some((_, scrollback)) = scrollbacks
+                .iter_mut()
+                .find(|(console, _)| *console == address)
+            else {
+                return;
+            };
+
+            if !scrollback.scroll(lines, height) {
+                return;
+            }
+        }
+
+        self.render_scrollback();
+    }
+
+    // Draw the lines in the view of the scrollback buffer, if enabled.
+    fn render_scrollback(&self) {
+        let address = self.context.get() as usize;
+        let width = self.width().into();
+        let height = self.height().into();
+
+        let Some((visible_lines, cursor)) = lock_scrollbacks()
+            .iter()
+            .find(|(console, _)| *console == address)
+            .map(|(_, scrollback)| scrollback.visible_lines(height))
+        else {
+            return;
+        };
+
+        // The lines are already kept, so they mustn't be kept again while rendering them.
+        unsafe { (*self.context.get()).PrintChar = None };
+
+        self.clear();
+
+        for (y, line) in visible_lines.iter().enumerate() {
+            unsafe {
+                (*self.context.get()).cursorX = 0;
+                (*self.context.get()).cursorY = y as _;
+            }
+
+            // The lines may have been kept while the window was wider.
+            let _ = self.write_raw(&line[..line.len().min(width)]);
+        }
+
+        // Only the view at the bottom can be printed on.
+        if let Some((x, y)) = cursor {
+            unsafe {
+                (*self.context.get()).cursorX = x as _;
+                (*self.context.get()).cursorY = y as _;
+            }
+        }
+
+        unsafe { (*self.context.get()).PrintChar = Some(scrollback_print_char) };
+    }
+
+    /// Block until a new key is pressed, and return the keys pressed during that frame.
+    ///
+    /// The text already printed is shown before waiting, and the input is polled once every VBlank.
+    /// It's usually read from the [`Hid`](crate::services::hid::Hid) service, but any [`InputSource`] can be used.
+    ///
+    /// # Notes
+    ///
+    /// If the application is asked to close (see [`Apt::main_loop()`](crate::services::apt::Apt::main_loop)) or the input source
+    /// [is finished](InputSource::is_finished) while waiting, this function returns an empty [`KeyPad`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::Console;
+    /// use ctru::services::hid::{Hid, KeyPad};
+    ///
+    /// let mut hid = Hid::new()?;
+    /// let top_console = Console::new(gfx.top_screen.borrow_mut());
+    ///
+    /// println!("Press A to continue");
+    ///
+    /// while !top_console.read_key(&mut hid).contains(KeyPad::A) {}
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_key(&self, mut input: impl InputSource) -> KeyPad {
+        flush_stdout();
+
+        while unsafe { ctru_sys::aptMainLoop() } && !input.is_finished() {
+            gspgpu::wait_for_event(Event::VBlank0, true);
+
+            let keys = input.poll().keys_down();
+
+            if !keys.is_empty() {
+                return keys;
+            }
+        }
+
+        KeyPad::empty()
+    }
+
+    /// Show a menu with the given items, and block until one of them is chosen.
+    ///
+    /// The item is chosen with the D-Pad (or the CirclePad) and confirmed with A, in which case its index is returned.
+    /// Pressing B (or the application being asked to close) cancels the menu, returning `None` instead.
+    /// The keys are read with [`Console::read_key()`], so from any [`InputSource`].
+    ///
+    /// # Notes
+    ///
+    /// The menu is shown from the cursor's row, or higher if it doesn't fit below it. If there are more items than rows in the console's window,
+    /// the menu scrolls to always show the chosen item. Once done, the menu is cleared and the cursor is put back where it was.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::Console;
+    /// use ctru::services::hid::Hid;
+    ///
+    /// let mut hid = Hid::new()?;
+    /// let top_console = Console::new(gfx.top_screen.borrow_mut());
+    ///
+    /// println!("Pick a difficulty:");
+    ///
+    /// match top_console.menu(&mut hid, &["Easy", "Normal", "Hard"]) {
+    ///     Some(index) => println!("Difficulty {index} chosen"),
+    ///     None => println!("Cancelled"),
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn menu(&self, mut input: impl InputSource, items: &[&str]) -> Option<usize> {
+        if items.is_empty() {
+            return None;
+        }
+
+        let (previous_x, previous_y) = self.cursor();
+
+        let height = usize::from(self.height());
+        let rows = items.len().min(height);
+        let first_row = usize::from(previous_y).min(height - rows);
+
+        let mut selected = 0;
+        let mut first_item = 0;
+
+        for (row, item) in items.iter().take(rows).enumerate() {
+            self.draw_menu_item(first_row + row, item, row == selected);
+        }
+
+        let choice = loop {
+            let keys = self.read_key(&mut input);
+
+            if keys.contains(KeyPad::A) {
+                break Some(selected);
+            }
+            if keys.is_empty() || keys.contains(KeyPad::B) {
+                break None;
+            }
+
+            let previous_selected = selected;
+
+            if keys.intersects(KeyPad::UP) {
+                selected = selected.checked_sub(1).unwrap_or(items.len() - 1);
+            } else if keys.intersects(KeyPad::DOWN) {
+                selected = (selected + 1) % items.len();
+            } else {
+                continue;
+            }
+
+            let previous_first_item = first_item;
+            first_item = menu_first_item(first_item, selected, rows);
+
+            // Only the lines which changed are drawn again.
+            let redrawn_items = if first_item == previous_first_item {
+                vec![previous_selected, selected]
+            } else {
+                (first_item..first_item + rows).collect()
+            };
+
+            for item in redrawn_items {
+                self.draw_menu_item(first_row + item - first_item, items[item], item == selected);
+            }
+        };
+
+        for row in first_row..first_row + rows {
+            let _ = self.clear_line(row as u8);
+        }
+
+        unsafe {
+            (*self.context.get()).cursorX = previous_x.into();
+            (*self.context.get()).cursorY = previous_y.into();
+        }
+
+        choice
+    }
+
+    // Draw a menu item on `row`, cut to fit in a single line.
+    fn draw_menu_item(&self, row: usize, item: &str, selected: bool) {
+        let marker = if selected { '>' } else { ' ' };
+        let text: String = item
+            .chars()
+            .take(usize::from(self.width()).saturating_sub(2))
+            .collect();
+
+        let _ = self.clear_line(row as u8);
+        let _ = self.set_cursor(0, row as u8);
+        let _ = self.write_raw(format!("{marker} {text}").as_bytes());
+    }
+
+    // Send a style escape sequence to this console.
+    fn write_style(&self, codes: &[u8]) {
+        let _ = self.write_raw(style_sequence(codes).as_bytes());
+    }
+
+    // Write the bytes straight to this console, whichever console is selected.
+    fn write_raw(&self, bytes: &[u8]) -> std::io::Result<usize> {
+        // Keep other threads from printing while this console is temporarily selected.
+        let _stdout = std::io::stdout().lock();
+        let _stderr = std::io::stderr().lock();
+
+        // The console device refuses empty writes.
+        if bytes.is_empty() {
+            return Ok(0);
+        }
+
+        let _selection = self.select_scoped();
+
+        // Neither the standard output nor its file descriptor are used: the former may have text buffered for another console,
+        // and the latter may have been redirected elsewhere (e.g. by `Soc::redirect_to_3dslink()`).
+        // The console device is called directly instead, which also handles the escape sequences.
+        let device = unsafe { (*std::ptr::addr_of!(devoptab_list))[libc::STDOUT_FILENO as usize] };
+        let write = unsafe { device.as_ref() }
+            .filter(|device| unsafe { CStr::from_ptr(device.name) } == CONSOLE_DEVICE_NAME)
+            .and_then(|device| device.write_r)
+            .ok_or(std::io::ErrorKind::Unsupported)?;
+
+        let written = unsafe {
+            write(
+                __getreent(),
+                std::ptr::null_mut(),
+                bytes.as_ptr().cast(),
+                bytes.len(),
+            )
+        };
+
+        if written < 0 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(written as usize)
+        }
+    }
+
+    // The screen this console prints to, whether it owns it or not.
+    fn raw_screen(&self) -> ctru_sys::gfxScreen_t {
+        match &self.screen {
+            ConsoleTarget::Screen(screen) => screen.as_raw(),
+            ConsoleTarget::Window { screen, .. } => *screen,
+        }
+    }
+
+    // Build a new console, keeping track of it until it's dropped.
+    fn register(context: Box<UnsafeCell<PrintConsole>>, screen: ConsoleTarget<'screen>) -> Self {
+        lock_live_consoles().push(context.get() as usize);
+
+        Console { context, screen }
+    }
+}
+
+impl Swap for Console<'_> {
+    /// Swaps the video buffers. Note: The console's cursor position is not reset, only the framebuffer is changed.
+    ///
+    /// Even if double buffering is disabled, "swapping" the buffers has the side effect
+    /// of committing any configuration changes to the buffers (e.g. [`TopScreen::set_wide_mode()`],
+    /// [`Screen::set_framebuffer_format()`], [`Swap::set_double_buffering()`]), so it should still be used.
+    ///
+    /// This should be called once per frame at most.
+    fn swap_buffers(&mut self) {
+        if let ConsoleTarget::Screen(screen) = &mut self.screen {
+            screen.swap_buffers();
+
+            unsafe { (*self.context.get()).frameBuffer = screen.raw_framebuffer().ptr as *mut u16 };
+        }
+    }
+
+    fn set_double_buffering(&mut self, enabled: bool) {
+        if let ConsoleTarget::Screen(screen) = &mut self.screen {
+            screen.set_double_buffering(enabled);
+        }
+    }
+}
+
+impl Flush for Console<'_> {
+    fn flush_buffers(&mut self) {
+        if let ConsoleTarget::Screen(screen) = &mut self.screen {
+            screen.flush_buffers();
+        }
+    }
+}
+
+/// Writes go straight to this console, even if it isn't selected or the standard output was redirected
+/// (e.g. by [`Soc::redirect_to_3dslink()`](crate::services::soc::Soc::redirect_to_3dslink)).
+///
+/// # Notes
+///
+/// Since the bytes are written without buffering, text written with [`write!`], [`writeln!`] or [`Console::with_color()`]
+/// is shown right away, and text formatted in a single call isn't mixed with text printed from other threads.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// # use ctru::services::gfx::Gfx;
+/// # let gfx = Gfx::new()?;
+/// #
+/// use std::io::Write;
+///
+/// use ctru::console::Console;
+///
+/// let mut top_console = Console::new(gfx.top_screen.borrow_mut());
+/// let bottom_console = Console::new(gfx.bottom_screen.borrow_mut());
+///
+/// // The bottom console is selected, but this is still printed on the top screen.
+/// writeln!(top_console, "I'm on the top screen!")?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+impl std::io::Write for &Console<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.write_raw(buf)
+    }
+
+    fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> std::io::Result<()> {
+        // Formatting first makes the whole text be written at once, so that it can't be torn apart.
+        self.write_all(std::fmt::format(args).as_bytes())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl std::io::Write for Console<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        (&*self).write(buf)
+    }
+
+    fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> std::io::Result<()> {
+        (&*self).write_fmt(args)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        (&*self).flush()
+    }
+}
+
+impl Drop for Console<'_> {
+    fn drop(&mut self) {
+        lock_live_consoles().retain(|&console| console != self.context.get() as usize);
+        lock_scrollbacks().retain(|(console, _)| *console != self.context.get() as usize);
+
+        unsafe {
+            // Safety: We are about to deallocate the PrintConsole data pointed
+            // to by libctru. Without this drop code libctru would have a
+            // dangling pointer that it writes to on every print. To prevent
+            // this we replace the console with an empty one if it was selected.
+            // This is the same state that libctru starts up in, before
+            // initializing a console. Writes to the console will not show up on
+            // the screen, but it won't crash either.
+
+            // Get the current console by replacing it with an empty one.
+            let current_console = ctru_sys::consoleSelect(std::ptr::addr_of_mut!(EMPTY_CONSOLE));
+
+            if std::ptr::eq(current_console, self.context.get()) {
+                // Console dropped while selected. We just replaced it with the
+                // empty console so nothing more to do.
+            } else {
+                // Console dropped while a different console was selected. Put back
+                // the console that was selected.
+                ctru_sys::consoleSelect(current_console);
+            }
+        }
+    }
+}
+
+/// Guard returned by [`Console::select_scoped()`], which selects the previous console again when dropped.
+#[must_use = "dropping the guard immediately selects the previous console again"]
+pub struct ConsoleSelection<'console> {
+    previous: *mut PrintConsole,
+    _console: PhantomData<&'console Console<'console>>,
+}
+
+impl Drop for ConsoleSelection<'_> {
+    fn drop(&mut self) {
+        flush_stdout();
+
+        // The previous console may have been dropped in the meantime, in which case no console gets selected.
+        let previous = if lock_live_consoles().contains(&(self.previous as usize)) {
+            self.previous
+        } else {
+            std::ptr::addr_of_mut!(EMPTY_CONSOLE)
+        };
+
+        unsafe { consoleSelect(previous) };
+    }
+}
+
+// Initialize a `PrintConsole` for the screen, keeping the debug output which was chosen.
+unsafe fn init_context(screen: ctru_sys::gfxScreen_t, context: *mut PrintConsole) {
+    unsafe { consoleInit(screen, context) };
+    gfx::sync_console_init(screen);
+
+    // `consoleInit` sends the debug output to the console, undoing any previous choice.
+    if let Some(output) = debug_output() {
+        unsafe { ctru_sys::consoleDebugInit(output as _) };
+    }
+}
+
+/// Choose where the debug output (`stderr`) is sent, such as the output of `eprintln!` and the messages of the default panic hook.
+///
+/// The choice is kept for the rest of the program, even when a new [`Console`] is initialized.
+/// [`set_panic_hook()`](crate::set_panic_hook) also sends its message to the debugger when [`DebugOutput::Svc`] is chosen.
+///
+/// # Notes
+///
+/// This replaces the device used for `stderr` in devkitARM's devoptab table, which is what the standard library writes to.
+/// As such, the last call between this function and [`Soc::redirect_to_3dslink()`](crate::services::soc::Soc::redirect_to_3dslink)
+/// decides where `stderr` goes.
+///
+/// This function can be called before any [`Console`] exists: the output sent to [`DebugOutput::Console3ds`]
+/// is simply discarded until a [`Console`] is selected.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::console::{self, DebugOutput};
+///
+/// // Send the debug output to GDB (or the emulator's log), instead of the screen.
+/// console::set_debug_output(DebugOutput::Svc);
+///
+/// eprintln!("Only visible in the debugger");
+/// ```
+#[doc(alias = "consoleDebugInit")]
+pub fn set_debug_output(output: DebugOutput) {
+    let mut debug_output = DEBUG_OUTPUT.lock().unwrap_or_else(PoisonError::into_inner);
+
+    unsafe { ctru_sys::consoleDebugInit(output as _) };
+
+    *debug_output = Some(output);
+}
+
+/// Send both the standard output (`stdout`) and the debug output (`stderr`) to `console`.
+///
+/// This selects `console` like [`Console::select()`], and sends `stderr` to the selected console
+/// like [`set_debug_output(DebugOutput::Console3ds)`](set_debug_output), so that code printing with both
+/// `println!` and `eprintln!` can be moved between consoles at once.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// # use ctru::services::gfx::Gfx;
+/// # let gfx = Gfx::new()?;
+/// #
+/// use ctru::console::{self, Console};
+///
+/// let top_console = Console::new(gfx.top_screen.borrow_mut());
+/// let bottom_console = Console::new(gfx.bottom_screen.borrow_mut());
+///
+/// console::redirect_stdout(&top_console);
+///
+/// println!("Everything is on the top screen...");
+/// eprintln!("...even the errors.");
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub fn redirect_stdout(console: &Console) {
+    let _ = std::io::stderr().flush();
+
+    console.select();
+    set_debug_output(DebugOutput::Console3ds);
+}
+
+// Flush the text printed to `stdout`, so that it reaches the selected console before switching to another one.
+fn flush_stdout() {
+    // Errors are ignored, since the text would have nowhere else to go.
+    let _ = std::io::stdout().flush();
+}
+
+// Returns the debug output chosen with `set_debug_output()`, if any.
+pub(crate) fn debug_output() -> Option<DebugOutput> {
+    *DEBUG_OUTPUT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Make every glyph one pixel wider, by drawing it again one pixel to the right.
+fn bold_glyphs(glyphs: &[u8]) -> Vec<u8> {
+    glyphs.iter().map(|row| row | (row >> 1)).collect()
+}
+
+// Returns the first item shown by a menu with `rows` rows, after moving from the view starting at `first_item` to the `selected` item.
+fn menu_first_item(first_item: usize, selected: usize, rows: usize) -> usize {
+    if selected < first_item {
+        selected
+    } else if selected >= first_item + rows {
+        selected + 1 - rows
+    } else {
+        first_item
+    }
+}
+
+// Build the ANSI escape sequence setting the given graphic rendition parameters.
+fn style_sequence(codes: &[u8]) -> String {
+    let codes: Vec<String> = codes.iter().map(u8::to_string).collect();
+
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn lock_live_consoles() -> std::sync::MutexGuard<'static, Vec<usize>> {
+    LIVE_CONSOLES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl std::fmt::Display for Axis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::X => write!(f, "x"),
+            Self::Y => write!(f, "y"),
+        }
+    }
+}
+
+impl std::fmt::Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Width => write!(f, "width"),
             Self::Height => write!(f, "height"),
         }
     }
@@ -412,3 +1676,73 @@ impl std::fmt::Display for Error {
 }
 
 impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn style_sequences() {
+        assert_eq!(style_sequence(&[0]), "\x1b[0m");
+        assert_eq!(style_sequence(&[30 + Color::Red as u8]), "\x1b[31m");
+        assert_eq!(style_sequence(&[40 + Color::White as u8]), "\x1b[47m");
+        assert_eq!(style_sequence(&[1, 36]), "\x1b[1;36m");
+    }
+
+    #[test]
+    fn font_validation() {
+        static GLYPHS: [u8; 16] = [0x18; 16];
+
+        assert!(ConsoleFont::new(&GLYPHS, b'0', 2).is_ok());
+        assert!(matches!(
+            ConsoleFont::new(&GLYPHS, b'0', 3),
+            Err(crate::Error::BufferTooShort {
+                provided: 16,
+                wanted: 24
+            })
+        ));
+        assert!(ConsoleFont::new(&GLYPHS, 255, 2).is_err());
+
+        assert_eq!(bold_glyphs(&[0x18, 0x81]), [0x1C, 0xC1]);
+    }
+
+    #[test]
+    fn menu_scrolling() {
+        // The view doesn't move while the selected item is in it.
+        assert_eq!(menu_first_item(0, 4, 5), 0);
+        assert_eq!(menu_first_item(3, 3, 5), 3);
+
+        // The view follows the selected item, including when wrapping around.
+        assert_eq!(menu_first_item(0, 5, 5), 1);
+        assert_eq!(menu_first_item(4, 3, 5), 3);
+        assert_eq!(menu_first_item(0, 9, 5), 5);
+        assert_eq!(menu_first_item(5, 0, 5), 0);
+    }
+
+    #[cfg(feature = "mock-input")]
+    #[test]
+    fn menu_scripted_input() {
+        use crate::services::gfx::Gfx;
+        use crate::services::hid::MockInput;
+
+        let gfx = Gfx::new().unwrap();
+        let console = Console::new(gfx.top_screen.borrow_mut());
+
+        let none = KeyPad::empty();
+        let input = MockInput::from_keys_held([
+            KeyPad::DPAD_DOWN,
+            none,
+            KeyPad::DPAD_DOWN,
+            none,
+            KeyPad::DPAD_UP,
+            none,
+            KeyPad::A,
+        ]);
+
+        assert_eq!(console.menu(input, &["Easy", "Normal", "Hard"]), Some(1));
+
+        // Running out of input cancels the menu.
+        let input = MockInput::from_keys_held([KeyPad::DPAD_DOWN]);
+        assert_eq!(console.menu(input, &["Easy", "Normal"]), None);
+    }
+}
@@ -0,0 +1,230 @@
+//! Scrollback buffers of the consoles.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use ctru_sys::PrintConsole;
+
+// Scrollback buffers of the consoles which enabled it, along with the address of their `PrintConsole`.
+static SCROLLBACKS: Mutex<Vec<(usize, Scrollback)>> = Mutex::new(Vec::new());
+
+// Last lines printed to a console, as they are shown on screen.
+#[derive(Debug)]
+pub(super) struct Scrollback {
+    // Always contains at least the line being printed.
+    lines: VecDeque<Vec<u8>>,
+    capacity: usize,
+    column: usize,
+    // Number of lines between the bottom of the view and the line being printed.
+    offset: usize,
+}
+
+impl Scrollback {
+    pub(super) fn new(capacity: usize) -> Self {
+        let mut lines = VecDeque::with_capacity(capacity);
+        lines.push_back(Vec::new());
+
+        Self {
+            lines,
+            capacity: capacity.max(1),
+            column: 0,
+            offset: 0,
+        }
+    }
+
+    // Keep a character printed on a window `width` characters wide, following what `libctru` does with it.
+    pub(super) fn push(&mut self, c: u8, width: usize, tab_size: usize) {
+        match c {
+            b'\n' => self.new_line(),
+            b'\r' => self.column = 0,
+            b'\x08' => self.column = self.column.saturating_sub(1),
+            b'\t' => {
+                let tab_size = tab_size.max(1);
+                let next_stop = (self.column / tab_size + 1) * tab_size;
+
+                while self.column < next_stop.min(width) {
+                    self.put(b' ', width);
+                }
+            }
+            _ => self.put(c, width),
+        }
+    }
+
+    fn put(&mut self, c: u8, width: usize) {
+        // Like on screen, lines longer than the window are wrapped.
+        if self.column >= width {
+            self.new_line();
+        }
+
+        let line = self.lines.back_mut().unwrap();
+
+        if self.column < line.len() {
+            line[self.column] = c;
+        } else {
+            line.resize(self.column, b' ');
+            line.push(c);
+        }
+
+        self.column += 1;
+    }
+
+    fn new_line(&mut self) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+
+        // Keep showing the same lines while scrolled up, as long as they are still in the buffer.
+        if self.offset > 0 {
+            self.offset = (self.offset + 1).min(self.lines.len());
+        }
+
+        self.lines.push_back(Vec::new());
+        self.column = 0;
+    }
+
+    pub(super) fn is_scrolled(&self) -> bool {
+        self.offset > 0
+    }
+
+    // Move the view by `lines` lines (upwards if positive), without going past the first line or the line being printed.
+    // Returns whether the view moved.
+    pub(super) fn scroll(&mut self, lines: isize, height: usize) -> bool {
+        let max_offset = self.lines.len().saturating_sub(height);
+        // The offset may be past the first line if the lines in view have been dropped, see `visible_lines()`.
+        let previous_offset = self.offset.min(max_offset);
+
+        self.offset = previous_offset.saturating_add_signed(lines).min(max_offset);
+
+        self.offset != previous_offset
+    }
+
+    // Returns the lines in view, along with the position of the cursor if the line being printed is visible.
+    pub(super) fn visible_lines(&self, height: usize) -> (Vec<Vec<u8>>, Option<(usize, usize)>) {
+        // Once the lines at the top of the view have been dropped, the view starts from the first line in the buffer.
+        let end = (self.lines.len() - self.offset).max(height.min(self.lines.len()));
+        let start = end.saturating_sub(height);
+
+        let lines: Vec<Vec<u8>> = self.lines.range(start..end).cloned().collect();
+        let cursor = (self.offset == 0).then(|| (self.column, lines.len() - 1));
+
+        (lines, cursor)
+    }
+}
+
+pub(super) fn lock_scrollbacks() -> MutexGuard<'static, Vec<(usize, Scrollback)>> {
+    SCROLLBACKS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Called by `libctru` for every character printed to a console with a scrollback buffer.
+pub(super) unsafe extern "C" fn scrollback_print_char(
+    console: *mut libc::c_void,
+    c: libc::c_int,
+) -> bool {
+    let context = unsafe { &*(console as *const PrintConsole) };
+    let mut scrollbacks = lock_scrollbacks();
+
+    let Some((_, scrollback)) = scrollbacks
+        .iter_mut()
+        .find(|(address, _)| *address == console as usize)
+    else {
+        return false;
+    };
+
+    scrollback.push(
+        c as u8,
+        context.windowWidth as usize,
+        context.tabSize as usize,
+    );
+
+    // While scrolled up, the text is only kept in the buffer, so that the view doesn't move.
+    scrollback.is_scrolled()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_str(scrollback: &mut Scrollback, text: &str) {
+        for c in text.bytes() {
+            scrollback.push(c, 10, 4);
+        }
+    }
+
+    #[test]
+    fn line_wrapping() {
+        let mut scrollback = Scrollback::new(10);
+        push_str(&mut scrollback, "0123456789abc\nx\ty\rz");
+
+        let (lines, cursor) = scrollback.visible_lines(5);
+
+        assert_eq!(lines, [&b"0123456789"[..], b"abc", b"z   y"]);
+        assert_eq!(cursor, Some((1, 2)));
+    }
+
+    #[test]
+    fn bounded_lines() {
+        let mut scrollback = Scrollback::new(3);
+        push_str(&mut scrollback, "a\nb\nc\nd\ne");
+
+        let (lines, _) = scrollback.visible_lines(10);
+
+        assert_eq!(lines, [b"c", b"d", b"e"]);
+    }
+
+    #[test]
+    fn scrolling_at_capacity() {
+        let mut scrollback = Scrollback::new(4);
+        push_str(&mut scrollback, "a\nb\nc\nd");
+
+        assert!(scrollback.scroll(1, 2));
+        assert_eq!(scrollback.visible_lines(2).0, [b"b", b"c"]);
+
+        // Dropping the oldest line doesn't move the view.
+        push_str(&mut scrollback, "\ne");
+        assert_eq!(scrollback.visible_lines(2).0, [b"b", b"c"]);
+
+        // Once the lines in view are dropped, it sticks to the first line in the buffer.
+        push_str(&mut scrollback, "\nf\ng");
+        assert_eq!(scrollback.visible_lines(2).0, [b"d", b"e"]);
+
+        push_str(&mut scrollback, "\nh\ni\nj");
+        assert_eq!(scrollback.visible_lines(2).0, [b"g", b"h"]);
+
+        // Scrolling down starts from the view being shown.
+        assert!(scrollback.scroll(-1, 2));
+        assert_eq!(scrollback.visible_lines(2).0, [b"h", b"i"]);
+
+        assert!(scrollback.scroll(-1, 2));
+        assert_eq!(
+            scrollback.visible_lines(2),
+            (vec![b"i".to_vec(), b"j".to_vec()], Some((1, 1)))
+        );
+    }
+
+    #[test]
+    fn scrolling() {
+        let mut scrollback = Scrollback::new(10);
+        push_str(&mut scrollback, "a\nb\nc\nd");
+
+        assert!(scrollback.scroll(1, 2));
+        assert_eq!(
+            scrollback.visible_lines(2),
+            (vec![b"b".to_vec(), b"c".to_vec()], None)
+        );
+
+        // The view can't go past the first line.
+        scrollback.scroll(5, 2);
+        assert_eq!(scrollback.visible_lines(2).0, [b"a", b"b"]);
+
+        // New lines don't move the view while scrolled up.
+        push_str(&mut scrollback, "\ne");
+        assert_eq!(scrollback.visible_lines(2).0, [b"a", b"b"]);
+
+        assert!(scrollback.scroll(-10, 2));
+        assert!(!scrollback.scroll(-1, 2));
+        assert_eq!(
+            scrollback.visible_lines(2),
+            (vec![b"d".to_vec(), b"e".to_vec()], Some((1, 1)))
+        );
+    }
+}
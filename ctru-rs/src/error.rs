@@ -7,9 +7,15 @@ use std::error;
 use std::ffi::CStr;
 use std::fmt;
 use std::ops::{ControlFlow, FromResidual, Try};
+#[cfg(feature = "debug-errors")]
+use std::panic::Location;
 
 use ctru_sys::result::{R_DESCRIPTION, R_LEVEL, R_MODULE, R_SUMMARY};
 
+pub mod codes;
+
+use codes::KnownCode;
+
 /// Custom type alias for generic [`ctru-rs`](crate) operations.
 ///
 /// This type is compatible with [`ctru_sys::Result`] codes.
@@ -34,10 +40,202 @@ pub type Result<T> = ::std::result::Result<T, Error>;
 ///     Ok(())
 /// }
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord)]
 #[repr(transparent)]
 pub struct ResultCode(pub ctru_sys::Result);
 
+impl ResultCode {
+    /// Wrap a raw [`ctru_sys::Result`] code.
+    pub const fn from_raw(code: ctru_sys::Result) -> Self {
+        Self(code)
+    }
+
+    /// Returns the raw [`ctru_sys::Result`] code.
+    pub const fn into_raw(self) -> ctru_sys::Result {
+        self.0
+    }
+
+    /// Returns `true` if the result code doesn't report a failure.
+    ///
+    /// # Notes
+    ///
+    /// Like `R_SUCCEEDED`, this only checks the sign of the code. Some non-failing codes (such as wait timeouts)
+    /// are still converted to an [`Error`] by [`ResultCode::ok()`] and the `?` operator.
+    #[doc(alias = "R_SUCCEEDED")]
+    pub const fn is_success(self) -> bool {
+        self.0 >= 0
+    }
+
+    /// Returns `true` if the result code reports a failure.
+    #[doc(alias = "R_FAILED")]
+    pub const fn is_failure(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Returns `true` if the result code has the same module and description as `code`, regardless of its level and summary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ctru::error::{codes, ResultCode};
+    ///
+    /// // The FS "not found" failure, with two different levels.
+    /// assert!(ResultCode(0xC8804464_u32 as i32).matches(codes::FS_NOT_FOUND));
+    /// assert!(ResultCode(0xD8804464_u32 as i32).matches(codes::FS_NOT_FOUND));
+    ///
+    /// assert!(!ResultCode(0xC8804464_u32 as i32).matches(codes::FS_ALREADY_EXISTS));
+    /// ```
+    pub fn matches(&self, code: KnownCode) -> bool {
+        KnownCode::from(*self) == code
+    }
+
+    /// Convert the result code into a [`Result`], following the same rules as the `?` operator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ctru::error::ResultCode;
+    ///
+    /// assert!(ResultCode::from_raw(0).ok().is_ok());
+    /// assert!(ResultCode::from_raw(0xC8804464_u32 as i32).ok().is_err());
+    /// ```
+    #[track_caller]
+    pub fn ok(self) -> Result<()> {
+        self.check(None)
+    }
+
+    /// Tag the result code with the name of the function which returned it.
+    ///
+    /// With the `debug-errors` feature, the name is recorded in the [`Error`] returned by the `?` operator,
+    /// along with the location of the operator itself. Otherwise, the name is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ctru::error::{Result, ResultCode};
+    ///
+    /// pub fn main() -> Result<()> {
+    /// #   let _runner = test_runner::GdbRunner::default();
+    ///     ResultCode(unsafe { ctru_sys::hidInit() }).named("hidInit")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn named(self, function: &'static str) -> NamedResultCode {
+        #[cfg(not(feature = "debug-errors"))]
+        let _ = function;
+
+        NamedResultCode {
+            code: self,
+            #[cfg(feature = "debug-errors")]
+            function,
+        }
+    }
+
+    #[track_caller]
+    fn check(self, function: Option<&'static str>) -> Result<()> {
+        // Wait timeouts aren't counted as "failures" in libctru, but an unfinished task means unsafety for us.
+        // Luckily all summary cases are for system failures (except RS_SUCCESS).
+        // I don't know if there are any cases in libctru where a Result holds a "failing" summary but a "success" code, so we'll just check for both.
+        if self.is_failure() || self.summary() != ctru_sys::RS_SUCCESS {
+            #[cfg(feature = "debug-errors")]
+            return Err(Error::OsCall {
+                code: self,
+                function,
+                location: Location::caller(),
+            });
+
+            #[cfg(not(feature = "debug-errors"))]
+            {
+                let _ = function;
+                return Err(self.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the level of the result code, which describes how severe the failure is (e.g. [`ctru_sys::RL_PERMANENT`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ctru::error::ResultCode;
+    ///
+    /// let code = ResultCode(0xC8804464_u32 as i32);
+    ///
+    /// assert_eq!(code.level(), ctru_sys::RL_STATUS);
+    /// assert_eq!(code.summary(), ctru_sys::RS_NOTFOUND);
+    /// assert_eq!(code.module(), ctru_sys::RM_FS);
+    /// assert_eq!(code.description(), 0x64);
+    /// ```
+    #[doc(alias = "R_LEVEL")]
+    pub fn level(self) -> u8 {
+        R_LEVEL(self.0)
+    }
+
+    /// Returns the summary of the result code, which describes the category of the failure (e.g. [`ctru_sys::RS_NOTFOUND`]).
+    #[doc(alias = "R_SUMMARY")]
+    pub fn summary(self) -> u8 {
+        R_SUMMARY(self.0)
+    }
+
+    /// Returns the module of the result code, which identifies the system component reporting it (e.g. [`ctru_sys::RM_FS`]).
+    #[doc(alias = "R_MODULE")]
+    pub fn module(self) -> u8 {
+        R_MODULE(self.0)
+    }
+
+    /// Returns the description of the result code, which identifies the exact failure within its module.
+    ///
+    /// # Notes
+    ///
+    /// Descriptions lower than [`ctru_sys::RD_INVALID_SELECTION`] are specific to the module, while the others are shared between all modules.
+    #[doc(alias = "R_DESCRIPTION")]
+    pub fn description(self) -> u16 {
+        R_DESCRIPTION(self.0)
+    }
+}
+
+impl fmt::Debug for ResultCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ResultCode")
+            .field("raw", &format_args!("{:#010X}", self.0))
+            .field("level", &result_code_level_str(self.0))
+            .field("module", &result_code_module_str(self.0))
+            .field("summary", &result_code_summary_str(self.0))
+            .field("description", &result_code_description_str(self.0))
+            .finish()
+    }
+}
+
+/// Displays the result code along with its decoded components.
+///
+/// # Example
+///
+/// ```
+/// use ctru::error::ResultCode;
+///
+/// let code = ResultCode(0xC8804464_u32 as i32);
+///
+/// assert_eq!(
+///     code.to_string(),
+///     "Result 0xC8804464 (Level=status, Summary=not_found, Module=fs, Desc=0x064)"
+/// );
+/// ```
+impl fmt::Display for ResultCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Result 0x{:08X} (Level={}, Summary={}, Module={}, Desc=0x{:03X})",
+            self.0,
+            result_code_level_str(self.0),
+            result_code_summary_str(self.0),
+            result_code_module_str(self.0),
+            self.description(),
+        )
+    }
+}
+
 impl Try for ResultCode {
     type Output = ();
     type Residual = Error;
@@ -46,27 +244,62 @@ impl Try for ResultCode {
         Self(0)
     }
 
+    #[track_caller]
     fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
-        // Wait timeouts aren't counted as "failures" in libctru, but an unfinished task means unsafety for us.
-        // Luckily all summary cases are for system failures (except RS_SUCCESS).
-        // I don't know if there are any cases in libctru where a Result holds a "failing" summary but a "success" code, so we'll just check for both.
-        if ctru_sys::R_FAILED(self.0) || ctru_sys::R_SUMMARY(self.0) != ctru_sys::RS_SUCCESS {
-            ControlFlow::Break(self.into())
-        } else {
-            ControlFlow::Continue(())
+        match self.ok() {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(e) => ControlFlow::Break(e),
         }
     }
 }
 
 impl FromResidual for ResultCode {
     fn from_residual(e: <Self as Try>::Residual) -> Self {
-        match e {
-            Error::Os(result) => Self(result),
-            _ => unreachable!(),
+        match e.result_code() {
+            Some(code) => code,
+            None => unreachable!(),
         }
     }
 }
 
+/// [`ResultCode`] tagged with the name of the function which returned it, see [`ResultCode::named()`].
+///
+/// This struct supports the "try" syntax (`?`) just like [`ResultCode`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NamedResultCode {
+    code: ResultCode,
+    #[cfg(feature = "debug-errors")]
+    function: &'static str,
+}
+
+impl Try for NamedResultCode {
+    type Output = ();
+    type Residual = Error;
+
+    fn from_output(_: Self::Output) -> Self {
+        ResultCode(0).named("")
+    }
+
+    #[track_caller]
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+        #[cfg(feature = "debug-errors")]
+        let result = self.code.check(Some(self.function));
+        #[cfg(not(feature = "debug-errors"))]
+        let result = self.code.check(None);
+
+        match result {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(e) => ControlFlow::Break(e),
+        }
+    }
+}
+
+impl FromResidual for NamedResultCode {
+    fn from_residual(e: <Self as Try>::Residual) -> Self {
+        ResultCode::from_residual(e).named("")
+    }
+}
+
 impl<T> FromResidual<Error> for Result<T> {
     fn from_residual(e: Error) -> Self {
         Err(e)
@@ -78,14 +311,29 @@ impl<T> FromResidual<Error> for Result<T> {
 /// This error enum supports parsing and displaying [`ctru_sys::Result`] codes.
 #[non_exhaustive]
 pub enum Error {
-    /// Raw [`ctru_sys::Result`] codes.
-    Os(ctru_sys::Result),
+    /// Result codes returned by the operating system.
+    Os(ResultCode),
+    /// Raw [`ctru_sys::Result`] codes, along with where they were returned from.
+    ///
+    /// Only available with the `debug-errors` feature, which replaces [`Error::Os`] with this variant for failures
+    /// converted with the `?` operator or [`ResultCode::ok()`].
+    #[cfg(feature = "debug-errors")]
+    OsCall {
+        /// Result code returned by the operating system.
+        code: ResultCode,
+        /// Name of the function which returned the code, if it was given with [`ResultCode::named()`].
+        function: Option<&'static str>,
+        /// Location of the code which checked the result.
+        location: &'static Location<'static>,
+    },
     /// Generic [`libc`] errors.
     Libc(String),
     /// Requested service is already active and cannot be activated again.
     ServiceAlreadyActive,
     /// `stdout` is already being redirected.
     OutputAlreadyRedirected,
+    /// The DSP firmware needed to initialize the [`Ndsp`](crate::services::ndsp::Ndsp) service could not be found.
+    MissingDspFirmware,
     /// The buffer provided by the user to store some data is shorter than required.
     BufferTooShort {
         /// Length of the buffer provided by the user.
@@ -93,6 +341,10 @@ pub enum Error {
         /// Size of the requested data (in bytes).
         wanted: usize,
     },
+    /// Errors returned by the [`Gfx`](crate::services::gfx::Gfx) service and its screens.
+    Gfx(crate::services::gfx::Error),
+    /// Errors returned by the [`Ndsp`](crate::services::ndsp::Ndsp) service and its channels.
+    Ndsp(crate::services::ndsp::Error),
     /// An error that doesn't fit into the other categories.
     Other(String),
 }
@@ -115,31 +367,124 @@ impl Error {
         Self::Libc(error_str.to_string_lossy().into())
     }
 
+    /// Returns the [`ResultCode`] of the error, if it was reported by the operating system.
+    ///
+    /// This gives access to the decoded components of the code, to handle specific failures.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ctru::error::{Error, ResultCode};
+    ///
+    /// let error = Error::from(0xC8804464_u32 as i32);
+    ///
+    /// match error.result_code() {
+    ///     Some(code) if code.module() == ctru_sys::RM_FS => println!("file system error: {code}"),
+    ///     _ => println!("other error: {error}"),
+    /// }
+    ///
+    /// assert_eq!(Error::ServiceAlreadyActive.result_code(), None);
+    /// ```
+    pub fn result_code(&self) -> Option<ResultCode> {
+        match *self {
+            Self::Os(code) => Some(code),
+            #[cfg(feature = "debug-errors")]
+            Self::OsCall { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Returns the common service failure described by the error, if any.
+    ///
+    /// Look at [`ServiceError`] for more information.
+    pub fn service_error(&self) -> Option<ServiceError> {
+        match self {
+            Self::MissingDspFirmware => Some(ServiceError::NotFound),
+            Self::Ndsp(err) => err.service_error(),
+            _ => self.result_code().map(ServiceError::from),
+        }
+    }
+
+    /// Build an error applet [`PopUp`](crate::applets::error::PopUp) to show the error to the user.
+    ///
+    /// Errors reported by the operating system are shown as system error codes, while the others are shown as text.
+    ///
+    /// # Notes
+    ///
+    /// The applet needs the [`Apt`](crate::services::apt::Apt) and [`Gfx`](crate::services::gfx::Gfx) services to be launched,
+    /// and reports any failure to do so with its own [`Result`](std::result::Result).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::{apt::Apt, gfx::Gfx, ndsp::Ndsp};
+    ///
+    /// let gfx = Gfx::new()?;
+    /// let apt = Apt::new()?;
+    ///
+    /// if let Err(e) = Ndsp::new() {
+    ///     // Fall back to printing the error if the applet can't be shown.
+    ///     if e.into_applet().launch(&apt, &gfx).is_err() {
+    ///         eprintln!("couldn't initialize NDSP");
+    ///     }
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_applet(self) -> crate::applets::error::PopUp {
+        use crate::applets::error::{PopUp, WordWrap};
+
+        match self.result_code() {
+            Some(code) => PopUp::with_code(code),
+            None => {
+                let mut popup = PopUp::new(WordWrap::Enabled);
+                popup.set_text(&self.to_string());
+                popup
+            }
+        }
+    }
+
     /// Check if the error is a timeout.
     pub fn is_timeout(&self) -> bool {
-        match *self {
-            Error::Os(code) => R_DESCRIPTION(code) == ctru_sys::RD_TIMEOUT,
-            _ => false,
+        match self.result_code() {
+            Some(code) => code.description() == ctru_sys::RD_TIMEOUT,
+            None => false,
         }
     }
 }
 
 impl From<ctru_sys::Result> for Error {
     fn from(err: ctru_sys::Result) -> Self {
-        Error::Os(err)
+        Error::Os(ResultCode(err))
     }
 }
 
 impl From<ResultCode> for Error {
     fn from(err: ResultCode) -> Self {
-        Self::Os(err.0)
+        Self::Os(err)
+    }
+}
+
+impl From<crate::services::gfx::Error> for Error {
+    fn from(err: crate::services::gfx::Error) -> Self {
+        Self::Gfx(err)
+    }
+}
+
+impl From<crate::services::ndsp::Error> for Error {
+    fn from(err: crate::services::ndsp::Error) -> Self {
+        Self::Ndsp(err)
     }
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &Self::Os(err) => f
+            &Self::Os(ResultCode(err)) => f
                 .debug_struct("Error")
                 .field("raw", &format_args!("{err:#08X}"))
                 .field("level", &result_code_level_str(err))
@@ -147,14 +492,32 @@ impl fmt::Debug for Error {
                 .field("summary", &result_code_summary_str(err))
                 .field("description", &result_code_description_str(err))
                 .finish(),
+            #[cfg(feature = "debug-errors")]
+            &Self::OsCall {
+                code: ResultCode(code),
+                function,
+                location,
+            } => f
+                .debug_struct("Error")
+                .field("raw", &format_args!("{code:#08X}"))
+                .field("function", &function)
+                .field("location", &location)
+                .field("level", &result_code_level_str(code))
+                .field("module", &result_code_module_str(code))
+                .field("summary", &result_code_summary_str(code))
+                .field("description", &result_code_description_str(code))
+                .finish(),
             Self::Libc(err) => f.debug_tuple("Libc").field(err).finish(),
             Self::ServiceAlreadyActive => f.debug_tuple("ServiceAlreadyActive").finish(),
             Self::OutputAlreadyRedirected => f.debug_tuple("OutputAlreadyRedirected").finish(),
+            Self::MissingDspFirmware => f.debug_tuple("MissingDspFirmware").finish(),
             Self::BufferTooShort { provided, wanted } => f
                 .debug_struct("BufferTooShort")
                 .field("provided", provided)
                 .field("wanted", wanted)
                 .finish(),
+            Self::Gfx(err) => f.debug_tuple("Gfx").field(err).finish(),
+            Self::Ndsp(err) => f.debug_tuple("Ndsp").field(err).finish(),
             Self::Other(err) => f.debug_tuple("Other").field(err).finish(),
         }
     }
@@ -165,7 +528,7 @@ impl fmt::Display for Error {
         match self {
             // TODO: should we consider using ctru_sys::osStrError here as well?
             // It might do some of the work for us or provide additional details
-            &Self::Os(err) => write!(
+            &Self::Os(ResultCode(err)) => write!(
                 f,
                 "libctru result code 0x{err:08X}: [{} {}] {}: {}",
                 result_code_level_str(err),
@@ -173,18 +536,136 @@ impl fmt::Display for Error {
                 result_code_summary_str(err),
                 result_code_description_str(err)
             ),
+            #[cfg(feature = "debug-errors")]
+            &Self::OsCall {
+                code,
+                function,
+                location,
+            } => {
+                if let Some(function) = function {
+                    write!(f, "{function} ")?;
+                }
+
+                write!(f, "({}:{}): ", location.file(), location.line())?;
+                fmt::Display::fmt(&Self::Os(code), f)
+            }
             Self::Libc(err) => write!(f, "{err}"),
             Self::ServiceAlreadyActive => write!(f, "service already active"),
             Self::OutputAlreadyRedirected => {
                 write!(f, "output streams are already redirected to 3dslink")
             }
+            Self::MissingDspFirmware => write!(
+                f,
+                "DSP firmware not found. It needs to be dumped to \"sdmc:/3ds/dspfirm.cdc\""
+            ),
             Self::BufferTooShort{provided, wanted} => write!(f, "the provided buffer's length is too short (length = {provided}) to hold the wanted data (size = {wanted})"),
+            Self::Gfx(err) => write!(f, "{err}"),
+            Self::Ndsp(err) => write!(f, "{err}"),
             Self::Other(err) => write!(f, "{err}"),
         }
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Os(code) => Some(code),
+            #[cfg(feature = "debug-errors")]
+            Self::OsCall { code, .. } => Some(code),
+            Self::Gfx(err) => error::Error::source(err),
+            Self::Ndsp(err) => error::Error::source(err),
+            _ => None,
+        }
+    }
+}
+
+impl error::Error for ResultCode {}
+
+/// Common failures reported by the system services, decoded from their [`ResultCode`]s.
+///
+/// This is re-exported by the modules of the services which commonly report them, such as [`fs`](crate::services::fs) and [`ndsp`](crate::services::ndsp).
+/// The errors returned by their wrappers are translated with [`Error::service_error()`] (or [`ndsp::Error::service_error()`](crate::services::ndsp::Error::service_error)),
+/// while raw [`ResultCode`]s can be converted directly.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::services::ndsp::{Ndsp, ServiceError};
+///
+/// match Ndsp::new() {
+///     Ok(ndsp) => {
+///         let _channel_0 = ndsp.channel(0)?;
+///
+///         if let Err(e) = ndsp.channel(0) {
+///             assert_eq!(e.service_error(), Some(ServiceError::Busy));
+///         }
+///     }
+///     Err(e) => match e.service_error() {
+///         Some(ServiceError::NotFound) => println!("the DSP firmware is missing"),
+///         Some(ServiceError::Busy) => println!("the DSP is being used by someone else"),
+///         _ => println!("couldn't initialize NDSP: {e}"),
+///     },
+/// }
+/// # Ok::<(), ctru::services::ndsp::Error>(())
+/// ```
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ServiceError {
+    /// The requested resource (such as a file, an archive or the DSP firmware) could not be found.
+    NotFound,
+    /// The resource to create (such as a file or directory) already exists.
+    AlreadyExists,
+    /// The system ran out of memory (or of another limited resource, such as handles).
+    OutOfMemory,
+    /// The application isn't allowed to access the requested resource.
+    AccessDenied,
+    /// The requested resource is being used.
+    Busy,
+    /// Any other failure, holding the original result code.
+    Other(ResultCode),
+}
+
+impl ServiceError {
+    /// Decode the failure held by a generic [`Error`], if any.
+    ///
+    /// This is the same as [`Error::service_error()`].
+    pub fn from_error(error: &Error) -> Option<Self> {
+        error.service_error()
+    }
+}
+
+impl From<ResultCode> for ServiceError {
+    fn from(code: ResultCode) -> Self {
+        if code.matches(codes::FS_ALREADY_EXISTS) {
+            return Self::AlreadyExists;
+        }
+
+        match (code.summary(), code.description()) {
+            (ctru_sys::RS_NOTFOUND, _) | (_, ctru_sys::RD_NOT_FOUND) => Self::NotFound,
+            (_, ctru_sys::RD_ALREADY_EXISTS) => Self::AlreadyExists,
+            (ctru_sys::RS_OUTOFRESOURCE, _) | (_, ctru_sys::RD_OUT_OF_MEMORY) => Self::OutOfMemory,
+            (_, ctru_sys::RD_NOT_AUTHORIZED) => Self::AccessDenied,
+            (_, ctru_sys::RD_BUSY) => Self::Busy,
+            _ => Self::Other(code),
+        }
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "the requested resource could not be found"),
+            Self::AlreadyExists => write!(f, "the resource already exists"),
+            Self::OutOfMemory => write!(f, "the service ran out of resources"),
+            Self::AccessDenied => write!(f, "access to the resource was denied"),
+            Self::Busy => write!(f, "the resource is busy"),
+            Self::Other(code) => write!(f, "the service failed: {code}"),
+        }
+    }
+}
+
+impl error::Error for ServiceError {}
 
 fn result_code_level_str(result: ctru_sys::Result) -> Cow<'static, str> {
     use ctru_sys::{
@@ -393,3 +874,144 @@ fn result_code_module_str(result: ctru_sys::Result) -> Cow<'static, str> {
         code => return Cow::Owned(format!("(unknown module: {code:#x})")),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_round_trip() {
+        assert_eq!(ResultCode::from_raw(-1).into_raw(), -1);
+        assert_eq!(ResultCode::from_raw(0x1234).into_raw(), 0x1234);
+    }
+
+    #[test]
+    fn negative_codes_are_failures() {
+        let failure = ResultCode::from_raw(0xC8804464_u32 as i32);
+
+        assert!(failure.is_failure());
+        assert!(!failure.is_success());
+        assert!(failure.ok().is_err());
+
+        let success = ResultCode::from_raw(0);
+
+        assert!(success.is_success());
+        assert!(!success.is_failure());
+        assert!(success.ok().is_ok());
+    }
+
+    #[test]
+    fn non_negative_codes_with_failing_summary_are_errors() {
+        // Level "success" with summary "would_block": the sign bit is clear, but the `?` operator still rejects it.
+        let code = ResultCode::from_raw(ctru_sys::MAKERESULT(
+            ctru_sys::RL_SUCCESS as _,
+            ctru_sys::RS_WOULDBLOCK as _,
+            ctru_sys::RM_OS as _,
+            ctru_sys::RD_TIMEOUT as _,
+        ));
+
+        assert!(code.is_success());
+        assert_eq!(code.ok().unwrap_err().result_code(), Some(code));
+    }
+
+    #[test]
+    fn sources_and_context() {
+        use std::error::Error as _;
+
+        let code = ResultCode::from_raw(0xC8804464_u32 as i32);
+        let error = code.ok().unwrap_err();
+
+        let source = error
+            .source()
+            .expect("OS errors should expose their result code");
+        assert_eq!(source.to_string(), code.to_string());
+
+        let error = Error::from(crate::services::ndsp::Error::InvalidChannel(42));
+        assert!(error.to_string().contains("42"));
+        assert!(error.source().is_none());
+
+        // Service errors keep their type.
+        let error = Error::from(crate::services::gfx::Error::WideModeUnsupported);
+        assert!(matches!(
+            error,
+            Error::Gfx(crate::services::gfx::Error::WideModeUnsupported)
+        ));
+
+        assert!(Error::ServiceAlreadyActive.source().is_none());
+    }
+
+    #[test]
+    fn service_error_mapping() {
+        let dsp_code = |summary, description| {
+            ResultCode(ctru_sys::MAKERESULT(
+                ctru_sys::RL_PERMANENT as _,
+                summary as _,
+                ctru_sys::RM_DSP as _,
+                description as _,
+            ))
+        };
+
+        // "Not found", as returned when opening a missing file on the SD card.
+        assert_eq!(
+            ServiceError::from(ResultCode(0xC8804464_u32 as i32)),
+            ServiceError::NotFound
+        );
+        // "Already exists", as returned when creating a file twice.
+        assert_eq!(
+            ServiceError::from(ResultCode(0xC82044BE_u32 as i32)),
+            ServiceError::AlreadyExists
+        );
+        // The same failure with other level and summary bits.
+        assert_eq!(
+            ServiceError::from(ResultCode(ctru_sys::MAKERESULT(
+                ctru_sys::RL_PERMANENT as _,
+                ctru_sys::RS_INVALIDARG as _,
+                codes::FS_ALREADY_EXISTS.module() as _,
+                codes::FS_ALREADY_EXISTS.description() as _,
+            ))),
+            ServiceError::AlreadyExists
+        );
+
+        // Missing firmware, as reported by `ndspInit`.
+        assert_eq!(
+            ServiceError::from(dsp_code(ctru_sys::RS_NOTFOUND, ctru_sys::RD_NOT_FOUND)),
+            ServiceError::NotFound
+        );
+        assert_eq!(
+            ServiceError::from(dsp_code(
+                ctru_sys::RS_OUTOFRESOURCE,
+                ctru_sys::RD_OUT_OF_MEMORY
+            )),
+            ServiceError::OutOfMemory
+        );
+        assert_eq!(
+            ServiceError::from(dsp_code(ctru_sys::RS_INVALIDSTATE, ctru_sys::RD_BUSY)),
+            ServiceError::Busy
+        );
+
+        let unknown = dsp_code(ctru_sys::RS_INTERNAL, ctru_sys::RD_INVALID_SIZE);
+        assert_eq!(ServiceError::from(unknown), ServiceError::Other(unknown));
+
+        assert_eq!(
+            ServiceError::from_error(&Error::MissingDspFirmware),
+            Some(ServiceError::NotFound)
+        );
+        assert_eq!(ServiceError::from_error(&Error::ServiceAlreadyActive), None);
+
+        // Errors returned by the service wrappers.
+        let busy = dsp_code(ctru_sys::RS_INVALIDSTATE, ctru_sys::RD_BUSY);
+        assert_eq!(Error::Os(busy).service_error(), Some(ServiceError::Busy));
+        assert_eq!(
+            Error::from(crate::services::ndsp::Error::ChannelAlreadyInUse(0)).service_error(),
+            Some(ServiceError::Busy)
+        );
+        assert_eq!(
+            crate::services::ndsp::Error::InvalidChannel(24).service_error(),
+            Some(ServiceError::NotFound)
+        );
+        assert_eq!(
+            crate::services::ndsp::Error::InvalidWavData.service_error(),
+            None
+        );
+    }
+}
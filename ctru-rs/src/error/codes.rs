@@ -0,0 +1,94 @@
+//! Well-known result codes.
+//!
+//! The same logical failure may be reported with different level and summary bits depending on the situation,
+//! so these constants only identify the module and description of a result code.
+//! Use [`ResultCode::matches()`] to compare them against the codes returned by the system.
+//!
+//! # Example
+//!
+//! ```
+//! use ctru::error::{codes, ResultCode};
+//!
+//! let code = ResultCode(0xC8804478_u32 as i32);
+//!
+//! if code.matches(codes::FS_FILE_NOT_FOUND) {
+//!     // Create the file...
+//! }
+//! ```
+
+use super::ResultCode;
+
+/// Module and description pair identifying a specific failure, regardless of its level and summary.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KnownCode {
+    module: u8,
+    description: u16,
+}
+
+impl KnownCode {
+    /// Build a new code out of its module (such as [`ctru_sys::RM_FS`]) and description.
+    pub const fn new(module: u8, description: u16) -> Self {
+        Self {
+            module,
+            description,
+        }
+    }
+
+    /// Returns the module of the code.
+    pub const fn module(self) -> u8 {
+        self.module
+    }
+
+    /// Returns the description of the code.
+    pub const fn description(self) -> u16 {
+        self.description
+    }
+}
+
+impl From<ResultCode> for KnownCode {
+    fn from(code: ResultCode) -> Self {
+        Self::new(code.module(), code.description())
+    }
+}
+
+/// The archive or path could not be found.
+///
+/// Raw value: `0xC8804464`.
+pub const FS_NOT_FOUND: KnownCode = KnownCode::new(ctru_sys::RM_FS, 100);
+
+/// The file could not be found.
+///
+/// Raw value: `0xC8804478`.
+pub const FS_FILE_NOT_FOUND: KnownCode = KnownCode::new(ctru_sys::RM_FS, 120);
+
+/// The file or directory to create already exists.
+///
+/// Raw value: `0xC82044BE`.
+pub const FS_ALREADY_EXISTS: KnownCode = KnownCode::new(ctru_sys::RM_FS, 190);
+
+/// The operating system ran out of memory.
+///
+/// Example raw value: `0xD8601BF3`.
+pub const OS_OUT_OF_MEMORY: KnownCode = KnownCode::new(ctru_sys::RM_OS, ctru_sys::RD_OUT_OF_MEMORY);
+
+/// The handle passed to the kernel isn't valid.
+///
+/// Raw value: `0xD8E007F7`.
+pub const KERNEL_INVALID_HANDLE: KnownCode =
+    KnownCode::new(ctru_sys::RM_KERNEL, ctru_sys::RD_INVALID_HANDLE);
+
+/// The operation was cancelled.
+///
+/// Example raw value: `0xC9201BFB`.
+pub const OS_CANCELLED: KnownCode = KnownCode::new(ctru_sys::RM_OS, ctru_sys::RD_CANCEL_REQUESTED);
+
+/// The wait operation timed out.
+///
+/// Raw value: `0x09401BFE`.
+pub const OS_TIMEOUT: KnownCode = KnownCode::new(ctru_sys::RM_OS, ctru_sys::RD_TIMEOUT);
+
+/// The DSP firmware needed by NDSP could not be found.
+///
+/// Raw value: `0xD880A7FA`.
+pub const DSP_FIRMWARE_NOT_FOUND: KnownCode =
+    KnownCode::new(ctru_sys::RM_DSP, ctru_sys::RD_NOT_FOUND);
@@ -63,10 +63,110 @@ pub mod applets;
 pub mod console;
 pub mod error;
 pub mod linear;
+pub mod mappable;
 pub mod mii;
 pub mod os;
 pub mod prelude;
 mod sealed;
 pub mod services;
+pub mod vram;
 
 pub use crate::error::{Error, Result};
+
+/// Sets a custom [panic hook](https://doc.rust-lang.org/std/panic/fn.set_hook.html) that prints panic messages on the bottom screen,
+/// and waits for the user to press START before letting the application exit.
+///
+/// Unlike [`applets::error::set_panic_hook()`], this doesn't need any applet to be available, which makes it usable in every environment.
+///
+/// You can also choose to have the previously registered panic hook called before printing the message, which can be useful
+/// if you want to get the panic messages (and backtraces, with `RUST_BACKTRACE`) over `3dslink`, `GDB` or an emulator's log too.
+///
+/// You can use [`std::panic::take_hook`](https://doc.rust-lang.org/std/panic/fn.take_hook.html) to unregister the panic hook
+/// set by this function.
+///
+/// # Notes
+///
+/// * If the [`Gfx`](services::gfx::Gfx) service is not initialized during a panic (e.g. because its initialization is what failed),
+///   the message is sent to the debug output with `svcOutputDebugString` instead, and the old panic hook is called.
+/// * Any [`Console`](console::Console) on the bottom screen is replaced by the one used to print the message.
+/// * If the debug output was sent to the debugger with [`console::set_debug_output()`], the message is sent there as well.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// // Print panic messages on the bottom screen, as well as wherever the default hook prints them.
+/// ctru::set_panic_hook(true);
+/// ```
+pub fn set_panic_hook(call_old_hook: bool) {
+    use crate::console::{self, DebugOutput};
+    use crate::services::gfx::GFX_ACTIVE;
+    use std::io::Write;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::TryLockError;
+
+    // Set while a panic is being displayed, to avoid recursing if the hook itself panics.
+    static PANICKING: AtomicBool = AtomicBool::new(false);
+
+    let old_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let thread = std::thread::current();
+        let name = thread.name().unwrap_or("<unnamed>");
+        let message = format!("thread '{name}' {panic_info}");
+
+        if PANICKING.swap(true, Ordering::SeqCst) {
+            unsafe { ctru_sys::svcOutputDebugString(message.as_ptr().cast(), message.len() as _) };
+            return;
+        }
+
+        if call_old_hook {
+            old_hook(panic_info);
+        }
+
+        // If we get a `WouldBlock` error, we know that the `Gfx` service has been initialized.
+        if let Err(TryLockError::WouldBlock) = GFX_ACTIVE.try_lock() {
+            // The old hook has already written the message to the debug output, if called.
+            if !call_old_hook && console::debug_output() == Some(DebugOutput::Svc) {
+                unsafe {
+                    ctru_sys::svcOutputDebugString(message.as_ptr().cast(), message.len() as _)
+                };
+            }
+
+            unsafe {
+                ctru_sys::consoleInit(ctru_sys::GFX_BOTTOM, std::ptr::null_mut());
+            }
+
+            // Errors are ignored, since there is nowhere left to report them.
+            let mut stdout = std::io::stdout();
+            let _ = writeln!(stdout, "{message}\n\nPress START to exit");
+            let _ = stdout.flush();
+
+            // `hidInit` is reference counted, so this doesn't interfere with any active `Hid` handle.
+            let hid_result = unsafe { ctru_sys::hidInit() };
+
+            if ctru_sys::R_SUCCEEDED(hid_result) {
+                unsafe {
+                    while ctru_sys::aptMainLoop() {
+                        ctru_sys::gspWaitForEvent(ctru_sys::GSPGPU_EVENT_VBlank0, true);
+                        ctru_sys::hidScanInput();
+
+                        if ctru_sys::hidKeysDown() & ctru_sys::KEY_START != 0 {
+                            break;
+                        }
+                    }
+
+                    ctru_sys::hidExit();
+                }
+            }
+        } else {
+            unsafe { ctru_sys::svcOutputDebugString(message.as_ptr().cast(), message.len() as _) };
+
+            if !call_old_hook {
+                old_hook(panic_info);
+            }
+        }
+
+        PANICKING.store(false, Ordering::SeqCst);
+    }));
+}
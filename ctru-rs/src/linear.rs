@@ -7,16 +7,39 @@
 //!
 //! - <https://github.com/devkitPro/libctru/blob/master/libctru/source/allocator/linear.cpp>
 //! - <https://www.3dbrew.org/wiki/Memory_layout>
+//!
+//! # Allocation tracking
+//!
+//! With the `linear-tracking` feature enabled, every allocation made through [`LinearAllocator`] is recorded in a global registry,
+//! which can be inspected with [`allocations()`] and [`total_allocated()`] to find out what's using (and fragmenting) the LINEAR memory.
+//! Allocations can be attributed to a subsystem with [`with_allocation_tag()`], which is also used internally by `ctru-rs` (e.g. `"ndsp"` for audio data).
+//!
+//! With the feature disabled, no bookkeeping takes place at all.
+//!
+//! # Global allocator
+//!
+//! With the `linear-global-alloc` feature enabled, `ctru-rs` installs a `#[global_allocator]` which serves large allocations
+//! (see [`set_linear_threshold()`]) and allocations made inside [`with_linear_heap()`] from LINEAR memory, and everything else from the regular heap.
+//! This makes buffers allocated by dependencies with plain `Vec`s and `Box`es usable by the GPU and the DSP.
+//!
+//! It is a sharp tool, and should only be used when there's no way to pass a [`LinearAllocator`] to the code doing the allocations:
+//!
+//! - LINEAR memory is much smaller than the regular heap, and big buffers which never reach the hardware will eat up space needed by the ones that do.
+//! - The LINEAR heap is slower to allocate from, and gets fragmented easily by many short-lived allocations.
+//! - Allocations made through the global allocator aren't considered [`LinearAllocation`]s by the type system,
+//!   and aren't recorded by the `linear-tracking` feature.
 
 use std::alloc::{AllocError, Allocator, Layout};
+use std::mem::MaybeUninit;
 use std::ptr::NonNull;
 use std::rc::{self, Rc};
 use std::sync::{self, Arc};
 
 // Implementing an `std::alloc::Allocator` type is the best way to handle this case, since it gives
 // us full control over the normal `std` implementations (like `Box`). The only issue is that this is another unstable feature to add.
-// Sadly the linear memory allocator included in `libctru` doesn't implement `linearRealloc` at the time of these additions,
-// but the default fallback of the `std` will take care of that for us.
+// Sadly the linear memory allocator included in `libctru` doesn't implement `linearRealloc` at the time of these additions
+// (it always fails), so resizing is done in place only when the existing block is already big enough,
+// and by allocating a new block and copying the data over otherwise.
 
 /// [`Allocator`] struct for LINEAR memory.
 ///
@@ -30,6 +53,183 @@ impl LinearAllocator {
     pub fn free_space() -> u32 {
         unsafe { ctru_sys::linearSpaceFree() }
     }
+
+    /// Allocate a zeroed slice of `len` bytes in the LINEAR memory, returning an error instead of aborting if there isn't enough space left.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the LINEAR memory sector doesn't have a large enough free block.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::linear::LinearAllocator;
+    ///
+    /// // Way more than the whole LINEAR memory.
+    /// assert!(LinearAllocator::try_alloc_slice_zeroed(1 << 30).is_err());
+    ///
+    /// let data = LinearAllocator::try_alloc_slice_zeroed(1024).unwrap();
+    /// assert!(data.iter().all(|&byte| byte == 0));
+    /// # }
+    /// ```
+    pub fn try_alloc_slice_zeroed(len: usize) -> Result<Box<[u8], LinearAllocator>, AllocError> {
+        Self::alloc_aligned_slice(len, 1)
+    }
+
+    /// Allocate an uninitialized slice of `len` bytes in the LINEAR memory.
+    ///
+    /// This avoids clearing buffers which are going to be overwritten right away (such as streaming buffers),
+    /// which would otherwise double the memory traffic for big allocations.
+    ///
+    /// # Notes
+    ///
+    /// Memory allocated by `libctru` isn't cleared, so it may hold leftover data from previous allocations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::linear::LinearAllocator;
+    ///
+    /// let mut data = LinearAllocator::alloc_uninit_slice(4096);
+    ///
+    /// for (i, byte) in data.iter_mut().enumerate() {
+    ///     byte.write(i as u8);
+    /// }
+    ///
+    /// // Safety: every byte has been initialized by the loop above.
+    /// let data = unsafe { data.assume_init() };
+    /// # }
+    /// ```
+    pub fn alloc_uninit_slice(len: usize) -> Box<[MaybeUninit<u8>], LinearAllocator> {
+        Box::new_uninit_slice_in(len, LinearAllocator)
+    }
+
+    /// Allocate a zeroed slice of `len` bytes in the LINEAR memory, aligned to `align` bytes.
+    ///
+    /// This is useful for hardware transfers (such as DMA and GX transfers) with stricter alignment requirements than the data itself.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `align` isn't a power of two,
+    /// or if the LINEAR memory sector doesn't have a large enough free block.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::linear::LinearAllocator;
+    ///
+    /// let data = LinearAllocator::alloc_aligned_slice(4096, 0x80).unwrap();
+    /// assert_eq!(data.as_ptr() as usize % 0x80, 0);
+    ///
+    /// // The alignment must be a power of two.
+    /// assert!(LinearAllocator::alloc_aligned_slice(4096, 0x60).is_err());
+    /// # }
+    /// ```
+    #[doc(alias = "linearMemAlign")]
+    pub fn alloc_aligned_slice(
+        len: usize,
+        align: usize,
+    ) -> Result<Box<[u8], LinearAllocator>, AllocError> {
+        // This also rejects alignments which aren't a power of two.
+        let layout = Layout::from_size_align(len, align).map_err(|_| AllocError)?;
+
+        // Empty slices don't need any allocation (nor alignment, since they can't be accessed).
+        if len == 0 {
+            return Ok(Box::new_in([0u8; 0], LinearAllocator));
+        }
+
+        let pointer = LinearAllocator.allocate_zeroed(layout)?;
+
+        debug_assert_eq!(
+            pointer.as_ptr().cast::<u8>() as usize % align,
+            0,
+            "`linearMemAlign` returned a misaligned pointer"
+        );
+
+        // Safety: the memory has just been allocated with the right layout, and zeroed memory is a valid `u8` slice.
+        Ok(unsafe { Box::from_raw_in(pointer.as_ptr(), LinearAllocator) })
+    }
+
+    /// Returns the size of the LINEAR memory block starting at `ptr`,
+    /// or `None` if `ptr` doesn't point to the start of a block allocated with [`LinearAllocator`].
+    ///
+    /// # Notes
+    ///
+    /// The size of a block may be larger than the size requested when allocating it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::linear::LinearAllocator;
+    ///
+    /// let data: Box<[u8], _> = Box::new_in([0u8; 1024], LinearAllocator);
+    /// assert!(LinearAllocator::size_of(data.as_ptr()).unwrap() >= 1024);
+    ///
+    /// // Data on the regular heap isn't tracked by the allocator.
+    /// let regular = vec![0u8; 1024];
+    /// assert_eq!(LinearAllocator::size_of(regular.as_ptr()), None);
+    /// # }
+    /// ```
+    #[doc(alias = "linearGetSize")]
+    pub fn size_of<T: ?Sized>(ptr: *const T) -> Option<u32> {
+        // `libctru` only looks the pointer up between its allocations, without accessing the memory.
+        match unsafe { ctru_sys::linearGetSize(ptr.cast_mut().cast()) } {
+            0 => None,
+            size => Some(size),
+        }
+    }
+}
+
+impl LinearAllocator {
+    // Resize the block at `ptr`, which must have been allocated by this allocator with `old_layout`.
+    //
+    // The block is kept in place if it's already big enough and suitably aligned,
+    // otherwise its contents are moved to a new block.
+    unsafe fn resize(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // `libctru` rounds the size of its blocks up, so there is often some room left at the end of them.
+        let capacity = Self::size_of(ptr.as_ptr()).map_or(old_layout.size(), |size| size as usize);
+        let aligned = ptr.as_ptr() as usize % new_layout.align() == 0;
+
+        if aligned && new_layout.size() <= capacity {
+            #[cfg(feature = "linear-tracking")]
+            tracking::resize(ptr, new_layout);
+
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+
+        unsafe {
+            ptr.as_ptr().copy_to_nonoverlapping(
+                new_ptr.as_ptr().cast(),
+                old_layout.size().min(new_layout.size()),
+            );
+            self.deallocate(ptr, old_layout);
+        }
+
+        Ok(new_ptr)
+    }
 }
 
 unsafe impl Allocator for LinearAllocator {
@@ -37,19 +237,546 @@ unsafe impl Allocator for LinearAllocator {
     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
         let pointer = unsafe { ctru_sys::linearMemAlign(layout.size(), layout.align()) };
 
-        NonNull::new(pointer.cast())
+        let pointer = NonNull::new(pointer.cast())
             .map(|ptr| NonNull::slice_from_raw_parts(ptr, layout.size()))
-            .ok_or(AllocError)
+            .ok_or(AllocError)?;
+
+        #[cfg(feature = "linear-tracking")]
+        tracking::record(pointer.cast(), layout);
+
+        Ok(pointer)
+    }
+
+    #[doc(alias = "linearAlloc", alias = "linearMemAlign")]
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let pointer = self.allocate(layout)?;
+
+        // `libctru` doesn't clear the memory it hands out, so a single pass over it is needed.
+        unsafe { pointer.as_ptr().cast::<u8>().write_bytes(0, layout.size()) };
+
+        Ok(pointer)
+    }
+
+    #[doc(alias = "linearRealloc")]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { self.resize(ptr, old_layout, new_layout) }
+    }
+
+    #[doc(alias = "linearRealloc")]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let pointer = unsafe { self.resize(ptr, old_layout, new_layout)? };
+
+        // Only the new part of the block needs to be cleared, whether it was moved or not.
+        unsafe {
+            pointer
+                .as_ptr()
+                .cast::<u8>()
+                .add(old_layout.size())
+                .write_bytes(0, new_layout.size() - old_layout.size());
+        }
+
+        Ok(pointer)
+    }
+
+    #[doc(alias = "linearRealloc")]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { self.resize(ptr, old_layout, new_layout) }
     }
 
     #[doc(alias = "linearFree")]
     unsafe fn deallocate(&self, ptr: NonNull<u8>, _layout: Layout) {
+        #[cfg(feature = "linear-tracking")]
+        tracking::forget(ptr);
+
         unsafe {
             ctru_sys::linearFree(ptr.as_ptr().cast());
         }
     }
 }
 
+/// Run `f`, attributing every LINEAR allocation it makes on the current thread to `tag`.
+///
+/// Tags are only recorded with the `linear-tracking` feature enabled. Otherwise, this function simply calls `f`.
+/// Nested calls override the outer tag until they return.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use ctru::linear::{with_allocation_tag, LinearAllocator};
+///
+/// let texture: Box<[u8], _> = with_allocation_tag("textures", || {
+///     LinearAllocator::try_alloc_slice_zeroed(64 * 64 * 4).unwrap()
+/// });
+/// # }
+/// ```
+#[inline]
+pub fn with_allocation_tag<R>(tag: &'static str, f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "linear-tracking")]
+    {
+        tracking::with_tag(tag, f)
+    }
+
+    #[cfg(not(feature = "linear-tracking"))]
+    {
+        let _ = tag;
+        f()
+    }
+}
+
+/// Information about a live allocation made with [`LinearAllocator`].
+///
+/// Only available with the `linear-tracking` feature.
+#[cfg(feature = "linear-tracking")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AllocationInfo {
+    /// Address of the start of the allocation.
+    pub address: usize,
+    /// Size requested for the allocation, in bytes.
+    pub size: usize,
+    /// Alignment requested for the allocation, in bytes.
+    pub align: usize,
+    /// Tag of the subsystem which made the allocation, as set by [`with_allocation_tag()`].
+    pub tag: Option<&'static str>,
+}
+
+/// Returns all live allocations made with [`LinearAllocator`], sorted by address.
+///
+/// Only available with the `linear-tracking` feature.
+///
+/// # Notes
+///
+/// Gaps between consecutive allocations show how fragmented the LINEAR memory is,
+/// though `libctru` may place some bookkeeping data of its own in them.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use std::collections::HashMap;
+///
+/// use ctru::linear;
+///
+/// // Sum up the memory used by each subsystem.
+/// let mut usage: HashMap<_, usize> = HashMap::new();
+///
+/// for allocation in linear::allocations() {
+///     *usage.entry(allocation.tag.unwrap_or("untagged")).or_default() += allocation.size;
+/// }
+///
+/// for (tag, size) in usage {
+///     println!("{tag}: {size} bytes");
+/// }
+/// # }
+/// ```
+#[cfg(feature = "linear-tracking")]
+pub fn allocations() -> Vec<AllocationInfo> {
+    tracking::snapshot()
+}
+
+/// Returns the total size of the live allocations made with [`LinearAllocator`], in bytes.
+///
+/// Only available with the `linear-tracking` feature.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use ctru::linear::{self, LinearAllocator};
+///
+/// let before = linear::total_allocated();
+/// let data = LinearAllocator::try_alloc_slice_zeroed(1024).unwrap();
+///
+/// assert_eq!(linear::total_allocated(), before + 1024);
+///
+/// drop(data);
+/// assert_eq!(linear::total_allocated(), before);
+/// # }
+/// ```
+#[cfg(feature = "linear-tracking")]
+pub fn total_allocated() -> usize {
+    tracking::total()
+}
+
+#[cfg(feature = "linear-tracking")]
+mod tracking {
+    use std::cell::Cell;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    // Live allocations, keyed by address.
+    static REGISTRY: Mutex<BTreeMap<usize, AllocationInfo>> = Mutex::new(BTreeMap::new());
+
+    thread_local! {
+        static CURRENT_TAG: Cell<Option<&'static str>> = const { Cell::new(None) };
+    }
+
+    fn registry() -> std::sync::MutexGuard<'static, BTreeMap<usize, AllocationInfo>> {
+        // The registry is never left in an inconsistent state, so a poisoned lock can be used anyway.
+        REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(super) fn with_tag<R>(tag: &'static str, f: impl FnOnce() -> R) -> R {
+        // Restores the outer tag even if `f` panics.
+        struct Restore(Option<&'static str>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT_TAG.set(self.0);
+            }
+        }
+
+        let _restore = Restore(CURRENT_TAG.replace(Some(tag)));
+
+        f()
+    }
+
+    pub(super) fn record(ptr: NonNull<u8>, layout: Layout) {
+        let address = ptr.as_ptr() as usize;
+
+        // The registry lives on the regular heap, so this can't recurse into the LINEAR allocator.
+        registry().insert(
+            address,
+            AllocationInfo {
+                address,
+                size: layout.size(),
+                align: layout.align(),
+                tag: CURRENT_TAG.get(),
+            },
+        );
+    }
+
+    pub(super) fn resize(ptr: NonNull<u8>, layout: Layout) {
+        if let Some(allocation) = registry().get_mut(&(ptr.as_ptr() as usize)) {
+            allocation.size = layout.size();
+            allocation.align = layout.align();
+        }
+    }
+
+    pub(super) fn forget(ptr: NonNull<u8>) {
+        registry().remove(&(ptr.as_ptr() as usize));
+    }
+
+    pub(super) fn snapshot() -> Vec<AllocationInfo> {
+        registry().values().copied().collect()
+    }
+
+    pub(super) fn total() -> usize {
+        registry().values().map(|allocation| allocation.size).sum()
+    }
+}
+
+/// Default value of the size threshold used by the global allocator, see [`set_linear_threshold()`].
+///
+/// Only available with the `linear-global-alloc` feature.
+#[cfg(feature = "linear-global-alloc")]
+pub const DEFAULT_LINEAR_THRESHOLD: usize = 64 * 1024;
+
+/// Set the size (in bytes) above which the global allocator serves allocations from LINEAR memory.
+///
+/// Passing `usize::MAX` disables the threshold, leaving only [`with_linear_heap()`] to use LINEAR memory.
+/// Already existing allocations aren't moved.
+///
+/// Only available with the `linear-global-alloc` feature.
+#[cfg(feature = "linear-global-alloc")]
+pub fn set_linear_threshold(threshold: usize) {
+    global::THRESHOLD.store(threshold, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Returns the size (in bytes) above which the global allocator serves allocations from LINEAR memory.
+///
+/// Only available with the `linear-global-alloc` feature.
+#[cfg(feature = "linear-global-alloc")]
+pub fn linear_threshold() -> usize {
+    global::THRESHOLD.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Run `f`, serving every allocation it makes on the current thread through the global allocator from LINEAR memory, regardless of its size.
+///
+/// Only available with the `linear-global-alloc` feature.
+///
+/// # Notes
+///
+/// Everything allocated by `f` ends up in LINEAR memory, including temporary data, so it's best to keep `f` as small as possible.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use ctru::linear::{with_linear_heap, LinearAllocator};
+///
+/// let vertices: Vec<f32> = with_linear_heap(|| vec![0.0; 12]);
+/// assert!(LinearAllocator::size_of(vertices.as_ptr()).is_some());
+/// # }
+/// ```
+#[cfg(feature = "linear-global-alloc")]
+pub fn with_linear_heap<R>(f: impl FnOnce() -> R) -> R {
+    global::with_linear_heap(f)
+}
+
+#[cfg(feature = "linear-global-alloc")]
+mod global {
+    use std::alloc::{GlobalAlloc, System};
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    pub(super) static THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_LINEAR_THRESHOLD);
+
+    thread_local! {
+        // Using a const initializer without a destructor, this never allocates (and thus never recurses into the allocator).
+        static FORCE_LINEAR: Cell<bool> = const { Cell::new(false) };
+    }
+
+    pub(super) fn with_linear_heap<R>(f: impl FnOnce() -> R) -> R {
+        // Restores the outer state even if `f` panics.
+        struct Restore(bool);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                FORCE_LINEAR.set(self.0);
+            }
+        }
+
+        let _restore = Restore(FORCE_LINEAR.replace(true));
+
+        f()
+    }
+
+    // Global allocator splitting requests between the LINEAR and the regular heap.
+    #[cfg_attr(test, allow(dead_code))]
+    struct SplitAllocator;
+
+    impl SplitAllocator {
+        fn use_linear(layout: Layout) -> bool {
+            layout.size() > THRESHOLD.load(Ordering::Relaxed) || FORCE_LINEAR.get()
+        }
+
+        fn is_linear(ptr: *mut u8) -> bool {
+            // `linearGetSize` only finds blocks allocated from the LINEAR heap.
+            LinearAllocator::size_of(ptr).is_some()
+        }
+    }
+
+    // `LinearAllocator` isn't used directly, to avoid recursing into the `linear-tracking` registry (which lives on the global heap).
+    unsafe impl GlobalAlloc for SplitAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            if Self::use_linear(layout) {
+                unsafe { ctru_sys::linearMemAlign(layout.size(), layout.align()).cast() }
+            } else {
+                unsafe { System.alloc(layout) }
+            }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            if Self::is_linear(ptr) {
+                unsafe { ctru_sys::linearFree(ptr.cast()) }
+            } else {
+                unsafe { System.dealloc(ptr, layout) }
+            }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            // Safety: the caller guarantees the new size is valid for the alignment.
+            let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+
+            // The fast path is still available when the data stays on the regular heap.
+            if !Self::is_linear(ptr) && !Self::use_linear(new_layout) {
+                return unsafe { System.realloc(ptr, layout, new_size) };
+            }
+
+            let new_ptr = unsafe { self.alloc(new_layout) };
+
+            if !new_ptr.is_null() {
+                unsafe {
+                    ptr.copy_to_nonoverlapping(new_ptr, layout.size().min(new_size));
+                    self.dealloc(ptr, layout);
+                }
+            }
+
+            new_ptr
+        }
+    }
+
+    // Like `__stacksize__`, this must not be defined twice when building lib tests.
+    #[cfg(not(test))]
+    #[global_allocator]
+    static GLOBAL: SplitAllocator = SplitAllocator;
+}
+
+/// Byte vector stored in LINEAR memory.
+///
+/// # Notes
+///
+/// Growing the vector only moves its data when the underlying LINEAR block isn't big enough to hold the new contents,
+/// so appending small amounts of data at a time (as streaming decoders and network receive buffers do) is cheaper than it looks.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use ctru::linear::with_capacity_linear;
+///
+/// let mut data = with_capacity_linear(16);
+///
+/// // Grow the vector across several block sizes, checking the contents survive every reallocation.
+/// for size in [16, 100, 1000, 4096, 70_000] {
+///     while data.len() < size {
+///         data.push(data.len() as u8);
+///     }
+///
+///     assert!(data.iter().enumerate().all(|(i, &byte)| byte == i as u8));
+/// }
+///
+/// data.truncate(10);
+/// data.shrink_to_fit();
+///
+/// assert_eq!(&*data, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+/// # }
+/// ```
+pub type LinearVec = Vec<u8, LinearAllocator>;
+
+/// Create an empty [`LinearVec`] with space for at least `capacity` bytes.
+pub fn with_capacity_linear(capacity: usize) -> LinearVec {
+    Vec::with_capacity_in(capacity, LinearAllocator)
+}
+
+/// Copy the given bytes to a new allocation in LINEAR memory.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use ctru::linear::linear_box_from_slice;
+///
+/// let data = linear_box_from_slice(b"RIFF");
+///
+/// assert_eq!(&*data, b"RIFF");
+/// # }
+/// ```
+pub fn linear_box_from_slice(data: &[u8]) -> Box<[u8], LinearAllocator> {
+    copy_into_linear(data)
+}
+
+/// Copy the given values to a new allocation in LINEAR memory.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use ctru::linear::copy_into_linear;
+///
+/// let samples = copy_into_linear(&[0i16, 1000, -1000]);
+///
+/// assert_eq!(&*samples, &[0, 1000, -1000]);
+/// # }
+/// ```
+pub fn copy_into_linear<T: Copy>(data: &[T]) -> Box<[T], LinearAllocator> {
+    let mut vec = Vec::with_capacity_in(data.len(), LinearAllocator);
+    vec.extend_from_slice(data);
+
+    // The capacity matches the length, so this doesn't reallocate.
+    vec.into_boxed_slice()
+}
+
+/// Copy the given values to a new allocation in LINEAR memory aligned to `align` bytes,
+/// returning an error instead of aborting if there isn't enough space left.
+///
+/// The allocation is always aligned enough for `T`, even if `align` is smaller.
+///
+/// # Errors
+///
+/// This function will return an error if `align` isn't a power of two,
+/// or if the LINEAR memory sector doesn't have a large enough free block.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use ctru::linear::try_copy_into_linear;
+///
+/// let samples = try_copy_into_linear(&[0i16, 1000, -1000], 0x80).unwrap();
+///
+/// assert_eq!(&*samples, &[0, 1000, -1000]);
+/// assert_eq!(samples.as_ptr() as usize % 0x80, 0);
+/// # }
+/// ```
+pub fn try_copy_into_linear<T: Copy>(
+    data: &[T],
+    align: usize,
+) -> Result<Box<[T], LinearAllocator>, AllocError> {
+    // This also rejects alignments which aren't a power of two.
+    let layout = Layout::array::<T>(data.len())
+        .and_then(|layout| layout.align_to(align))
+        .map_err(|_| AllocError)?;
+
+    // Empty slices (and slices of zero-sized types) don't need any allocation, so `Vec` won't make one.
+    if layout.size() == 0 {
+        let mut vec = Vec::new_in(LinearAllocator);
+        vec.extend_from_slice(data);
+
+        return Ok(vec.into_boxed_slice());
+    }
+
+    let pointer = LinearAllocator.allocate(layout)?.cast::<T>();
+
+    // Safety: the block has just been allocated with room for `data.len()` values of `T`, correctly aligned,
+    // and every value is initialized by the copy. `LinearAllocator` doesn't need the original layout to free the block.
+    unsafe {
+        pointer
+            .as_ptr()
+            .copy_from_nonoverlapping(data.as_ptr(), data.len());
+
+        Ok(Box::from_raw_in(
+            std::ptr::slice_from_raw_parts_mut(pointer.as_ptr(), data.len()),
+            LinearAllocator,
+        ))
+    }
+}
+
 /// Trait indicating a type has been allocated using [`LinearAllocator`].
 /// This can be used to enforce that a given slice was allocated in LINEAR memory.
 ///
@@ -70,6 +797,105 @@ unsafe impl<T: ?Sized> LinearAllocation for Arc<T, LinearAllocator> {}
 unsafe impl<T: ?Sized> LinearAllocation for sync::Weak<T, LinearAllocator> {}
 unsafe impl<T: ?Sized> LinearAllocation for Box<T, LinearAllocator> {}
 
+/// Borrowed slice of data stored in LINEAR memory.
+///
+/// This can be used to share a single, big LINEAR allocation between many users (for example, many [`Wave`](crate::services::ndsp::wave::Wave)s
+/// playing sound effects out of a single preloaded bank), without copying the data for each one of them.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use ctru::linear::{LinearAllocator, LinearSlice};
+///
+/// let bank: Box<[u8], _> = Box::new_in([0u8; 1024], LinearAllocator);
+///
+/// // Any part of a LINEAR allocation is valid.
+/// let first_half = LinearSlice::new(&bank[..512]).unwrap();
+///
+/// // Data on the regular heap isn't.
+/// let regular = vec![0u8; 1024];
+/// assert!(LinearSlice::new(&regular).is_none());
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct LinearSlice<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> LinearSlice<'a> {
+    /// Wrap the given slice, if it's entirely stored in LINEAR memory.
+    ///
+    /// Returns `None` if any part of the slice lies outside of the LINEAR memory sector.
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        let start = data.as_ptr() as usize;
+        let end = start + data.len();
+
+        // FCRAM is mapped linearly at different addresses depending on the system version, so both mappings are checked.
+        let in_linear_memory = [
+            (ctru_sys::OS_FCRAM_VADDR, ctru_sys::OS_FCRAM_SIZE),
+            (ctru_sys::OS_OLD_FCRAM_VADDR, ctru_sys::OS_OLD_FCRAM_SIZE),
+        ]
+        .into_iter()
+        .any(|(address, size)| {
+            let (address, size) = (address as usize, size as usize);
+
+            start >= address && end <= address + size
+        });
+
+        in_linear_memory.then_some(Self { data })
+    }
+
+    /// Returns the wrapped slice.
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl AsRef<[u8]> for LinearSlice<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self.data
+    }
+}
+
+unsafe impl LinearAllocation for LinearSlice<'_> {}
+
 // We could also impl for various std::collections types, but it seems unlikely
 // those would ever be used for this purpose in practice, since most of the type
 // we're dereferencing to a &[T]. The workaround would just be to convert to a Vec/Box.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_copy_into_linear_alignment() {
+        let data = [1u32, 2, 3, 4, 5];
+
+        for align in [1, 4, 0x80, 0x1000] {
+            let copy = try_copy_into_linear(&data, align).unwrap();
+
+            assert_eq!(&*copy, &data);
+            assert_eq!(copy.as_ptr() as usize % align, 0);
+        }
+
+        // Smaller alignments than the type's own are raised to it.
+        let copy = try_copy_into_linear(&data, 1).unwrap();
+        assert_eq!(copy.as_ptr() as usize % std::mem::align_of::<u32>(), 0);
+
+        assert!(try_copy_into_linear(&data, 0x60).is_err());
+        assert!(try_copy_into_linear(&data, 0).is_err());
+    }
+
+    #[test]
+    fn try_copy_into_linear_empty() {
+        let copy = try_copy_into_linear::<u16>(&[], 0x80).unwrap();
+        assert!(copy.is_empty());
+
+        // Invalid alignments are rejected even if nothing is allocated.
+        assert!(try_copy_into_linear::<u16>(&[], 3).is_err());
+    }
+}
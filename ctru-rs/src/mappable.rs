@@ -0,0 +1,71 @@
+//! Page-aligned memory allocator.
+//!
+//! Many system services (such as SOC, MIC or CSND) work on a memory block shared with the application.
+//! The memory backing such blocks must be aligned to the size of a memory page (`0x1000` bytes) and span a whole amount of pages.
+//!
+//! # Notes
+//!
+//! `libctru`'s own `mappableAlloc` only reserves a range of virtual addresses (to be mapped later via `svcMapMemoryBlock`),
+//! without any memory backing it. As such, it can't be used as an [`Allocator`], and [`MappableAllocator`] allocates
+//! page-aligned memory from the regular heap instead, like the service wrappers of this crate already do.
+
+use std::alloc::{AllocError, Allocator, Layout};
+use std::ptr::NonNull;
+
+// Size of a memory page, which is the granularity of shared memory blocks.
+const PAGE_SIZE: usize = 0x1000;
+
+/// [`Allocator`] struct for page-aligned memory, suitable to be shared with system services.
+///
+/// Every allocation is aligned to `0x1000` bytes (or to the alignment requested by the layout, if bigger)
+/// and its size is rounded up to a multiple of `0x1000` bytes.
+///
+/// To use this struct the main crate must activate the `allocator_api` unstable feature.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # fn main() {
+/// # let _runner = test_runner::GdbRunner::default();
+/// #
+/// use ctru::mappable::MappableAllocator;
+///
+/// let buffer: Vec<u8, _> = Vec::with_capacity_in(0x3000, MappableAllocator);
+///
+/// assert_eq!(buffer.as_ptr() as usize % 0x1000, 0);
+/// # }
+/// ```
+#[derive(Copy, Clone, Default, Debug)]
+pub struct MappableAllocator;
+
+// Returns the layout actually used for an allocation requested with `layout`.
+fn page_layout(layout: Layout) -> Result<Layout, AllocError> {
+    let size = layout
+        .size()
+        .checked_next_multiple_of(PAGE_SIZE)
+        .ok_or(AllocError)?;
+
+    Layout::from_size_align(size, layout.align().max(PAGE_SIZE)).map_err(|_| AllocError)
+}
+
+unsafe impl Allocator for MappableAllocator {
+    #[doc(alias = "memalign")]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let page_layout = page_layout(layout)?;
+
+        let pointer = unsafe { libc::memalign(page_layout.align(), page_layout.size()) };
+
+        // The whole padded block is usable by the caller.
+        NonNull::new(pointer.cast())
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, page_layout.size()))
+            .ok_or(AllocError)
+    }
+
+    #[doc(alias = "free")]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, _layout: Layout) {
+        unsafe {
+            libc::free(ptr.as_ptr().cast());
+        }
+    }
+}
@@ -144,6 +144,8 @@ impl WifiStrength {
 }
 
 /// Get the current value of the stereoscopic 3D slider on a scale from 0.0­–­1.0.
+///
+/// The value is read from memory shared with the system, which keeps it up to date without any service or polling.
 pub fn current_3d_slider_state() -> f32 {
     unsafe { ctru_sys::osGet3DSliderState() }
 }
@@ -13,3 +13,7 @@ impl Sealed for TopScreenLeft {}
 impl Sealed for TopScreenRight {}
 impl Sealed for BottomScreen {}
 impl Sealed for Console<'_> {}
+
+// PCM sample types of `ndsp` waves.
+impl Sealed for i8 {}
+impl Sealed for i16 {}
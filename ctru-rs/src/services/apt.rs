@@ -6,8 +6,38 @@
 //! It also handles running applets, small programs made available by the OS to streamline specific functionality.
 //! Those are implemented in the [`applets`](crate::applets) module.
 
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
 use crate::error::ResultCode;
 
+type HookCallback = Box<dyn FnMut() + Send>;
+
+const HOOK_COUNT: usize = ctru_sys::APTHOOK_COUNT as usize;
+
+// Callbacks for each hook type, along with whether the hook is registered with `libctru`.
+struct Hooks {
+    callbacks: [Option<HookCallback>; HOOK_COUNT],
+    registered: bool,
+    // Number of `Apt` handles alive, since the callbacks are shared by all of them.
+    handles: usize,
+}
+
+static HOOKS: Mutex<Hooks> = {
+    const NONE: Option<HookCallback> = None;
+    Mutex::new(Hooks {
+        callbacks: [NONE; HOOK_COUNT],
+        registered: false,
+        handles: 0,
+    })
+};
+
+// Linked into the list of hooks of `libctru` while registered, so it must never move.
+static mut HOOK_COOKIE: ctru_sys::aptHookCookie = ctru_sys::aptHookCookie {
+    next: std::ptr::null_mut(),
+    callback: None,
+    param: std::ptr::null_mut(),
+};
+
 /// Handle to the Applet service.
 pub struct Apt(());
 
@@ -30,10 +60,11 @@ impl Apt {
     /// ```
     #[doc(alias = "aptInit")]
     pub fn new() -> crate::Result<Apt> {
-        unsafe {
-            ResultCode(ctru_sys::aptInit())?;
-            Ok(Apt(()))
-        }
+        ResultCode(unsafe { ctru_sys::aptInit() })?;
+
+        lock_hooks().handles += 1;
+
+        Ok(Apt(()))
     }
 
     /// Returns `true` if the application is running in the foreground as normal.
@@ -119,20 +150,259 @@ impl Apt {
         unsafe { ctru_sys::aptIsHomeAllowed() }
     }
 
+    /// Forbid the console from entering the HOME Menu and sleep mode until the returned guard is dropped.
+    ///
+    /// This protects operations which mustn't be interrupted, like writing a save file. Once the guard is dropped
+    /// (including when returning early because of an error), the previous settings are restored.
+    ///
+    /// # Notes
+    ///
+    /// While the HOME Menu is forbidden, pressing the HOME button shows a notification to the user,
+    /// and can be detected with [`Apt::is_home_press_rejected()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::apt::Apt;
+    ///
+    /// let apt = Apt::new()?;
+    ///
+    /// {
+    ///     let _inhibit = apt.inhibit_home_and_sleep();
+    ///     assert!(!apt.is_home_allowed());
+    ///
+    ///     // Write the save file here.
+    /// }
+    ///
+    /// assert!(apt.is_home_allowed());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "aptSetHomeAllowed")]
+    #[doc(alias = "aptSetSleepAllowed")]
+    pub fn inhibit_home_and_sleep(&self) -> InhibitGuard<'_> {
+        let guard = InhibitGuard {
+            _apt: self,
+            home_allowed: self.is_home_allowed(),
+            sleep_allowed: self.is_sleep_allowed(),
+        };
+
+        unsafe {
+            ctru_sys::aptSetHomeAllowed(false);
+            ctru_sys::aptSetSleepAllowed(false);
+        }
+
+        guard
+    }
+
+    /// Returns `true` if the HOME button was pressed while the HOME Menu wasn't allowed, since the last call to this function.
+    ///
+    /// This can be checked on every frame, after [`Apt::main_loop()`], to tell the user why the application isn't suspended.
+    /// Have a look at [`Apt::set_home_allowed()`] and [`Apt::inhibit_home_and_sleep()`].
+    #[doc(alias = "aptCheckHomePressRejected")]
+    pub fn is_home_press_rejected(&self) -> bool {
+        unsafe { ctru_sys::aptCheckHomePressRejected() }
+    }
+
     /// Immediately jumps to the home menu.
     #[doc(alias = "aptJumpToHomeMenu")]
     pub fn jump_to_home_menu(&mut self) {
         unsafe { ctru_sys::aptJumpToHomeMenu() }
     }
+
+    /// Set a function to be called when the application is suspended, such as when opening the HOME Menu.
+    ///
+    /// This is the place to pause what shouldn't keep going while the application isn't in the foreground, like music or timers.
+    /// The new callback replaces any previously set one. Callbacks are shared by all [`Apt`] handles,
+    /// and are removed when the last of them is dropped.
+    ///
+    /// # Notes
+    ///
+    /// The suspend, resume and exit callbacks are called from within [`Apt::main_loop()`], on the thread calling it,
+    /// before it returns (or blocks while the application is suspended).
+    /// The sleep and wake-up callbacks are instead called from the event thread of the APT service, as soon as the event happens.
+    ///
+    /// In both cases, the callbacks must not block. Setting a callback from within a callback will deadlock,
+    /// and panicking inside of one will abort the program.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// use ctru::services::apt::Apt;
+    ///
+    /// let mut apt = Apt::new()?;
+    ///
+    /// let paused = Arc::new(AtomicBool::new(false));
+    ///
+    /// let paused_clone = Arc::clone(&paused);
+    /// apt.on_suspend(move || paused_clone.store(true, Ordering::Relaxed));
+    ///
+    /// let paused_clone = Arc::clone(&paused);
+    /// apt.on_resume(move || paused_clone.store(false, Ordering::Relaxed));
+    ///
+    /// while apt.main_loop() {
+    ///     if !paused.load(Ordering::Relaxed) {
+    ///         // Keep the music playing.
+    ///     }
+    /// #   break;
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "aptHook")]
+    pub fn on_suspend<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        set_hook(ctru_sys::APTHOOK_ONSUSPEND, Box::new(callback));
+    }
+
+    /// Set a function to be called when the application is back in the foreground after being suspended.
+    ///
+    /// Have a look at [`Apt::on_suspend()`] for more information.
+    #[doc(alias = "aptHook")]
+    pub fn on_resume<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        set_hook(ctru_sys::APTHOOK_ONRESTORE, Box::new(callback));
+    }
+
+    /// Set a function to be called when the console goes to sleep, such as when closing the lid.
+    ///
+    /// This callback is called from the event thread of the APT service, have a look at [`Apt::on_suspend()`] for more information.
+    #[doc(alias = "aptHook")]
+    pub fn on_sleep<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        set_hook(ctru_sys::APTHOOK_ONSLEEP, Box::new(callback));
+    }
+
+    /// Set a function to be called when the console wakes up from sleep.
+    ///
+    /// This callback is called from the event thread of the APT service, have a look at [`Apt::on_suspend()`] for more information.
+    #[doc(alias = "aptHook")]
+    pub fn on_wakeup<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        set_hook(ctru_sys::APTHOOK_ONWAKEUP, Box::new(callback));
+    }
+
+    /// Set a function to be called when the application is asked to close, right before [`Apt::main_loop()`] returns `false`.
+    ///
+    /// Have a look at [`Apt::on_suspend()`] for more information.
+    #[doc(alias = "aptHook")]
+    pub fn on_exit<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        set_hook(ctru_sys::APTHOOK_ONEXIT, Box::new(callback));
+    }
+
+    /// Remove all the functions set with [`Apt::on_suspend()`] and the other hook methods, including those set through other [`Apt`] handles.
+    #[doc(alias = "aptUnhook")]
+    pub fn clear_hooks(&mut self) {
+        let mut hooks = lock_hooks();
+
+        if hooks.registered {
+            unsafe { ctru_sys::aptUnhook(std::ptr::addr_of_mut!(HOOK_COOKIE)) };
+            hooks.registered = false;
+        }
+
+        // Take the callbacks out first, so that they get dropped without holding the lock.
+        let _callbacks = std::mem::take(&mut hooks.callbacks);
+        drop(hooks);
+    }
 }
 
 impl Drop for Apt {
     #[doc(alias = "aptExit")]
     fn drop(&mut self) {
+        let mut hooks = lock_hooks();
+        hooks.handles -= 1;
+        let last_handle = hooks.handles == 0;
+        drop(hooks);
+
+        // The hooks must not outlive the service, which would call them after `aptExit`.
+        if last_handle {
+            self.clear_hooks();
+        }
+
         unsafe { ctru_sys::aptExit() };
     }
 }
 
+/// Guard forbidding the HOME Menu and sleep mode, returned by [`Apt::inhibit_home_and_sleep()`].
+///
+/// The previous settings are restored when the guard is dropped.
+#[must_use = "the HOME Menu and sleep mode are allowed again as soon as the guard is dropped"]
+pub struct InhibitGuard<'a> {
+    _apt: &'a Apt,
+    home_allowed: bool,
+    sleep_allowed: bool,
+}
+
+impl Drop for InhibitGuard<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            ctru_sys::aptSetHomeAllowed(self.home_allowed);
+            ctru_sys::aptSetSleepAllowed(self.sleep_allowed);
+        }
+    }
+}
+
+fn set_hook(hook: ctru_sys::APT_HookType, callback: HookCallback) {
+    let mut hooks = lock_hooks();
+
+    let _previous = hooks.callbacks[hook as usize].replace(callback);
+
+    // A single hook is registered for all types, forwarding them to the callbacks.
+    if !hooks.registered {
+        unsafe {
+            ctru_sys::aptHook(
+                std::ptr::addr_of_mut!(HOOK_COOKIE),
+                Some(hook_trampoline),
+                std::ptr::null_mut(),
+            )
+        };
+        hooks.registered = true;
+    }
+
+    drop(hooks);
+}
+
+fn lock_hooks() -> MutexGuard<'static, Hooks> {
+    HOOKS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Forwards the `libctru` hook to the Rust closure stored for its type.
+unsafe extern "C" fn hook_trampoline(hook: ctru_sys::APT_HookType, _param: *mut libc::c_void) {
+    let mut hooks = lock_hooks();
+
+    if let Some(callback) = hooks
+        .callbacks
+        .get_mut(hook as usize)
+        .and_then(Option::as_mut)
+    {
+        callback();
+    }
+}
+
 /// Can launch other applications when the current one exits.
 pub struct Chainloader<'a> {
     _apt: &'a Apt,
@@ -1,10 +1,15 @@
 //! FileSystem service.
 //!
 //! Currently, this module contains only datatypes to easily operate with unsafe [`ctru_sys`] code regarding the file-system functionality.
+//!
+//! Failures reported by the service can be told apart as a [`ServiceError`], either through [`Error::service_error()`](crate::Error::service_error)
+//! or by converting the raw [`ResultCode`](crate::error::ResultCode) returned by [`ctru_sys`] functions.
 #![doc(alias = "filesystem")]
 
 use bitflags::bitflags;
 
+pub use crate::error::ServiceError;
+
 bitflags! {
     #[derive(Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
     struct Open: u8 {
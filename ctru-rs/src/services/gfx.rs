@@ -6,13 +6,29 @@
 
 use std::cell::{Ref, RefCell, RefMut};
 use std::marker::PhantomData;
-use std::sync::Mutex;
+use std::ops::Range;
+use std::sync::{Mutex, PoisonError};
 
-use crate::error::Result;
+use crate::error::{Result, ResultCode};
 use crate::sealed::Sealed;
+use crate::services::cfgu::{Cfgu, SystemModel};
 use crate::services::gspgpu::{self, FramebufferFormat};
 use crate::services::ServiceReference;
 
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics;
+mod events;
+mod frame_limiter;
+mod transfer;
+
+#[cfg(feature = "embedded-graphics")]
+pub use embedded_graphics::FrameBufferTarget;
+use events::clear_all_event_callbacks;
+pub(crate) use events::wait_for_event_timeout;
+pub use events::{clear_event_callback, set_event_callback};
+pub use frame_limiter::FrameLimiter;
+pub use transfer::{GxBuffer, Transfer, TransferFlags, TransferScaling};
+
 /// Trait to handle common functionality for all screens.
 ///
 /// This trait is implemented by the screen structs for working with frame buffers and
@@ -33,6 +49,12 @@ pub trait Screen: Sealed {
     /// The pointer of the framebuffer returned by this function can change after each call
     /// to this function if double buffering is enabled, so it's suggested to NOT save it for later use.
     ///
+    /// With double buffering enabled, the returned framebuffer is the one hidden from view (which will be displayed by the next [`Swap::swap_buffers`]).
+    /// Otherwise, the returned framebuffer is always the one being displayed, so its contents are kept between frames.
+    ///
+    /// The returned framebuffer keeps the screen mutably borrowed, so it must be obtained again after swapping the buffers.
+    /// Use [`RawFrameBuffer::buffer_index()`] to tell which of the two framebuffers of the screen it is.
+    ///
     /// # Panics
     ///
     /// If the [`Gfx`] service was initialised via [`Gfx::with_formats_vram()`] this function will crash the program with an ARM exception.
@@ -43,12 +65,13 @@ pub trait Screen: Sealed {
         let ptr = unsafe {
             ctru_sys::gfxGetFramebuffer(self.as_raw(), self.side().into(), &mut width, &mut height)
         };
-        RawFrameBuffer {
+        RawFrameBuffer::new(
+            self.as_raw(),
             ptr,
-            width: width.into(),
-            height: height.into(),
-            screen: PhantomData,
-        }
+            width.into(),
+            height.into(),
+            self.framebuffer_format(),
+        )
     }
 
     /// Gets the framebuffer format.
@@ -65,6 +88,157 @@ pub trait Screen: Sealed {
     fn set_framebuffer_format(&mut self, fmt: FramebufferFormat) {
         unsafe { ctru_sys::gfxSetScreenFormat(self.as_raw(), fmt.into()) }
     }
+
+    /// Copy the contents of the screen into a [`ScreenCapture`], converting them to a row-major RGBA8 image.
+    ///
+    /// # Notes
+    ///
+    /// This captures the same framebuffer returned by [`Screen::raw_framebuffer()`]. When double buffering is enabled,
+    /// that's the one being drawn to, so the capture should be taken right before calling [`Swap::swap_buffers`].
+    ///
+    /// # Panics
+    ///
+    /// If the [`Gfx`] service was initialised via [`Gfx::with_formats_vram()`] this function will crash the program with an ARM exception.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Gfx, Screen};
+    ///
+    /// let gfx = Gfx::new()?;
+    ///
+    /// let capture = gfx.bottom_screen.borrow().capture();
+    ///
+    /// assert_eq!((capture.width, capture.height), (320, 240));
+    /// assert_eq!(capture.data.len(), 320 * 240 * 4);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "gfxGetFramebuffer")]
+    fn capture(&self) -> ScreenCapture {
+        let mut width: u16 = 0;
+        let mut height: u16 = 0;
+        let ptr = unsafe {
+            ctru_sys::gfxGetFramebuffer(self.as_raw(), self.side().into(), &mut width, &mut height)
+        };
+        let format = self.framebuffer_format();
+        let len = usize::from(width) * usize::from(height) * format.pixel_depth_bytes();
+
+        // Safety: the framebuffer is big enough for the screen in its current format,
+        // and it can't be written to while the screen is borrowed.
+        let data = unsafe { std::slice::from_raw_parts(ptr, len) };
+
+        // Write back what the CPU drew, so the copy is complete and nothing is discarded.
+        // Pixels written by the GPU are already visible, since completed transfers invalidate their destination.
+        let _ = flush_data_cache(data);
+
+        ScreenCapture::from_framebuffer(data, width.into(), height.into(), format)
+    }
+}
+
+/// Copy of the contents of a [`Screen`], obtained with [`Screen::capture()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScreenCapture {
+    /// The width of the image in pixels.
+    pub width: usize,
+    /// The height of the image in pixels.
+    pub height: usize,
+    /// RGBA8 pixel data, stored row by row starting from the top-left corner.
+    pub data: Vec<u8>,
+}
+
+impl ScreenCapture {
+    // Convert the data of a rotated framebuffer, which has `fb_width` pixels for each column of the screen (starting from the bottom).
+    fn from_framebuffer(
+        data: &[u8],
+        fb_width: usize,
+        fb_height: usize,
+        format: FramebufferFormat,
+    ) -> Self {
+        let (width, height) = (fb_height, fb_width);
+        let bytes_per_pixel = format.pixel_depth_bytes();
+        let mut pixels = vec![0; width * height * 4];
+
+        for (i, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+            let (x, y) = (i % width, i / width);
+            let index = (x * fb_width + (fb_width - 1 - y)) * bytes_per_pixel;
+
+            pixel.copy_from_slice(&to_rgba8(&data[index..index + bytes_per_pixel], format));
+        }
+
+        Self {
+            width,
+            height,
+            data: pixels,
+        }
+    }
+}
+
+// Convert a color to a single pixel in the given format. Only the first `format.pixel_depth_bytes()` bytes are used.
+fn from_color(color: Color, format: FramebufferFormat) -> [u8; 4] {
+    // Reduce an 8 bits channel to its `bits` highest bits.
+    fn reduce(value: u8, bits: u32) -> u16 {
+        u16::from(value) >> (8 - bits)
+    }
+
+    let Color { r, g, b, a } = color;
+
+    let value = match format {
+        FramebufferFormat::Rgba8 => return [a, b, g, r],
+        FramebufferFormat::Bgr8 => return [b, g, r, 0],
+        FramebufferFormat::Rgb565 => reduce(r, 5) << 11 | reduce(g, 6) << 5 | reduce(b, 5),
+        FramebufferFormat::Rgb5A1 => {
+            reduce(r, 5) << 11 | reduce(g, 5) << 6 | reduce(b, 5) << 1 | reduce(a, 1)
+        }
+        FramebufferFormat::Rgba4 => {
+            reduce(r, 4) << 12 | reduce(g, 4) << 8 | reduce(b, 4) << 4 | reduce(a, 4)
+        }
+    };
+
+    let [low, high] = value.to_le_bytes();
+    [low, high, 0, 0]
+}
+
+// Convert a single pixel to RGBA8. Every format is stored as a little-endian integer with the red channel in the highest bits.
+fn to_rgba8(pixel: &[u8], format: FramebufferFormat) -> [u8; 4] {
+    // Scale a channel of `bits` bits to 8 bits, so that its maximum maps to 255.
+    fn expand(value: u16, bits: u32) -> u8 {
+        ((u32::from(value) * 255) / ((1 << bits) - 1)) as u8
+    }
+
+    match format {
+        FramebufferFormat::Rgba8 => [pixel[3], pixel[2], pixel[1], pixel[0]],
+        FramebufferFormat::Bgr8 => [pixel[2], pixel[1], pixel[0], 0xFF],
+        _ => {
+            let value = u16::from_le_bytes([pixel[0], pixel[1]]);
+
+            match format {
+                FramebufferFormat::Rgb565 => [
+                    expand(value >> 11, 5),
+                    expand((value >> 5) & 0x3F, 6),
+                    expand(value & 0x1F, 5),
+                    0xFF,
+                ],
+                FramebufferFormat::Rgb5A1 => [
+                    expand(value >> 11, 5),
+                    expand((value >> 6) & 0x1F, 5),
+                    expand((value >> 1) & 0x1F, 5),
+                    expand(value & 1, 1),
+                ],
+                _ => [
+                    expand(value >> 12, 4),
+                    expand((value >> 8) & 0xF, 4),
+                    expand((value >> 4) & 0xF, 4),
+                    expand(value & 0xF, 4),
+                ],
+            }
+        }
+    }
 }
 
 /// The top LCD screen.
@@ -94,6 +268,7 @@ pub trait Swap: Sealed {
     /// Even if double buffering is disabled, "swapping" the buffers has the side effect
     /// of committing any configuration changes to the buffers (e.g. [`TopScreen::set_wide_mode()`],
     /// [`Screen::set_framebuffer_format()`], [`Swap::set_double_buffering()`]), so it should still be used.
+    /// Other than that, it doesn't change which framebuffer is displayed, nor its contents.
     ///
     /// This should be called once per frame at most.
     #[doc(alias = "gfxScreenSwapBuffers")]
@@ -101,11 +276,44 @@ pub trait Swap: Sealed {
 
     /// Set whether to use double buffering.
     ///
+    /// With double buffering disabled, data is drawn directly to the displayed framebuffer.
+    /// This is useful for static interfaces which are only redrawn in parts, when something changes.
+    ///
     /// # Notes
     ///
-    /// Double buffering is enabled by default.
-    /// [`Swap::swap_buffers`] must be called after this function for the configuration
-    /// change to take effect.
+    /// Double buffering is enabled by default. Like in `libctru`, the change takes effect right away:
+    /// with double buffering disabled, [`Screen::raw_framebuffer()`] returns the displayed framebuffer from now on,
+    /// and [`Swap::swap_buffers`] stops alternating between the two framebuffers.
+    ///
+    /// A [`Console`](crate::console::Console) disables double buffering on its screen.
+    ///
+    /// Data written to the framebuffer still needs to be flushed (see [`Flush::flush_buffers()`]) to be displayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Flush, Gfx, Screen, Swap};
+    ///
+    /// let gfx = Gfx::new()?;
+    /// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+    ///
+    /// bottom_screen.set_double_buffering(false);
+    ///
+    /// // Draw the background once...
+    /// bottom_screen.raw_framebuffer().as_mut_slice().fill(0x20);
+    /// bottom_screen.flush_buffers();
+    ///
+    /// // ...and update only a part of the screen later on, without redrawing everything.
+    /// bottom_screen.raw_framebuffer().as_mut_slice()[..240 * 3].fill(0xFF);
+    /// bottom_screen.flush_buffers();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
     #[doc(alias = "gfxSetDoubleBuffering")]
     fn set_double_buffering(&mut self, enabled: bool);
 }
@@ -115,22 +323,27 @@ impl Swap for TopScreen3D<'_> {
         unsafe {
             ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_TOP, true);
         }
+        with_swap_state(ctru_sys::GFX_TOP, SwapState::swap);
     }
 
     fn set_double_buffering(&mut self, enabled: bool) {
         unsafe { ctru_sys::gfxSetDoubleBuffering(ctru_sys::GFX_TOP, enabled) }
+        with_swap_state(ctru_sys::GFX_TOP, |state| state.double_buffering = enabled);
     }
 }
 
 impl Swap for TopScreen {
     fn swap_buffers(&mut self) {
+        // Both sides are presented if 3D was enabled with `TopScreen::set_3d_enabled()`.
         unsafe {
-            ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_TOP, false);
+            ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_TOP, ctru_sys::gfxIs3D());
         }
+        with_swap_state(ctru_sys::GFX_TOP, SwapState::swap);
     }
 
     fn set_double_buffering(&mut self, enabled: bool) {
         unsafe { ctru_sys::gfxSetDoubleBuffering(ctru_sys::GFX_TOP, enabled) }
+        with_swap_state(ctru_sys::GFX_TOP, |state| state.double_buffering = enabled);
     }
 }
 
@@ -139,10 +352,14 @@ impl Swap for BottomScreen {
         unsafe {
             ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_BOTTOM, false);
         }
+        with_swap_state(ctru_sys::GFX_BOTTOM, SwapState::swap);
     }
 
     fn set_double_buffering(&mut self, enabled: bool) {
         unsafe { ctru_sys::gfxSetDoubleBuffering(ctru_sys::GFX_BOTTOM, enabled) }
+        with_swap_state(ctru_sys::GFX_BOTTOM, |state| {
+            state.double_buffering = enabled
+        });
     }
 }
 
@@ -159,15 +376,8 @@ pub trait Flush: Sealed {
 
 impl<S: Screen> Flush for S {
     fn flush_buffers(&mut self) {
-        let framebuffer = self.raw_framebuffer();
-
-        // Flush the data array. `self.raw_framebuffer` should get the correct parameters for all kinds of screens
-        let _ = unsafe {
-            ctru_sys::GSPGPU_FlushDataCache(
-                framebuffer.ptr.cast(),
-                (framebuffer.height * framebuffer.width) as u32,
-            )
-        };
+        // `self.raw_framebuffer` should get the correct parameters for all kinds of screens
+        self.raw_framebuffer().flush();
     }
 }
 
@@ -202,6 +412,15 @@ pub struct BottomScreen;
 ///
 /// The inner pointer is only valid for one frame if double
 /// buffering is enabled. Data written to `ptr` will be rendered to the screen.
+///
+/// With the `debug-framebuffers` feature, using a framebuffer obtained before the last swap of its screen
+/// (which is only possible by extending its lifetime with unsafe code) triggers a debug assertion.
+///
+/// # Notes
+///
+/// The screens are mounted rotated by 90° in the console, so the framebuffers are stored in column-major order
+/// starting from the bottom-left corner: `width` is the *vertical* size of the screen (240 pixels),
+/// while `height` is the horizontal one (400 pixels for the top screen, 320 for the bottom one).
 #[derive(Debug)]
 pub struct RawFrameBuffer<'screen> {
     /// Pointer to graphics data to be rendered.
@@ -210,8 +429,288 @@ pub struct RawFrameBuffer<'screen> {
     pub width: usize,
     /// The height of the framebuffer in pixels.
     pub height: usize,
+    /// The pixel format of the framebuffer.
+    pub format: FramebufferFormat,
     /// Keep a mutable reference to the Screen for which this framebuffer is tied.
     screen: PhantomData<&'screen mut dyn Screen>,
+    buffer_index: u8,
+    #[cfg(feature = "debug-framebuffers")]
+    screen_id: ctru_sys::gfxScreen_t,
+    #[cfg(feature = "debug-framebuffers")]
+    generation: u32,
+}
+
+impl RawFrameBuffer<'_> {
+    // Wrap a framebuffer of the given screen, as returned by `gfxGetFramebuffer`.
+    fn new(
+        screen_id: ctru_sys::gfxScreen_t,
+        ptr: *mut u8,
+        width: usize,
+        height: usize,
+        format: FramebufferFormat,
+    ) -> Self {
+        let state = with_swap_state(screen_id, |state| *state);
+
+        Self {
+            ptr,
+            width,
+            height,
+            format,
+            screen: PhantomData,
+            buffer_index: state.back_buffer(),
+            #[cfg(feature = "debug-framebuffers")]
+            screen_id,
+            #[cfg(feature = "debug-framebuffers")]
+            generation: state.generation,
+        }
+    }
+
+    /// Returns which of the two framebuffers of the screen (`0` or `1`) this is.
+    ///
+    /// With double buffering enabled, the index alternates after each [`Swap::swap_buffers()`].
+    /// Otherwise, it stays the same, since the displayed framebuffer is always the one being drawn to.
+    pub fn buffer_index(&self) -> u8 {
+        self.buffer_index
+    }
+
+    /// Returns the framebuffer's data, `width * height * format.pixel_depth_bytes()` bytes long.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Flush, Gfx, Screen};
+    ///
+    /// let gfx = Gfx::new()?;
+    /// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+    ///
+    /// // Paint the whole screen white.
+    /// bottom_screen.raw_framebuffer().as_mut_slice().fill(0xFF);
+    /// bottom_screen.flush_buffers();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        #[cfg(feature = "debug-framebuffers")]
+        self.check_generation();
+
+        // Safety: `libctru` allocates framebuffers big enough for the screen in the current format,
+        // and the screen stays mutably borrowed as long as this slice is alive.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.size()) }
+    }
+
+    /// Flushes the framebuffer's data from the CPU cache, so that it can be displayed.
+    ///
+    /// This is useful when the framebuffer wasn't obtained through a [`Flush`] implementor,
+    /// such as the right side of the screen returned by [`TopScreen::side_framebuffer()`].
+    #[doc(alias = "GSPGPU_FlushDataCache")]
+    pub fn flush(&self) {
+        #[cfg(feature = "debug-framebuffers")]
+        self.check_generation();
+
+        // Safety: same as `as_mut_slice`, but the data is only read.
+        let data = unsafe { std::slice::from_raw_parts(self.ptr, self.size()) };
+
+        let _ = flush_data_cache(data);
+    }
+
+    /// Set the color of the pixel at `(x, y)`, in screen coordinates starting from the top-left corner.
+    ///
+    /// Pixels outside of the screen are ignored.
+    pub fn set_pixel(&mut self, x: isize, y: isize, color: Color) {
+        self.fill_rect(x, y, 1, 1, color);
+    }
+
+    /// Fill a `width` by `height` rectangle with its top-left corner at `(x, y)`, in screen coordinates.
+    ///
+    /// The parts of the rectangle outside of the screen are clipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Color, Flush, Gfx, Screen};
+    ///
+    /// let gfx = Gfx::new()?;
+    /// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+    ///
+    /// let mut frame_buffer = bottom_screen.raw_framebuffer();
+    ///
+    /// // Draw a red square in the top-left corner of the screen.
+    /// frame_buffer.fill_rect(10, 10, 50, 50, Color::rgb(0xFF, 0, 0));
+    ///
+    /// bottom_screen.flush_buffers();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fill_rect(&mut self, x: isize, y: isize, width: usize, height: usize, color: Color) {
+        let (columns, rows) = self.clip(x, y, width, height);
+        let (fb_width, bytes_per_pixel) = (self.width, self.format.pixel_depth_bytes());
+        let pixel = from_color(color, self.format);
+        let data = self.as_mut_slice();
+
+        for column in columns {
+            for row in rows.clone() {
+                let index = (column * fb_width + (fb_width - 1 - row)) * bytes_per_pixel;
+                data[index..index + bytes_per_pixel].copy_from_slice(&pixel[..bytes_per_pixel]);
+            }
+        }
+    }
+
+    /// Copy a `src_width` by `src_height` image onto the framebuffer, with its top-left corner at `(x, y)` in screen coordinates.
+    ///
+    /// `src` is stored row by row starting from the top-left corner (not rotated like the framebuffer),
+    /// with each pixel already in the framebuffer's [`format`](Self::format).
+    /// The parts of the image outside of the screen are clipped.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `src` is shorter than `src_width * src_height * format.pixel_depth_bytes()` bytes.
+    pub fn blit(&mut self, src: &[u8], src_width: usize, src_height: usize, x: isize, y: isize) {
+        let (fb_width, bytes_per_pixel) = (self.width, self.format.pixel_depth_bytes());
+
+        assert!(
+            src.len() >= src_width * src_height * bytes_per_pixel,
+            "the source image is too short for its size"
+        );
+
+        let (columns, rows) = self.clip(x, y, src_width, src_height);
+        let data = self.as_mut_slice();
+
+        for column in columns {
+            for row in rows.clone() {
+                let src_x = (column as isize - x) as usize;
+                let src_y = (row as isize - y) as usize;
+                let src_index = (src_y * src_width + src_x) * bytes_per_pixel;
+                let index = (column * fb_width + (fb_width - 1 - row)) * bytes_per_pixel;
+
+                data[index..index + bytes_per_pixel]
+                    .copy_from_slice(&src[src_index..src_index + bytes_per_pixel]);
+            }
+        }
+    }
+
+    // Ranges of screen columns and rows covered by the given rectangle, clipped to the screen.
+    fn clip(
+        &self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+    ) -> (Range<usize>, Range<usize>) {
+        fn clip_axis(start: isize, len: usize, max: usize) -> Range<usize> {
+            let max = max as isize;
+            let end = start.saturating_add_unsigned(len).clamp(0, max);
+
+            (start.clamp(0, max) as usize)..(end as usize)
+        }
+
+        // The framebuffer is rotated, so its height is the width of the screen.
+        (
+            clip_axis(x, width, self.height),
+            clip_axis(y, height, self.width),
+        )
+    }
+
+    // Size of the framebuffer in bytes.
+    fn size(&self) -> usize {
+        self.width * self.height * self.format.pixel_depth_bytes()
+    }
+
+    // Detect accesses through a framebuffer that outlived a swap of its screen.
+    #[cfg(feature = "debug-framebuffers")]
+    fn check_generation(&self) {
+        debug_assert_eq!(
+            self.generation,
+            with_swap_state(self.screen_id, |state| state.generation),
+            "framebuffer used after its screen swapped buffers, get a new one with `Screen::raw_framebuffer()`"
+        );
+    }
+}
+
+// Bookkeeping of the swaps of a screen, to tell its two framebuffers apart.
+#[derive(Copy, Clone, Debug)]
+struct SwapState {
+    // Number of swaps since the service was initialized.
+    generation: u32,
+    // Index of the framebuffer being displayed.
+    displayed: u8,
+    // Applied right away, since `libctru` uses it both to get the framebuffer and to swap.
+    double_buffering: bool,
+}
+
+impl SwapState {
+    const fn new() -> Self {
+        Self {
+            generation: 0,
+            displayed: 0,
+            double_buffering: true,
+        }
+    }
+
+    // Index of the framebuffer returned by `gfxGetFramebuffer`.
+    fn back_buffer(&self) -> u8 {
+        if self.double_buffering {
+            self.displayed ^ 1
+        } else {
+            self.displayed
+        }
+    }
+
+    fn swap(&mut self) {
+        self.displayed = self.back_buffer();
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+// Indexed by `gfxScreen_t`.
+static SWAP_STATES: Mutex<[SwapState; 2]> = Mutex::new([SwapState::new(); 2]);
+
+fn with_swap_state<T>(screen: ctru_sys::gfxScreen_t, f: impl FnOnce(&mut SwapState) -> T) -> T {
+    let mut states = SWAP_STATES.lock().unwrap_or_else(PoisonError::into_inner);
+
+    f(&mut states[usize::from(screen)])
+}
+
+// Keep track of the changes made by `consoleInit`, which disables double buffering on the console's screen and swaps its buffers.
+pub(crate) fn sync_console_init(screen: ctru_sys::gfxScreen_t) {
+    with_swap_state(screen, |state| {
+        state.double_buffering = false;
+        state.swap();
+    });
+}
+
+/// Color used by the drawing functions of [`RawFrameBuffer`], converted to the framebuffer's format when drawing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// The red channel.
+    pub r: u8,
+    /// The green channel.
+    pub g: u8,
+    /// The blue channel.
+    pub b: u8,
+    /// The alpha channel. It's ignored by formats without transparency.
+    pub a: u8,
+}
+
+impl Color {
+    /// Build a new opaque color.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, 0xFF)
+    }
+
+    /// Build a new color with the given transparency.
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
 }
 
 /// Side of the [`TopScreen`]'s framebuffer.
@@ -227,14 +726,73 @@ pub enum Side {
     Right = ctru_sys::GFX_RIGHT,
 }
 
+/// Error enum for generic errors within the [`Gfx`] service.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Wide mode isn't supported by the console (Old 2DS models).
+    WideModeUnsupported,
+    /// Wide mode can't be enabled while stereoscopic 3D is active.
+    Stereoscopic3DActive,
+    /// The right side of the top screen can't be used while stereoscopic 3D is disabled.
+    Stereoscopic3DDisabled,
+}
+
 /// Handle to the GFX service.
 ///
 /// This service is a wrapper around the lower-level [GSPGPU](crate::services::gspgpu) service that
 /// provides helper functions and utilities for software rendering.
+///
+/// Each screen is flushed ([`Flush::flush_buffers()`]) and swapped ([`Swap::swap_buffers()`]) on its own,
+/// so they can be redrawn at different rates. Since drawing to a screen requires borrowing it mutably,
+/// two parts of the application can't write to the same framebuffer at once.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::gfx::{Flush, Gfx, Screen, Swap};
+/// use ctru::services::hid::{Hid, KeyPad};
+///
+/// let gfx = Gfx::new()?;
+/// let mut hid = Hid::new()?;
+///
+/// let mut top_screen = gfx.top_screen.borrow_mut();
+/// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+///
+/// # for _ in 0..2 {
+/// hid.scan_input();
+///
+/// // The top screen is redrawn every frame...
+/// top_screen.raw_framebuffer().as_mut_slice().fill(0);
+/// top_screen.flush_buffers();
+/// top_screen.swap_buffers();
+///
+/// // ...while the bottom screen is only redrawn when it's touched.
+/// if hid.keys_down().contains(KeyPad::TOUCH) {
+///     bottom_screen.raw_framebuffer().as_mut_slice().fill(0xFF);
+///     bottom_screen.flush_buffers();
+///     bottom_screen.swap_buffers();
+/// }
+///
+/// gfx.wait_for_vblank();
+/// # }
+/// #
+/// # Ok(())
+/// # }
+/// ```
 pub struct Gfx {
     /// Top screen representation.
+    ///
+    /// Each screen only exists once, and can be mutably borrowed by a single user at a time
+    /// (such as a [`Console`](crate::console::Console) or a [`RawFrameBuffer`]).
     pub top_screen: RefCell<TopScreen>,
     /// Bottom screen representation.
+    ///
+    /// Look at [`Gfx::top_screen`] for more information.
     pub bottom_screen: RefCell<BottomScreen>,
     _service_handler: ServiceReference,
 }
@@ -284,6 +842,33 @@ impl Gfx {
         Gfx::with_formats_shared(FramebufferFormat::Bgr8, FramebufferFormat::Bgr8)
     }
 
+    /// Returns a [`GfxBuilder`] to choose the framebuffer formats, their placement in memory and the initial wide mode setting.
+    ///
+    /// The builder starts with the same configuration used by [`Gfx::new()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::Gfx;
+    /// use ctru::services::gspgpu::FramebufferFormat;
+    ///
+    /// // 16-bit framebuffers take up less LINEAR memory than the default 24-bit ones.
+    /// let gfx = Gfx::builder()
+    ///     .top_format(FramebufferFormat::Rgb565)
+    ///     .bottom_format(FramebufferFormat::Rgb565)
+    ///     .build()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> GfxBuilder {
+        GfxBuilder::default()
+    }
+
     /// Initialize a new service handle with the chosen framebuffer formats on the HEAP for the top and bottom screens.
     ///
     /// Use [`Gfx::new()`] instead of this function to initialize the module with default parameters
@@ -361,9 +946,15 @@ impl Gfx {
             || unsafe {
                 ctru_sys::gfxInit(top_fb_fmt.into(), bottom_fb_fmt.into(), vram_buffer);
 
+                *SWAP_STATES.lock().unwrap_or_else(PoisonError::into_inner) = [SwapState::new(); 2];
+
                 Ok(())
             },
-            || unsafe { ctru_sys::gfxExit() },
+            || {
+                clear_all_event_callbacks();
+
+                unsafe { ctru_sys::gfxExit() }
+            },
         )?;
 
         Ok(Self {
@@ -406,6 +997,165 @@ impl Gfx {
     }
 }
 
+/// Builder for a [`Gfx`] service handle, obtained with [`Gfx::builder()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GfxBuilder {
+    top_format: FramebufferFormat,
+    bottom_format: FramebufferFormat,
+    vram_buffers: bool,
+    wide_mode: bool,
+}
+
+impl GfxBuilder {
+    /// Set the framebuffer format of the top screen. Defaults to [`FramebufferFormat::Bgr8`].
+    pub fn top_format(mut self, format: FramebufferFormat) -> Self {
+        self.top_format = format;
+        self
+    }
+
+    /// Set the framebuffer format of the bottom screen. Defaults to [`FramebufferFormat::Bgr8`].
+    pub fn bottom_format(mut self, format: FramebufferFormat) -> Self {
+        self.bottom_format = format;
+        self
+    }
+
+    /// Choose whether the framebuffers are allocated in VRAM instead of the LINEAR memory. Defaults to `false`.
+    ///
+    /// # Safety
+    ///
+    /// The same restrictions as [`Gfx::with_formats_vram()`] apply when the framebuffers are in VRAM:
+    /// functionality that relies on CPU manipulation of the framebuffers (such as [`Screen::raw_framebuffer()`],
+    /// [`Screen::capture()`] and [`Console`](crate::console::Console)) must not be used.
+    pub unsafe fn vram_buffers(mut self, enabled: bool) -> Self {
+        self.vram_buffers = enabled;
+        self
+    }
+
+    /// Choose whether the top screen starts in wide mode. Defaults to `false`.
+    ///
+    /// See [`TopScreen::set_wide_mode()`] for more information.
+    pub fn wide_mode(mut self, enabled: bool) -> Self {
+        self.wide_mode = enabled;
+        self
+    }
+
+    /// Initialize the [`Gfx`] service with the chosen configuration.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::ServiceAlreadyActive`](crate::Error::ServiceAlreadyActive) if a [`Gfx`] handle already exists,
+    /// or [`Error::Gfx`](crate::Error::Gfx) holding [`Error::WideModeUnsupported`] if wide mode was requested on a console that doesn't support it.
+    #[doc(alias = "gfxInit")]
+    pub fn build(self) -> Result<Gfx> {
+        let gfx = Gfx::with_configuration(self.top_format, self.bottom_format, self.vram_buffers)?;
+
+        if self.wide_mode {
+            let mut top_screen = gfx.top_screen.borrow_mut();
+
+            top_screen.set_wide_mode(true)?;
+            top_screen.swap_buffers();
+        }
+
+        Ok(gfx)
+    }
+}
+
+impl Default for GfxBuilder {
+    fn default() -> Self {
+        Self {
+            top_format: FramebufferFormat::Bgr8,
+            bottom_format: FramebufferFormat::Bgr8,
+            vram_buffers: false,
+            wide_mode: false,
+        }
+    }
+}
+
+/// Write `data` back from the CPU cache to memory, so that the GPU (or any other hardware reading memory directly) sees its latest contents.
+///
+/// # Errors
+///
+/// This function will return an error if `data` isn't in the LINEAR memory or VRAM (see [`crate::linear`]),
+/// or if the GSPGPU service refuses the operation. Empty slices are always accepted.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::linear::LinearAllocator;
+/// use ctru::services::gfx::{self, Gfx};
+///
+/// let _gfx = Gfx::new()?;
+///
+/// let mut vertices = Vec::with_capacity_in(3, LinearAllocator);
+/// vertices.extend_from_slice(&[[0.0_f32, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+///
+/// // Make the vertices visible to the GPU before using them.
+/// gfx::flush_data_cache(&vertices)?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "GSPGPU_FlushDataCache")]
+pub fn flush_data_cache<T>(data: &[T]) -> Result<()> {
+    if let Some((ptr, len)) = data_cache_region(data)? {
+        ResultCode(unsafe { ctru_sys::GSPGPU_FlushDataCache(ptr, len) })
+            .named("GSPGPU_FlushDataCache")?;
+    }
+
+    Ok(())
+}
+
+/// Discard the CPU cache for `data`, so that the following reads fetch the contents written by the GPU (or any other hardware writing memory directly).
+///
+/// Any change to `data` made by the CPU that wasn't written back with [`flush_data_cache()`] may be lost.
+///
+/// # Errors
+///
+/// This function will return an error if `data` isn't in the LINEAR memory or VRAM (see [`crate::linear`]),
+/// or if the GSPGPU service refuses the operation. Empty slices are always accepted.
+#[doc(alias = "GSPGPU_InvalidateDataCache")]
+pub fn invalidate_data_cache<T>(data: &mut [T]) -> Result<()> {
+    if let Some((ptr, len)) = data_cache_region(data)? {
+        ResultCode(unsafe { ctru_sys::GSPGPU_InvalidateDataCache(ptr, len) })
+            .named("GSPGPU_InvalidateDataCache")?;
+    }
+
+    Ok(())
+}
+
+// Check that `data` can be used for cache maintenance operations, and return its address and size in bytes (if it isn't empty).
+//
+// Internal Use Only.
+pub(crate) fn data_cache_region<T>(data: &[T]) -> Result<Option<(*const libc::c_void, u32)>> {
+    let size = std::mem::size_of_val(data);
+
+    if size == 0 {
+        return Ok(None);
+    }
+
+    let len = u32::try_from(size).map_err(|_| {
+        crate::Error::Other("the region is too big for a cache operation".to_string())
+    })?;
+
+    let start = data.as_ptr().cast::<u8>();
+
+    // Only the LINEAR memory and VRAM (the memory regions hardware can access directly) have a known physical address.
+    // Checking both ends is enough, since these regions are mapped contiguously.
+    let is_mapped = |ptr: *const u8| unsafe { ctru_sys::osConvertVirtToPhys(ptr.cast()) } != 0;
+
+    if !is_mapped(start) || !is_mapped(start.wrapping_add(size - 1)) {
+        return Err(crate::Error::Other(
+            "the region isn't in LINEAR memory or VRAM".to_string(),
+        ));
+    }
+
+    Ok(Some((data.as_ptr().cast(), len)))
+}
+
 impl TopScreen3D<'_> {
     /// Immutably borrow the two sides of the screen as `(left, right)`.
     pub fn split(&self) -> (Ref<TopScreenLeft>, Ref<TopScreenRight>) {
@@ -422,6 +1172,8 @@ impl TopScreen3D<'_> {
 
 /// Convert the [`TopScreen`] into a [`TopScreen3D`] and activate stereoscopic 3D.
 ///
+/// Since wide mode and stereoscopic 3D are mutually exclusive, this also disables wide mode if it was enabled.
+///
 /// # Example
 ///
 /// ```
@@ -452,6 +1204,7 @@ impl<'screen> From<&'screen RefCell<TopScreen>> for TopScreen3D<'screen> {
     #[doc(alias = "gfxSet3D")]
     fn from(top_screen: &'screen RefCell<TopScreen>) -> Self {
         unsafe {
+            ctru_sys::gfxSetWide(false);
             ctru_sys::gfxSet3D(true);
         }
 
@@ -477,17 +1230,45 @@ impl TopScreen {
 
     /// Enable or disable wide mode on the top screen.
     ///
+    /// Wide mode doubles the horizontal resolution of the top screen to 800 pixels,
+    /// which is also reflected by the size of the framebuffers returned by [`Screen::raw_framebuffer()`].
+    ///
+    /// # Errors
+    ///
+    /// Wide mode does NOT work on Old 2DS models (but still does on New 2DS XL models), so [`Error::WideModeUnsupported`] is returned when trying to enable it on one of them.
+    ///
+    /// Wide mode and stereoscopic 3D are mutually exclusive, since they both use the right side of the screen's framebuffer.
+    /// Trying to enable wide mode while a [`TopScreen3D`] is active returns [`Error::Stereoscopic3DActive`].
+    ///
     /// # Notes
     ///
     /// [`Swap::swap_buffers`] must be called after this method for the configuration
     /// to take effect.
     ///
-    /// Wide mode does NOT work on Old 2DS models (but still does on New 2DS XL models).
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{self, Gfx, Swap};
+    ///
+    /// let gfx = Gfx::new()?;
+    /// let mut top_screen = gfx.top_screen.borrow_mut();
+    ///
+    /// match top_screen.set_wide_mode(true) {
+    ///     Ok(()) => top_screen.swap_buffers(),
+    ///     Err(gfx::Error::WideModeUnsupported) => println!("This console can't use wide mode"),
+    ///     Err(e) => return Err(e.into()),
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
     #[doc(alias = "gfxSetWide")]
-    pub fn set_wide_mode(&mut self, enable: bool) {
-        unsafe {
-            ctru_sys::gfxSetWide(enable);
-        }
+    pub fn set_wide_mode(&mut self, enable: bool) -> std::result::Result<(), Error> {
+        set_wide_mode(enable)
     }
 
     /// Returns whether or not wide mode is enabled on the top screen.
@@ -495,6 +1276,75 @@ impl TopScreen {
     pub fn is_wide(&self) -> bool {
         unsafe { ctru_sys::gfxIsWide() }
     }
+
+    /// Enable or disable stereoscopic 3D on the top screen.
+    ///
+    /// Have a look at [`TopScreen3D`] for a wrapper which enables 3D for as long as it's alive,
+    /// and splits the screen between its two sides.
+    ///
+    /// # Notes
+    ///
+    /// [`Swap::swap_buffers`] must be called after this method for the configuration
+    /// to take effect.
+    ///
+    /// Since wide mode and stereoscopic 3D are mutually exclusive, enabling 3D also disables wide mode.
+    #[doc(alias = "gfxSet3D")]
+    pub fn set_3d_enabled(&mut self, enabled: bool) {
+        unsafe {
+            if enabled {
+                ctru_sys::gfxSetWide(false);
+            }
+
+            ctru_sys::gfxSet3D(enabled);
+        }
+    }
+
+    /// Returns whether or not stereoscopic 3D is enabled on the top screen.
+    #[doc(alias = "gfxIs3D")]
+    pub fn is_3d_enabled(&self) -> bool {
+        unsafe { ctru_sys::gfxIs3D() }
+    }
+
+    /// Returns a [`RawFrameBuffer`] for the chosen side of the screen.
+    ///
+    /// # Errors
+    ///
+    /// The right side is only used in stereoscopic 3D mode, so [`Error::Stereoscopic3DDisabled`] is returned when requesting it while 3D is disabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{self, Gfx, Side};
+    ///
+    /// let gfx = Gfx::new()?;
+    /// let mut top_screen = gfx.top_screen.borrow_mut();
+    ///
+    /// assert_eq!(
+    ///     top_screen.side_framebuffer(Side::Right).unwrap_err(),
+    ///     gfx::Error::Stereoscopic3DDisabled
+    /// );
+    ///
+    /// top_screen.set_3d_enabled(true);
+    /// let right_eye = top_screen.side_framebuffer(Side::Right)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "gfxGetFramebuffer")]
+    pub fn side_framebuffer(
+        &mut self,
+        side: Side,
+    ) -> std::result::Result<RawFrameBuffer<'_>, Error> {
+        match side {
+            Side::Left => Ok(self.left.raw_framebuffer()),
+            Side::Right if self.is_3d_enabled() => Ok(self.right.raw_framebuffer()),
+            Side::Right => Err(Error::Stereoscopic3DDisabled),
+        }
+    }
 }
 
 // When 3D mode is disabled, only the left side is used, so this Screen impl
@@ -541,11 +1391,63 @@ impl Screen for BottomScreen {
 
 from_impl!(Side, ctru_sys::gfx3dSide_t);
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WideModeUnsupported => write!(f, "wide mode isn't supported on Old 2DS consoles"),
+            Self::Stereoscopic3DDisabled => write!(
+                f,
+                "the right side of the top screen can't be used while stereoscopic 3D is disabled"
+            ),
+            Self::Stereoscopic3DActive => {
+                write!(
+                    f,
+                    "wide mode can't be enabled while stereoscopic 3D is active"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// Enable or disable wide mode on the top screen, for `TopScreen::set_wide_mode()` and the `Console` owning the top screen.
+pub(crate) fn set_wide_mode(enable: bool) -> std::result::Result<(), Error> {
+    if enable {
+        if unsafe { ctru_sys::gfxIs3D() } {
+            return Err(Error::Stereoscopic3DActive);
+        }
+
+        // If the model can't be read, wide mode is assumed to be supported, since most consoles do.
+        let model = Cfgu::new().and_then(|cfgu| cfgu.model());
+
+        if let Ok(SystemModel::Old2DS) = model {
+            return Err(Error::WideModeUnsupported);
+        }
+    }
+
+    unsafe {
+        ctru_sys::gfxSetWide(enable);
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Error;
 
+    #[test]
+    fn builder_defaults() {
+        let builder = Gfx::builder();
+
+        assert_eq!(builder.top_format, FramebufferFormat::Bgr8);
+        assert_eq!(builder.bottom_format, FramebufferFormat::Bgr8);
+        assert!(!builder.vram_buffers);
+        assert!(!builder.wide_mode);
+    }
+
     #[test]
     fn gfx_duplicate() {
         // NOTE: this is expected to fail if using the console test runner, since
@@ -554,4 +1456,184 @@ mod tests {
 
         assert!(matches!(Gfx::new(), Err(Error::ServiceAlreadyActive)));
     }
+
+    // Build a framebuffer over a buffer in memory, `width` pixels tall and `height` pixels wide on the screen.
+    fn test_framebuffer(data: &mut [u8], width: usize, height: usize) -> RawFrameBuffer {
+        assert_eq!(data.len(), width * height * 3);
+
+        RawFrameBuffer::new(
+            ctru_sys::GFX_BOTTOM,
+            data.as_mut_ptr(),
+            width,
+            height,
+            FramebufferFormat::Bgr8,
+        )
+    }
+
+    #[test]
+    fn swap_buffer_indices() {
+        let mut state = SwapState::new();
+        assert_eq!(state.back_buffer(), 1);
+
+        state.swap();
+        assert_eq!(state.back_buffer(), 0);
+
+        // Disabling double buffering takes effect right away: the displayed framebuffer is drawn to from now on.
+        state.double_buffering = false;
+        assert_eq!((state.displayed, state.back_buffer()), (0, 0));
+
+        // Swaps keep displaying the same framebuffer.
+        state.swap();
+        assert_eq!(
+            (state.displayed, state.back_buffer(), state.generation),
+            (0, 0, 2)
+        );
+
+        // Enabling it again draws to the other framebuffer, which is displayed by the next swap.
+        state.double_buffering = true;
+        assert_eq!(state.back_buffer(), 1);
+
+        state.swap();
+        assert_eq!((state.displayed, state.back_buffer()), (1, 0));
+    }
+
+    #[test]
+    fn fill_rect_clipping() {
+        let mut data = [0; 2 * 3 * 3];
+        let mut frame_buffer = test_framebuffer(&mut data, 2, 3);
+
+        // Only the bottom-right corner of the screen is covered.
+        frame_buffer.fill_rect(2, 1, 5, 5, Color::rgb(0x11, 0x22, 0x33));
+        // Entirely outside of the screen.
+        frame_buffer.fill_rect(-4, 0, 4, 2, Color::rgb(0xFF, 0xFF, 0xFF));
+        frame_buffer.set_pixel(0, 2, Color::rgb(0xFF, 0xFF, 0xFF));
+
+        // The screen pixel at (2, 1) is the first one of the last column in memory.
+        assert_eq!(
+            data,
+            [[0; 3], [0; 3], [0; 3], [0; 3], [0x33, 0x22, 0x11], [0; 3]].concat()
+        );
+    }
+
+    #[test]
+    fn blit_rotation() {
+        let mut data = [0; 2 * 3 * 3];
+        let mut frame_buffer = test_framebuffer(&mut data, 2, 3);
+
+        // A 2x2 image, each pixel holding its position in the image as `[B, G, R] = [0, y, x]`.
+        let image = [[0, 0, 0], [0, 0, 1], [0, 1, 0], [0, 1, 1]].concat();
+
+        // The left column of the image is clipped.
+        frame_buffer.blit(&image, 2, 2, -1, 0);
+
+        assert_eq!(
+            data,
+            [[0, 1, 1], [0, 0, 1], [0; 3], [0; 3], [0; 3], [0; 3]].concat()
+        );
+    }
+
+    #[test]
+    fn color_formats() {
+        let color = Color::rgba(0xFF, 0x00, 0xFF, 0x00);
+        let formats = [
+            FramebufferFormat::Rgba8,
+            FramebufferFormat::Bgr8,
+            FramebufferFormat::Rgb565,
+            FramebufferFormat::Rgb5A1,
+            FramebufferFormat::Rgba4,
+        ];
+
+        for format in formats {
+            let pixel = from_color(color, format);
+            let alpha = match format {
+                FramebufferFormat::Bgr8 | FramebufferFormat::Rgb565 => 0xFF,
+                _ => 0x00,
+            };
+
+            assert_eq!(
+                to_rgba8(&pixel[..format.pixel_depth_bytes()], format),
+                [0xFF, 0x00, 0xFF, alpha],
+                "{format:?}"
+            );
+        }
+
+        assert_eq!(
+            from_color(Color::rgb(0xFF, 0x00, 0x00), FramebufferFormat::Rgb565)[..2],
+            [0x00, 0xF8]
+        );
+    }
+
+    #[test]
+    fn capture_rotation() {
+        // A 2x3 screen (3 pixels wide, 2 tall), stored as 3 columns of 2 pixels from the bottom up.
+        // Each pixel holds its screen position as `[B, G, R] = [0, y, x]`.
+        let framebuffer = [
+            [0, 1, 0],
+            [0, 0, 0],
+            [0, 1, 1],
+            [0, 0, 1],
+            [0, 1, 2],
+            [0, 0, 2],
+        ]
+        .concat();
+
+        let capture = ScreenCapture::from_framebuffer(&framebuffer, 2, 3, FramebufferFormat::Bgr8);
+
+        assert_eq!((capture.width, capture.height), (3, 2));
+        assert_eq!(
+            capture.data,
+            [
+                [0, 0, 0, 0xFF],
+                [1, 0, 0, 0xFF],
+                [2, 0, 0, 0xFF],
+                [0, 1, 0, 0xFF],
+                [1, 1, 0, 0xFF],
+                [2, 1, 0, 0xFF],
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn capture_formats() {
+        let cases: [(FramebufferFormat, &[u8], [u8; 4]); 6] = [
+            (
+                FramebufferFormat::Rgba8,
+                &[0x44, 0x33, 0x22, 0x11],
+                [0x11, 0x22, 0x33, 0x44],
+            ),
+            (
+                FramebufferFormat::Bgr8,
+                &[0x33, 0x22, 0x11],
+                [0x11, 0x22, 0x33, 0xFF],
+            ),
+            // Pure red and pure blue.
+            (
+                FramebufferFormat::Rgb565,
+                &0xF800_u16.to_le_bytes(),
+                [0xFF, 0, 0, 0xFF],
+            ),
+            (
+                FramebufferFormat::Rgb565,
+                &0x001F_u16.to_le_bytes(),
+                [0, 0, 0xFF, 0xFF],
+            ),
+            // Pure green, opaque.
+            (
+                FramebufferFormat::Rgb5A1,
+                &0x07C1_u16.to_le_bytes(),
+                [0, 0xFF, 0, 0xFF],
+            ),
+            // Half-transparent white.
+            (
+                FramebufferFormat::Rgba4,
+                &0xFFF8_u16.to_le_bytes(),
+                [0xFF, 0xFF, 0xFF, 0x88],
+            ),
+        ];
+
+        for (format, pixel, expected) in cases {
+            assert_eq!(to_rgba8(pixel, format), expected, "{format:?}");
+        }
+    }
 }
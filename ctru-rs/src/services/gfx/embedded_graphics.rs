@@ -0,0 +1,119 @@
+//! [`embedded-graphics`](https://docs.rs/embedded-graphics) integration.
+
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+use embedded_graphics_core::pixelcolor::{PixelColor, Rgb888, RgbColor};
+use embedded_graphics_core::prelude::{Dimensions, DrawTarget, OriginDimensions, Pixel, Size};
+use embedded_graphics_core::primitives::Rectangle;
+
+use super::{Color, RawFrameBuffer};
+
+/// Adapter to draw on a [`RawFrameBuffer`] with [`embedded-graphics`](https://docs.rs/embedded-graphics).
+///
+/// The target uses screen coordinates, starting from the top-left corner, regardless of the rotated layout of the framebuffer.
+/// Pixels outside of the screen are clipped.
+///
+/// The color type `C` can be any type that converts to [`Rgb888`] (such as [`Rgb565`](embedded_graphics_core::pixelcolor::Rgb565)).
+/// Colors are converted to the framebuffer's [`format`](RawFrameBuffer::format) when drawing, so using the color type
+/// that matches it will avoid losing precision.
+///
+/// # Notes
+///
+/// Just like with the [`RawFrameBuffer`] itself, the screen must be flushed after drawing for the changes to be displayed.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::gfx::{Flush, FrameBufferTarget, Gfx, Screen};
+/// use embedded_graphics::pixelcolor::Rgb888;
+/// use embedded_graphics::prelude::*;
+/// use embedded_graphics::primitives::{Circle, PrimitiveStyle};
+///
+/// let gfx = Gfx::new()?;
+/// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+///
+/// let mut target = FrameBufferTarget::new(bottom_screen.raw_framebuffer());
+///
+/// Circle::new(Point::new(100, 60), 120)
+///     .into_styled(PrimitiveStyle::with_fill(Rgb888::RED))
+///     .draw(&mut target)?;
+///
+/// bottom_screen.flush_buffers();
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct FrameBufferTarget<'screen, C = Rgb888> {
+    frame_buffer: RawFrameBuffer<'screen>,
+    color: PhantomData<C>,
+}
+
+impl<'screen, C> FrameBufferTarget<'screen, C> {
+    /// Wrap a framebuffer to draw on it.
+    pub fn new(frame_buffer: RawFrameBuffer<'screen>) -> Self {
+        Self {
+            frame_buffer,
+            color: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped framebuffer.
+    pub fn into_inner(self) -> RawFrameBuffer<'screen> {
+        self.frame_buffer
+    }
+}
+
+impl<C> OriginDimensions for FrameBufferTarget<'_, C> {
+    fn size(&self) -> Size {
+        // The framebuffer is rotated, so its height is the width of the screen.
+        Size::new(
+            self.frame_buffer.height as u32,
+            self.frame_buffer.width as u32,
+        )
+    }
+}
+
+impl<C: PixelColor + Into<Rgb888>> DrawTarget for FrameBufferTarget<'_, C> {
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.frame_buffer
+                .set_pixel(point.x as isize, point.y as isize, to_color(color));
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.frame_buffer.fill_rect(
+            area.top_left.x as isize,
+            area.top_left.y as isize,
+            area.size.width as usize,
+            area.size.height as usize,
+            to_color(color),
+        );
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_solid(&self.bounding_box(), color)
+    }
+}
+
+// Convert any color to the one used by the drawing functions of `RawFrameBuffer`.
+fn to_color(color: impl Into<Rgb888>) -> Color {
+    let color = color.into();
+
+    Color::rgb(color.r(), color.g(), color.b())
+}
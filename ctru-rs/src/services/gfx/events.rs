@@ -0,0 +1,187 @@
+//! GSP event callbacks.
+
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use crate::services::gspgpu::Event;
+
+type EventCallback = Box<dyn FnMut() + Send>;
+
+const EVENT_COUNT: usize = ctru_sys::GSPGPU_EVENT_MAX as usize;
+
+// State of the handling of each event.
+struct EventStates {
+    // Callbacks set by the user, along with whether they should only run once.
+    callbacks: [Option<(EventCallback, bool)>; EVENT_COUNT],
+    // Number of threads waiting in `wait_for_event_timeout()`.
+    waiters: [usize; EVENT_COUNT],
+    // Number of times each event occurred while the trampoline was registered.
+    counts: [u32; EVENT_COUNT],
+}
+
+static EVENT_STATES: Mutex<EventStates> = {
+    const NONE: Option<(EventCallback, bool)> = None;
+    Mutex::new(EventStates {
+        callbacks: [NONE; EVENT_COUNT],
+        waiters: [0; EVENT_COUNT],
+        counts: [0; EVENT_COUNT],
+    })
+};
+
+// Notified by the trampoline whenever an event occurs.
+static EVENT_OCCURRED: Condvar = Condvar::new();
+
+/// Set a function to be called when a GSP event occurs, such as the end of a display transfer ([`Event::PPF`]) or a VBlank.
+///
+/// If `oneshot` is `true`, the callback is removed after running once. The new callback replaces any previously set one for the same event.
+/// Callbacks are removed when the [`Gfx`](super::Gfx) service is dropped.
+///
+/// # Notes
+///
+/// The callback is run on the GSP event thread, not on the thread which set it.
+/// Since it delays the handling of all other events (and any thread waiting for them), the callback must not block or do long-running work.
+/// For the same reason, calling [`set_event_callback()`], [`clear_event_callback()`] or [`wait_for_event_timeout()`](crate::services::gspgpu::wait_for_event_timeout)
+/// from within a callback will deadlock,
+/// and panicking inside of it will abort the program.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// use ctru::services::gfx::{self, Gfx};
+/// use ctru::services::gspgpu::Event;
+///
+/// let gfx = Gfx::new()?;
+///
+/// let vblanks = Arc::new(AtomicUsize::new(0));
+/// let vblanks_clone = Arc::clone(&vblanks);
+///
+/// // Count the VBlanks of the top screen.
+/// gfx::set_event_callback(
+///     Event::VBlank0,
+///     move || {
+///         vblanks_clone.fetch_add(1, Ordering::Relaxed);
+///     },
+///     false,
+/// );
+///
+/// gfx.wait_for_vblank();
+/// gfx.wait_for_vblank();
+///
+/// gfx::clear_event_callback(Event::VBlank0);
+///
+/// assert!(vblanks.load(Ordering::Relaxed) >= 1);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "gspSetEventCallback")]
+pub fn set_event_callback<F>(event: Event, callback: F, oneshot: bool)
+where
+    F: FnMut() + Send + 'static,
+{
+    let index = event as usize;
+
+    // Store the callback before registering the trampoline, so that it can't run without one.
+    let _previous = std::mem::replace(
+        &mut lock_event_states().callbacks[index],
+        Some((Box::new(callback), oneshot)),
+    );
+
+    // One-shot callbacks are removed by the trampoline itself, which stays registered for the threads waiting on the event.
+    register_trampoline(event);
+}
+
+/// Remove the function set with [`set_event_callback()`] for `event`, if any.
+#[doc(alias = "gspSetEventCallback")]
+pub fn clear_event_callback(event: Event) {
+    let mut states = lock_event_states();
+
+    // The trampoline is still needed by the threads waiting on the event.
+    if states.waiters[event as usize] == 0 {
+        unregister_trampoline(event);
+    }
+
+    // Take the callback out first, so that it gets dropped without holding the lock.
+    let _callback = states.callbacks[event as usize].take();
+    drop(states);
+}
+
+// Wait for the next occurrence of `event`, giving up after `timeout`. Returns whether the event occurred.
+pub(crate) fn wait_for_event_timeout(event: Event, timeout: Duration) -> bool {
+    let index = event as usize;
+    let mut states = lock_event_states();
+
+    if states.waiters[index] == 0 && states.callbacks[index].is_none() {
+        register_trampoline(event);
+    }
+
+    states.waiters[index] += 1;
+
+    let count = states.counts[index];
+    let (mut states, result) = EVENT_OCCURRED
+        .wait_timeout_while(states, timeout, |states| states.counts[index] == count)
+        .unwrap_or_else(PoisonError::into_inner);
+
+    states.waiters[index] -= 1;
+
+    if states.waiters[index] == 0 && states.callbacks[index].is_none() {
+        unregister_trampoline(event);
+    }
+
+    !result.timed_out()
+}
+
+// Remove all callbacks, when the `Gfx` service is closed.
+pub(super) fn clear_all_event_callbacks() {
+    for index in 0..EVENT_COUNT {
+        unsafe {
+            ctru_sys::gspSetEventCallback(index as _, None, std::ptr::null_mut(), false);
+        }
+    }
+
+    // Take the callbacks out first, so that they get dropped without holding the lock.
+    let _callbacks = std::mem::take(&mut lock_event_states().callbacks);
+}
+
+fn lock_event_states() -> MutexGuard<'static, EventStates> {
+    EVENT_STATES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn register_trampoline(event: Event) {
+    unsafe {
+        ctru_sys::gspSetEventCallback(
+            event.into(),
+            Some(event_callback_trampoline),
+            event as usize as *mut libc::c_void,
+            false,
+        )
+    };
+}
+
+fn unregister_trampoline(event: Event) {
+    unsafe { ctru_sys::gspSetEventCallback(event.into(), None, std::ptr::null_mut(), false) };
+}
+
+// Forwards the `libctru` callback to the Rust closure stored for the event, whose index is passed as `data`.
+unsafe extern "C" fn event_callback_trampoline(data: *mut libc::c_void) {
+    let index = data as usize;
+    let mut states = lock_event_states();
+
+    if let Some((callback, oneshot)) = states.callbacks[index].as_mut() {
+        callback();
+
+        if *oneshot {
+            states.callbacks[index] = None;
+        }
+    }
+
+    states.counts[index] = states.counts[index].wrapping_add(1);
+    EVENT_OCCURRED.notify_all();
+}
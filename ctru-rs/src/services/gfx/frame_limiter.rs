@@ -0,0 +1,180 @@
+//! Frame pacing.
+
+use std::time::Duration;
+
+use super::Gfx;
+
+// Frequency of the system tick counter (`SYSCLOCK_ARM11`).
+const TICKS_PER_SECOND: u64 = 268_111_856;
+// Time between two VBlanks, since the screens refresh at ~59.83 Hz.
+const TICKS_PER_VBLANK: u64 = 4_481_136;
+
+/// Frame pacing helper, limiting the main loop to the refresh rate of the screens (or a fraction of it)
+/// and measuring the resulting frame rate.
+///
+/// # Notes
+///
+/// Frames taking longer than the chosen interval aren't made up for: the limiter just waits for the next VBlank,
+/// so a slow frame never makes the following ones wait less (or not at all).
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::apt::Apt;
+/// use ctru::services::gfx::{FrameLimiter, Gfx};
+///
+/// let apt = Apt::new()?;
+/// let gfx = Gfx::new()?;
+///
+/// // Run at 30 FPS.
+/// let mut limiter = FrameLimiter::with_interval(2);
+///
+/// while apt.main_loop() {
+///     // Main program logic
+///
+///     limiter.wait(&gfx);
+///
+///     if limiter.frame_count() % 30 == 0 {
+///         println!("{:.1} FPS", limiter.fps());
+///     }
+/// #   break;
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrameLimiter {
+    interval: u32,
+    frame_count: u64,
+    last_tick: Option<u64>,
+    frame_time: Duration,
+    window_start: u64,
+    window_frames: u32,
+    fps: f32,
+}
+
+impl FrameLimiter {
+    /// Returns a limiter waiting for every VBlank, for ~60 frames per second.
+    pub fn new() -> Self {
+        Self::with_interval(1)
+    }
+
+    /// Returns a limiter waiting for `vblanks` VBlanks each frame, such as 2 for 30 frames per second, or 3 for 20.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `vblanks` is zero.
+    pub fn with_interval(vblanks: u32) -> Self {
+        assert!(
+            vblanks > 0,
+            "the frame interval must be at least one VBlank"
+        );
+
+        Self {
+            interval: vblanks,
+            frame_count: 0,
+            last_tick: None,
+            frame_time: Duration::ZERO,
+            window_start: 0,
+            window_frames: 0,
+            fps: 0.0,
+        }
+    }
+
+    /// Block until the end of the current frame, then update the frame statistics.
+    ///
+    /// This waits for as many VBlanks as are left in the chosen interval since the previous call, and at least one.
+    #[doc(alias = "gspWaitForVBlank")]
+    pub fn wait(&mut self, gfx: &Gfx) {
+        let elapsed = self
+            .last_tick
+            .map_or(0, |last| unsafe { ctru_sys::svcGetSystemTick() } - last);
+
+        for _ in 0..vblanks_to_wait(self.interval, elapsed) {
+            gfx.wait_for_vblank();
+        }
+
+        let now = unsafe { ctru_sys::svcGetSystemTick() };
+
+        match self.last_tick {
+            Some(last) => self.frame_time = ticks_to_duration(now - last),
+            None => self.window_start = now,
+        }
+
+        self.last_tick = Some(now);
+        self.frame_count += 1;
+        self.window_frames += 1;
+
+        // The frame rate is averaged over about a second, so that it stays readable.
+        let window = now - self.window_start;
+
+        if window >= TICKS_PER_SECOND {
+            self.fps =
+                (f64::from(self.window_frames) * TICKS_PER_SECOND as f64 / window as f64) as f32;
+            self.window_start = now;
+            self.window_frames = 0;
+        }
+    }
+
+    /// Returns the number of frames per second, averaged over the last second.
+    ///
+    /// This is `0.0` until the limiter has been running for a second.
+    pub fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    /// Returns the time between the last two calls to [`FrameLimiter::wait()`].
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    /// Returns the number of frames waited for since the limiter was created.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+}
+
+impl Default for FrameLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Number of VBlanks to wait for to end a frame, `elapsed` ticks after the end of the previous one.
+fn vblanks_to_wait(interval: u32, elapsed: u64) -> u32 {
+    let elapsed_vblanks = u32::try_from(elapsed / TICKS_PER_VBLANK).unwrap_or(u32::MAX);
+
+    interval.saturating_sub(elapsed_vblanks).max(1)
+}
+
+fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_nanos((u128::from(ticks) * 1_000_000_000 / u128::from(TICKS_PER_SECOND)) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_frames() {
+        // Fast frames wait for the whole interval.
+        assert_eq!(vblanks_to_wait(3, 0), 3);
+        assert_eq!(vblanks_to_wait(3, TICKS_PER_VBLANK / 2), 3);
+        assert_eq!(vblanks_to_wait(3, TICKS_PER_VBLANK + 1), 2);
+
+        // Slow frames only wait for the next VBlank.
+        assert_eq!(vblanks_to_wait(1, TICKS_PER_VBLANK * 3), 1);
+        assert_eq!(vblanks_to_wait(2, u64::MAX), 1);
+    }
+
+    #[test]
+    fn tick_conversion() {
+        assert_eq!(ticks_to_duration(TICKS_PER_SECOND), Duration::from_secs(1));
+        assert_eq!(ticks_to_duration(TICKS_PER_VBLANK).as_micros(), 16_713);
+    }
+}
@@ -0,0 +1,439 @@
+//! GPU memory transfers.
+
+use std::marker::PhantomData;
+
+use super::{data_cache_region, flush_data_cache, invalidate_data_cache, Gfx, RawFrameBuffer};
+use crate::error::{Result, ResultCode};
+use crate::services::gspgpu::{self, FramebufferFormat};
+
+/// Image in GPU-accessible memory, used as the source or destination of the transfers performed by [`Gfx`].
+///
+/// Just like [`RawFrameBuffer`], `width` and `height` describe the buffer's memory layout:
+/// a framebuffer of the top screen is 240 pixels wide and 400 pixels high.
+#[derive(Debug)]
+pub struct GxBuffer<'a> {
+    data: &'a mut [u8],
+    width: u16,
+    height: u16,
+    format: FramebufferFormat,
+}
+
+impl<'a> GxBuffer<'a> {
+    /// Wrap `data` as an image of the given size and format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    ///
+    /// - `width` or `height` are zero or not multiples of 8.
+    /// - `data` is too short for the image.
+    /// - `data` isn't aligned to 8 bytes.
+    /// - `data` isn't in the LINEAR memory or VRAM (see [`LinearAllocator`](crate::linear::LinearAllocator)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::linear::LinearAllocator;
+    /// use ctru::services::gfx::GxBuffer;
+    /// use ctru::services::gspgpu::FramebufferFormat;
+    ///
+    /// let mut data = LinearAllocator::try_alloc_slice_zeroed(240 * 400 * 4)?;
+    ///
+    /// let buffer = GxBuffer::new(&mut data, 240, 400, FramebufferFormat::Rgba8)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        data: &'a mut [u8],
+        width: u16,
+        height: u16,
+        format: FramebufferFormat,
+    ) -> Result<Self> {
+        if width == 0 || height == 0 || width % 8 != 0 || height % 8 != 0 {
+            return Err(invalid(
+                "the image dimensions must be non-zero multiples of 8",
+            ));
+        }
+
+        let wanted = usize::from(width) * usize::from(height) * format.pixel_depth_bytes();
+
+        if data.len() < wanted {
+            return Err(crate::Error::BufferTooShort {
+                provided: data.len(),
+                wanted,
+            });
+        }
+
+        if data.as_ptr() as usize % 8 != 0 {
+            return Err(invalid("the image data must be aligned to 8 bytes"));
+        }
+
+        // Reject memory the GPU can't access.
+        data_cache_region(data)?;
+
+        Ok(Self {
+            data,
+            width,
+            height,
+            format,
+        })
+    }
+
+    /// Use a screen's framebuffer as a transfer buffer.
+    pub fn from_framebuffer(frame_buffer: RawFrameBuffer<'a>) -> Result<Self> {
+        let size = frame_buffer.size();
+
+        // Safety: the framebuffer is borrowed for `'a`, just like the slice.
+        let data = unsafe { std::slice::from_raw_parts_mut(frame_buffer.ptr, size) };
+
+        Self::new(
+            data,
+            frame_buffer.width as u16,
+            frame_buffer.height as u16,
+            frame_buffer.format,
+        )
+    }
+
+    /// Returns the width of the image in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the height of the image in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the pixel format of the image.
+    pub fn format(&self) -> FramebufferFormat {
+        self.format
+    }
+
+    /// Returns the image's data.
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.size()]
+    }
+
+    /// Returns the image's data.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        let size = self.size();
+        &mut self.data[..size]
+    }
+
+    // Size of the image in bytes.
+    fn size(&self) -> usize {
+        usize::from(self.width) * usize::from(self.height) * self.format.pixel_depth_bytes()
+    }
+
+    // Dimensions of the image, as expected by the GX commands.
+    fn dimensions(&self) -> u32 {
+        u32::from(self.height) << 16 | u32::from(self.width)
+    }
+}
+
+/// Downscaling applied by a display transfer.
+#[doc(alias = "GX_TRANSFER_SCALE")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum TransferScaling {
+    /// No scaling.
+    #[default]
+    None = ctru_sys::GX_TRANSFER_SCALE_NO,
+    /// Halve the width of the image, averaging pairs of pixels.
+    X = ctru_sys::GX_TRANSFER_SCALE_X,
+    /// Halve both the width and the height of the image, averaging squares of 4 pixels.
+    XY = ctru_sys::GX_TRANSFER_SCALE_XY,
+}
+
+/// Options of a display transfer, see [`Gfx::display_transfer()`].
+///
+/// The input and output formats are taken from the [`GxBuffer`]s.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct TransferFlags {
+    flip_vertical: bool,
+    tiled_output: bool,
+    scaling: TransferScaling,
+}
+
+impl TransferFlags {
+    /// Returns the default options: tiled input to linear output, without flipping or scaling.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Choose whether the image is flipped vertically.
+    pub fn flip_vertical(mut self, enabled: bool) -> Self {
+        self.flip_vertical = enabled;
+        self
+    }
+
+    /// Choose whether to convert a linear image into a tiled one (such as a texture), instead of the opposite.
+    pub fn tiled_output(mut self, enabled: bool) -> Self {
+        self.tiled_output = enabled;
+        self
+    }
+
+    /// Set the downscaling of the image.
+    pub fn scaling(mut self, scaling: TransferScaling) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    // Encode the flags for `GX_DisplayTransfer`.
+    fn bits(self, input: FramebufferFormat, output: FramebufferFormat) -> u32 {
+        u32::from(self.flip_vertical)
+            | u32::from(self.tiled_output) << 1
+            | u32::from(input as u8) << 8
+            | u32::from(output as u8) << 12
+            | u32::from(self.scaling as u8) << 24
+    }
+}
+
+/// Transfer submitted to the GPU, obtained from [`Gfx::display_transfer()`] or [`Gfx::texture_copy()`].
+///
+/// The buffers used by the transfer stay borrowed until it completes.
+/// Dropping this struct waits for the transfer to complete, just like [`Transfer::wait()`].
+///
+/// Since the GPU keeps using the buffers until then, a transfer must never be leaked (see the safety notes of [`Gfx::display_transfer()`]).
+#[must_use = "dropping a transfer blocks until it completes"]
+pub struct Transfer<'a> {
+    destination: *mut [u8],
+    _buffers: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> Transfer<'a> {
+    fn new(dst: &'a mut GxBuffer) -> Self {
+        Self {
+            destination: dst.data_mut(),
+            _buffers: PhantomData,
+        }
+    }
+
+    /// Block until the transfer completes.
+    ///
+    /// # Notes
+    ///
+    /// Completion is signaled by the [`PPF`](gspgpu::Event::PPF) event, which doesn't tell transfers apart.
+    /// Waiting for the same event elsewhere while a transfer is in progress may make this function return early, or wait for the next transfer.
+    #[doc(alias = "gspWaitForPPF")]
+    pub fn wait(self) {
+        // The actual wait is in `Drop`.
+    }
+}
+
+impl Drop for Transfer<'_> {
+    fn drop(&mut self) {
+        gspgpu::wait_for_event(gspgpu::Event::PPF, false);
+
+        // The CPU cache may hold data older than the result of the transfer.
+        // Safety: the destination is still mutably borrowed.
+        let _ = invalidate_data_cache(unsafe { &mut *self.destination });
+    }
+}
+
+impl Gfx {
+    /// Copy an image to another buffer with the GPU, converting its format, layout and size.
+    ///
+    /// This is most commonly used to copy a render target (which has a tiled layout) to a framebuffer.
+    /// The transfer is performed asynchronously: the returned [`Transfer`] must be waited on (or dropped) to use the buffers again.
+    /// Look at [`Gfx::display_transfer_blocking()`] for a safe alternative.
+    ///
+    /// # Safety
+    ///
+    /// The returned [`Transfer`] must be waited on or dropped, and never leaked (such as with [`std::mem::forget()`]).
+    /// Leaking it ends the borrows of `src` and `dst` while the GPU is still accessing them,
+    /// so their memory could be freed or reused before the transfer completes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    ///
+    /// - `dst` is bigger than `src` (after scaling) in either direction.
+    /// - the pixel format of `dst` uses more bytes per pixel than the one of `src`, which the GPU can't convert.
+    /// - the GSPGPU service refuses the command.
+    #[doc(alias = "GX_DisplayTransfer")]
+    pub unsafe fn display_transfer<'a>(
+        &'a self,
+        src: &'a GxBuffer,
+        dst: &'a mut GxBuffer,
+        flags: TransferFlags,
+    ) -> Result<Transfer<'a>> {
+        let (scale_x, scale_y) = match flags.scaling {
+            TransferScaling::None => (1, 1),
+            TransferScaling::X => (2, 1),
+            TransferScaling::XY => (2, 2),
+        };
+
+        if u32::from(dst.width) * scale_x > u32::from(src.width)
+            || u32::from(dst.height) * scale_y > u32::from(src.height)
+        {
+            return Err(invalid(
+                "the destination image is bigger than the scaled source image",
+            ));
+        }
+
+        if dst.format.pixel_depth_bytes() > src.format.pixel_depth_bytes() {
+            return Err(invalid(
+                "the destination format can't have more bytes per pixel than the source format",
+            ));
+        }
+
+        prepare(src, dst)?;
+
+        ResultCode(unsafe {
+            ctru_sys::GX_DisplayTransfer(
+                src.data.as_ptr() as *mut u32,
+                src.dimensions(),
+                dst.data.as_mut_ptr().cast(),
+                dst.dimensions(),
+                flags.bits(src.format, dst.format),
+            )
+        })
+        .named("GX_DisplayTransfer")?;
+
+        Ok(Transfer::new(dst))
+    }
+
+    /// Copy an image to another buffer with the GPU, and wait for the transfer to complete.
+    ///
+    /// Look at [`Gfx::display_transfer()`] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::linear::LinearAllocator;
+    /// use ctru::services::gfx::{Gfx, GxBuffer, Screen, Swap, TransferFlags};
+    /// use ctru::services::gspgpu::FramebufferFormat;
+    ///
+    /// let gfx = Gfx::builder()
+    ///     .top_format(FramebufferFormat::Rgb565)
+    ///     .build()?;
+    /// let mut top_screen = gfx.top_screen.borrow_mut();
+    ///
+    /// // A render target, usually drawn by the GPU.
+    /// let mut data = LinearAllocator::try_alloc_slice_zeroed(240 * 400 * 4)?;
+    /// let render_target = GxBuffer::new(&mut data, 240, 400, FramebufferFormat::Rgba8)?;
+    ///
+    /// // Convert the render target to the format of the framebuffer.
+    /// let mut frame_buffer = GxBuffer::from_framebuffer(top_screen.raw_framebuffer())?;
+    /// gfx.display_transfer_blocking(&render_target, &mut frame_buffer, TransferFlags::new())?;
+    ///
+    /// top_screen.swap_buffers();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "GX_DisplayTransfer")]
+    #[doc(alias = "gspWaitForPPF")]
+    pub fn display_transfer_blocking(
+        &self,
+        src: &GxBuffer,
+        dst: &mut GxBuffer,
+        flags: TransferFlags,
+    ) -> Result<()> {
+        // Safety: the transfer is waited on right away.
+        unsafe { self.display_transfer(src, dst, flags) }?.wait();
+
+        Ok(())
+    }
+
+    /// Copy an image to another buffer with the GPU, without any conversion.
+    ///
+    /// The transfer is performed asynchronously: the returned [`Transfer`] must be waited on (or dropped) to use the buffers again.
+    /// Look at [`Gfx::texture_copy_blocking()`] for a safe alternative.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the images don't have the same size and format,
+    /// or if the GSPGPU service refuses the command.
+    ///
+    /// # Safety
+    ///
+    /// The returned [`Transfer`] must never be leaked, just like with [`Gfx::display_transfer()`].
+    #[doc(alias = "GX_TextureCopy")]
+    pub unsafe fn texture_copy<'a>(
+        &'a self,
+        src: &'a GxBuffer,
+        dst: &'a mut GxBuffer,
+    ) -> Result<Transfer<'a>> {
+        if (src.width, src.height, src.format) != (dst.width, dst.height, dst.format) {
+            return Err(invalid(
+                "the images of a texture copy must have the same size and format",
+            ));
+        }
+
+        prepare(src, dst)?;
+
+        // Copy blocks of 8 rows (always a multiple of 16 bytes) without gaps between them.
+        let line = u32::from(src.width) * src.format.pixel_depth_bytes() as u32 / 2;
+
+        ResultCode(unsafe {
+            ctru_sys::GX_TextureCopy(
+                src.data.as_ptr() as *mut u32,
+                line,
+                dst.data.as_mut_ptr().cast(),
+                line,
+                src.size() as u32,
+                // Raw copy.
+                1 << 3,
+            )
+        })
+        .named("GX_TextureCopy")?;
+
+        Ok(Transfer::new(dst))
+    }
+
+    /// Copy an image to another buffer with the GPU without any conversion, and wait for the transfer to complete.
+    ///
+    /// Look at [`Gfx::texture_copy()`] for more information.
+    #[doc(alias = "GX_TextureCopy")]
+    #[doc(alias = "gspWaitForPPF")]
+    pub fn texture_copy_blocking(&self, src: &GxBuffer, dst: &mut GxBuffer) -> Result<()> {
+        // Safety: the transfer is waited on right away.
+        unsafe { self.texture_copy(src, dst) }?.wait();
+
+        Ok(())
+    }
+}
+
+// Make sure the GPU reads the latest data, and that no stale cache line ends up overwriting the result.
+fn prepare(src: &GxBuffer, dst: &GxBuffer) -> Result<()> {
+    flush_data_cache(src.data())?;
+    flush_data_cache(dst.data())
+}
+
+fn invalid(message: &str) -> crate::Error {
+    crate::Error::Other(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_encoding() {
+        let flags = TransferFlags::new()
+            .flip_vertical(true)
+            .scaling(TransferScaling::XY);
+
+        assert_eq!(
+            flags.bits(FramebufferFormat::Rgba8, FramebufferFormat::Rgb565),
+            0x0200_2001
+        );
+        assert_eq!(
+            TransferFlags::new()
+                .tiled_output(true)
+                .bits(FramebufferFormat::Bgr8, FramebufferFormat::Bgr8),
+            0x0000_1102
+        );
+    }
+}
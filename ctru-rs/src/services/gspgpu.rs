@@ -1,5 +1,7 @@
 //! GSPGPU service
 
+use std::time::Duration;
+
 /// GSPGPU events that can be awaited.
 #[doc(alias = "GSPGPU_Event")]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -62,6 +64,84 @@ pub fn wait_for_event(ev: Event, discard_current: bool) {
     }
 }
 
+/// Waits for the next occurrence of a GSPGPU event, giving up after `timeout`.
+///
+/// Returns `true` if the event occurred, or `false` if the timeout expired first.
+/// Like [`wait_for_event()`] with `discard_current` set, any occurrence of the event before the call is ignored.
+///
+/// # Notes
+///
+/// Events are only delivered while the [`Gfx`](crate::services::gfx::Gfx) service is active. Without it, this function always times out.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use std::time::Duration;
+///
+/// use ctru::services::gfx::Gfx;
+/// use ctru::services::gspgpu::{self, Event};
+///
+/// let _gfx = Gfx::new()?;
+///
+/// // No command list was submitted, so the GPU won't ever finish processing one.
+/// assert!(!gspgpu::wait_for_event_timeout(Event::P3D, Duration::from_millis(50)));
+///
+/// // VBlanks happen 60 times per second.
+/// assert!(gspgpu::wait_for_event_timeout(Event::VBlank0, Duration::from_secs(1)));
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "gspSetEventCallback")]
+pub fn wait_for_event_timeout(ev: Event, timeout: Duration) -> bool {
+    crate::services::gfx::wait_for_event_timeout(ev, timeout)
+}
+
+/// Waits for any GSPGPU event to occur, and returns which one it was.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::gfx::Gfx;
+/// use ctru::services::gspgpu::{self, Event};
+///
+/// let _gfx = Gfx::new()?;
+///
+/// // VBlank events are always bound to come.
+/// while !matches!(gspgpu::wait_for_any_event(), Event::VBlank0 | Event::VBlank1) {}
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "gspWaitForAnyEvent")]
+pub fn wait_for_any_event() -> Event {
+    unsafe { ctru_sys::gspWaitForAnyEvent() }.into()
+}
+
+impl From<ctru_sys::GSPGPU_Event> for Event {
+    fn from(e: ctru_sys::GSPGPU_Event) -> Self {
+        use self::Event::*;
+        match e {
+            ctru_sys::GSPGPU_EVENT_PSC0 => Psc0,
+            ctru_sys::GSPGPU_EVENT_PSC1 => Psc1,
+            ctru_sys::GSPGPU_EVENT_VBlank0 => VBlank0,
+            ctru_sys::GSPGPU_EVENT_VBlank1 => VBlank1,
+            ctru_sys::GSPGPU_EVENT_PPF => PPF,
+            ctru_sys::GSPGPU_EVENT_P3D => P3D,
+            ctru_sys::GSPGPU_EVENT_DMA => DMA,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<ctru_sys::GSPGPU_FramebufferFormat> for FramebufferFormat {
     fn from(g: ctru_sys::GSPGPU_FramebufferFormat) -> Self {
         use self::FramebufferFormat::*;
@@ -3,22 +3,38 @@
 //! The HID service provides read access to user input such as [button presses](Hid::keys_down), [touch screen presses](Hid::touch_position),
 //! and [circle pad information](Hid::circlepad_position). It also provides information from the [volume slider](Hid::volume_slider()),
 //! the [accelerometer](Hid::accelerometer_vector()), and the [gyroscope](Hid::gyroscope_rate()).
+//! All of it can also be read at once into an [`InputSnapshot`], with [`Hid::snapshot()`].
 #![doc(alias = "input")]
 #![doc(alias = "controller")]
 #![doc(alias = "gamepad")]
 
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use crate::error::ResultCode;
 use crate::services::ServiceReference;
 
 use bitflags::bitflags;
 
+mod gesture;
+mod input_source;
+mod key_repeat;
+#[cfg(feature = "mock-input")]
+mod mock_input;
+
+pub use gesture::{Gesture, GestureRecognizer, SwipeDirection};
+pub use input_source::InputSource;
+pub use key_repeat::KeyRepeat;
+#[cfg(feature = "mock-input")]
+pub use mock_input::MockInput;
+
 static HID_ACTIVE: Mutex<()> = Mutex::new(());
 
 bitflags! {
     /// A set of flags corresponding to the button and directional pad inputs present on the 3DS.
-    #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+    ///
+    /// The ZL/ZR buttons and the C-stick of New 3DS systems are only read while an [`IrRst`](crate::services::ir_rst::IrRst) handle is alive.
+    #[derive(Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
     pub struct KeyPad: u32 {
         /// A button.
         const A             = ctru_sys::KEY_A;
@@ -80,6 +96,38 @@ bitflags! {
     }
 }
 
+/// Events signaled by the HID service when new input data is available, see [`Hid::wait_for_event()`].
+#[doc(alias = "HID_Event")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum HidEvent {
+    /// The state of the buttons, the touch screen and the circle pad was updated.
+    Pad0 = ctru_sys::HIDEVENT_PAD0,
+    /// The state of the buttons, the touch screen and the circle pad was updated (second event).
+    Pad1 = ctru_sys::HIDEVENT_PAD1,
+    /// The accelerometer readings were updated.
+    Accelerometer = ctru_sys::HIDEVENT_Accel,
+    /// The gyroscope readings were updated.
+    Gyroscope = ctru_sys::HIDEVENT_Gyro,
+    /// The state of the debug pad was updated.
+    ///
+    /// `libctru` doesn't read the keys of the debug pad (available on some development units), so only the event itself can be waited for.
+    DebugPad = ctru_sys::HIDEVENT_DebugPad,
+}
+
+/// Response curve of the circle pad, applied by [`Hid::circle_position()`] past the dead zone.
+///
+/// Have a look at [`Hid::set_circle_response_curve()`].
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResponseCurve {
+    /// The position grows linearly with the distance the circle pad is pushed.
+    #[default]
+    Linear,
+    /// The position grows with the square of the distance the circle pad is pushed,
+    /// giving finer control close to the center.
+    Squared,
+}
+
 /// Error enum for generic errors within the [`Hid`] service.
 #[non_exhaustive]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -92,8 +140,12 @@ pub enum Error {
 
 /// Representation of the acceleration vector read by the accelerometer.
 ///
+/// The values are raw readings of the sensor, in which the gravity measures about 512 (so 1 g).
+/// With the console held open in front of the player, the `x` axis goes through the sides of the console,
+/// the `y` axis is perpendicular to the bottom screen and the `z` axis goes from the hinge to the player.
+/// A console lying still on a table thus mostly reads the gravity on the `y` axis.
+///
 /// Have a look at [`Hid::set_accelerometer()`] for more information.
-#[allow(missing_docs)]
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Acceleration {
     x: i16,
@@ -101,10 +153,39 @@ pub struct Acceleration {
     z: i16,
 }
 
+impl Acceleration {
+    /// Returns an acceleration vector with the given raw values, such as to replay recorded input.
+    pub fn new(x: i16, y: i16, z: i16) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Returns the acceleration along the axis going through the sides of the console.
+    pub fn x(&self) -> i16 {
+        self.x
+    }
+
+    /// Returns the acceleration along the axis perpendicular to the bottom screen.
+    pub fn y(&self) -> i16 {
+        self.y
+    }
+
+    /// Returns the acceleration along the axis going from the hinge of the console to the player.
+    pub fn z(&self) -> i16 {
+        self.z
+    }
+}
+
 /// Representation of the angular rate read by the gyroscope.
 ///
+/// The values are raw readings of the sensor, which can be converted to degrees per second with [`AngularRate::to_dps()`].
+///
+/// # Notes
+///
+/// The readings aren't corrected in any way: a console whose gyroscope isn't (or is badly) calibrated
+/// may read a rate far from zero while lying still, or implausibly large rates.
+/// It's up to the application to detect these cases (e.g. by checking the rate while the console is supposed to be still) and handle them.
+///
 /// Have a look at [`Hid::set_gyroscope()`] for more information.
-#[allow(missing_docs)]
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct AngularRate {
     roll: i16,
@@ -112,10 +193,221 @@ pub struct AngularRate {
     yaw: i16,
 }
 
+impl AngularRate {
+    /// Returns an angular rate with the given raw values, such as to replay recorded input.
+    pub fn new(roll: i16, pitch: i16, yaw: i16) -> Self {
+        Self { roll, pitch, yaw }
+    }
+
+    /// Returns the rate of rotation around the `x` axis (see [`Acceleration`] for the axes).
+    pub fn roll(&self) -> i16 {
+        self.roll
+    }
+
+    /// Returns the rate of rotation around the `y` axis (see [`Acceleration`] for the axes).
+    pub fn pitch(&self) -> i16 {
+        self.pitch
+    }
+
+    /// Returns the rate of rotation around the `z` axis (see [`Acceleration`] for the axes).
+    pub fn yaw(&self) -> i16 {
+        self.yaw
+    }
+
+    /// Returns the angular rate as `(roll, pitch, yaw)` in degrees per second,
+    /// using the coefficient returned by [`Hid::gyroscope_coefficient()`].
+    pub fn to_dps(&self, coefficient: f32) -> (f32, f32, f32) {
+        let convert = |rate: i16| f32::from(rate) / coefficient;
+
+        (convert(self.roll), convert(self.pitch), convert(self.yaw))
+    }
+}
+
+/// Position touched on the touch screen, read with [`Hid::touch_position()`].
+///
+/// The coordinates are pixels of the bottom screen, from (0, 0) in the top-left corner to (319, 239) in the bottom-right corner.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TouchPosition {
+    x: u16,
+    y: u16,
+}
+
+impl TouchPosition {
+    /// Returns the position of the given pixel of the bottom screen, such as to replay recorded input.
+    pub fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the horizontal coordinate of the touched pixel, from 0 (left) to 319 (right).
+    pub fn x(&self) -> u16 {
+        self.x
+    }
+
+    /// Returns the vertical coordinate of the touched pixel, from 0 (top) to 239 (bottom).
+    pub fn y(&self) -> u16 {
+        self.y
+    }
+}
+
+/// Position of the circle pad, read with [`Hid::circle_position()`].
+///
+/// The `x` axis points to the right and the `y` axis points up, with (0, 0) at the center of the circle pad.
+/// Each coordinate ranges from about -156 to 156, though the exact limits slightly vary between consoles.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CirclePosition {
+    x: i16,
+    y: i16,
+}
+
+impl CirclePosition {
+    // Distance from the center of the circle pad when pushed to the edge.
+    const RADIUS: i16 = 156;
+
+    /// Returns a position of the circle pad with the given coordinates, such as to replay recorded input.
+    pub fn new(x: i16, y: i16) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the horizontal position of the circle pad, positive to the right.
+    pub fn x(&self) -> i16 {
+        self.x
+    }
+
+    /// Returns the vertical position of the circle pad, positive upwards.
+    pub fn y(&self) -> i16 {
+        self.y
+    }
+
+    /// Returns the position of the circle pad as `(x, y)`, scaled to the -1.0..=1.0 range.
+    ///
+    /// Positions past the usual edge of the circle pad are clamped.
+    pub fn as_f32(&self) -> (f32, f32) {
+        let scale = |value: i16| (f32::from(value) / f32::from(Self::RADIUS)).clamp(-1.0, 1.0);
+
+        (scale(self.x), scale(self.y))
+    }
+}
+
+/// Input of a single frame, read at once with [`Hid::snapshot()`].
+///
+/// Unlike the state read by [`Hid::keys_down()`] and the other methods of [`Hid`], which changes on every call to [`Hid::scan_input()`],
+/// a snapshot is an owned copy of the input. It can be passed around to different parts of the program,
+/// which are all sure to see the same data, whatever order they run in.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InputSnapshot {
+    keys_down: KeyPad,
+    keys_held: KeyPad,
+    keys_up: KeyPad,
+    circle_position: CirclePosition,
+    touch_position: Option<TouchPosition>,
+    acceleration: Option<Acceleration>,
+    angular_rate: Option<AngularRate>,
+}
+
+impl InputSnapshot {
+    /// Returns a snapshot in which `keys_held` are held down, following a frame in which `previous_keys_held` were.
+    ///
+    /// The keys pressed and released are deduced from the difference between the two, like the HID service does.
+    /// The other inputs are at rest, and can be set with the `with_*` methods.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ctru::services::hid::{CirclePosition, InputSnapshot, KeyPad};
+    ///
+    /// let input = InputSnapshot::new(KeyPad::A | KeyPad::B, KeyPad::B)
+    ///     .with_circle_position(CirclePosition::new(0, 100));
+    ///
+    /// assert_eq!(input.keys_down(), KeyPad::A);
+    /// assert_eq!(input.keys_up(), KeyPad::empty());
+    /// ```
+    pub fn new(keys_held: KeyPad, previous_keys_held: KeyPad) -> Self {
+        Self {
+            keys_down: keys_held - previous_keys_held,
+            keys_held,
+            keys_up: previous_keys_held - keys_held,
+            ..Self::default()
+        }
+    }
+
+    /// Returns this snapshot with the circle pad at `position`.
+    pub fn with_circle_position(self, position: CirclePosition) -> Self {
+        Self {
+            circle_position: position,
+            ..self
+        }
+    }
+
+    /// Returns this snapshot with the touch screen touched at `position`, or not touched if `None`.
+    ///
+    /// # Notes
+    ///
+    /// This doesn't change whether [`KeyPad::TOUCH`] is held, which should agree with `position`.
+    pub fn with_touch_position(self, position: Option<TouchPosition>) -> Self {
+        Self {
+            touch_position: position,
+            ..self
+        }
+    }
+
+    /// Returns this snapshot with the given accelerometer reading, or with the accelerometer disabled if `None`.
+    pub fn with_acceleration(self, acceleration: Option<Acceleration>) -> Self {
+        Self {
+            acceleration,
+            ..self
+        }
+    }
+
+    /// Returns this snapshot with the given gyroscope reading, or with the gyroscope disabled if `None`.
+    pub fn with_angular_rate(self, angular_rate: Option<AngularRate>) -> Self {
+        Self {
+            angular_rate,
+            ..self
+        }
+    }
+
+    /// Returns the keys which have just been pressed on this frame, like [`Hid::keys_down()`].
+    pub fn keys_down(&self) -> KeyPad {
+        self.keys_down
+    }
+
+    /// Returns the keys held down during this frame, like [`Hid::keys_held()`].
+    pub fn keys_held(&self) -> KeyPad {
+        self.keys_held
+    }
+
+    /// Returns the keys which have just been released on this frame, like [`Hid::keys_up()`].
+    pub fn keys_up(&self) -> KeyPad {
+        self.keys_up
+    }
+
+    /// Returns the position of the circle pad, like [`Hid::circle_position()`] (so with the dead zone and response curve applied).
+    pub fn circle_position(&self) -> CirclePosition {
+        self.circle_position
+    }
+
+    /// Returns the position touched on the bottom screen, like [`Hid::touch_position()`].
+    pub fn touch_position(&self) -> Option<TouchPosition> {
+        self.touch_position
+    }
+
+    /// Returns the acceleration vector, or `None` if the accelerometer was disabled when the snapshot was taken.
+    pub fn acceleration(&self) -> Option<Acceleration> {
+        self.acceleration
+    }
+
+    /// Returns the angular rate, or `None` if the gyroscope was disabled when the snapshot was taken.
+    pub fn angular_rate(&self) -> Option<AngularRate> {
+        self.angular_rate
+    }
+}
+
 /// Handle to the HID service.
 pub struct Hid {
     active_accelerometer: bool,
     active_gyroscope: bool,
+    circle_deadzone: i16,
+    circle_curve: ResponseCurve,
     _service_handler: ServiceReference,
 }
 
@@ -161,6 +453,8 @@ impl Hid {
         Ok(Self {
             active_accelerometer: false,
             active_gyroscope: false,
+            circle_deadzone: 0,
+            circle_curve: ResponseCurve::Linear,
             _service_handler: handler,
         })
     }
@@ -190,6 +484,170 @@ impl Hid {
         unsafe { ctru_sys::hidScanInput() };
     }
 
+    /// Scan the HID service for the input of the current frame, like [`Hid::scan_input()`], and return a copy of it.
+    ///
+    /// The accelerometer and gyroscope readings are only included if enabled with [`Hid::set_accelerometer()`] and [`Hid::set_gyroscope()`].
+    ///
+    /// # Notes
+    ///
+    /// This updates the same state as [`Hid::scan_input()`]: calling either of them more than once per frame
+    /// makes the next snapshot miss the keys pressed or released in between.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, InputSnapshot, KeyPad};
+    ///
+    /// fn update_menu(input: InputSnapshot) {
+    ///     if input.keys_down().contains(KeyPad::A) {
+    ///         println!("Selected!");
+    ///     }
+    /// }
+    ///
+    /// fn update_player(input: InputSnapshot) {
+    ///     let (speed_x, speed_y) = input.circle_position().as_f32();
+    /// }
+    ///
+    /// let mut hid = Hid::new()?;
+    ///
+    /// // Both systems see the same input, even if one of them scans for new input.
+    /// let input = hid.snapshot();
+    ///
+    /// update_menu(input);
+    /// update_player(input);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidScanInput")]
+    pub fn snapshot(&mut self) -> InputSnapshot {
+        self.scan_input();
+
+        InputSnapshot {
+            keys_down: self.keys_down(),
+            keys_held: self.keys_held(),
+            keys_up: self.keys_up(),
+            circle_position: self.circle_position(),
+            touch_position: self.touch_position(),
+            acceleration: self.accelerometer_vector().ok(),
+            angular_rate: self.gyroscope_rate().ok(),
+        }
+    }
+
+    /// Block the current thread until the HID service signals `event`.
+    ///
+    /// `discard_current` determines whether to discard the current event and wait for the next one.
+    ///
+    /// # Notes
+    ///
+    /// Waiting doesn't update the input state: [`Hid::scan_input()`] must still be called to read the new data.
+    #[doc(alias = "hidWaitForEvent")]
+    pub fn wait_for_event(&self, event: HidEvent, discard_current: bool) {
+        unsafe { ctru_sys::hidWaitForEvent(event as _, discard_current) };
+    }
+
+    /// Block the current thread until a new key is pressed, and return the keys pressed.
+    ///
+    /// Unlike calling [`Hid::scan_input()`] in a loop, the thread sleeps until the HID service has new input data.
+    ///
+    /// # Notes
+    ///
+    /// The application can't react to anything else while waiting, including being asked to close by the system
+    /// (see [`Apt::main_loop()`](crate::services::apt::Apt::main_loop)). Have a look at [`Hid::wait_for_any_key_timeout()`] to wake up regularly instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// println!("Press A to continue");
+    ///
+    /// while !hid.wait_for_any_key().contains(KeyPad::A) {}
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidWaitForEvent")]
+    pub fn wait_for_any_key(&mut self) -> KeyPad {
+        loop {
+            self.wait_for_event(HidEvent::Pad0, true);
+            self.scan_input();
+
+            let keys = self.keys_down();
+
+            if !keys.is_empty() {
+                return keys;
+            }
+        }
+    }
+
+    /// Block the current thread until a new key is pressed, giving up after `timeout`.
+    ///
+    /// Returns the keys pressed, or `None` if no key was pressed before the timeout expired.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use ctru::services::apt::Apt;
+    /// use ctru::services::hid::{Hid, KeyPad};
+    ///
+    /// let apt = Apt::new()?;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// // Check whether the application must close once a second, while waiting for START.
+    /// while apt.main_loop() {
+    ///     if let Some(keys) = hid.wait_for_any_key_timeout(Duration::from_secs(1)) {
+    ///         if keys.contains(KeyPad::START) {
+    ///             break;
+    ///         }
+    ///     }
+    /// #   break;
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidWaitForAnyEvent")]
+    pub fn wait_for_any_key_timeout(&mut self, timeout: Duration) -> Option<KeyPad> {
+        let start = Instant::now();
+
+        loop {
+            let remaining = timeout.saturating_sub(start.elapsed());
+
+            if remaining.is_zero() {
+                return None;
+            }
+
+            let remaining = i64::try_from(remaining.as_nanos()).unwrap_or(i64::MAX);
+
+            // Timing out is reported as an error, but the timeout is checked on the next iteration anyway.
+            let _ = unsafe { ctru_sys::hidWaitForAnyEvent(true, 0, remaining) };
+
+            self.scan_input();
+
+            let keys = self.keys_down();
+
+            if !keys.is_empty() {
+                return Some(keys);
+            }
+        }
+    }
+
     /// Returns a bitflag struct representing which buttons have just been pressed
     /// on the current frame (and were not pressed on the previous frame).
     ///
@@ -280,11 +738,9 @@ impl Hid {
         }
     }
 
-    /// Returns the current touch position in pixels (x, y).
-    ///
-    /// # Notes
+    /// Returns the position touched on the bottom screen, or `None` if the touch screen isn't being touched.
     ///
-    /// (0, 0) represents the top left corner of the screen.
+    /// Have a look at [`TouchPosition`] for the conventions of the coordinates.
     ///
     /// # Example
     ///
@@ -298,20 +754,30 @@ impl Hid {
     ///
     /// hid.scan_input();
     ///
-    /// let (touch_x, touch_y) = hid.touch_position();
+    /// if let Some(touch) = hid.touch_position() {
+    ///     println!("Touching ({}, {})", touch.x(), touch.y());
+    /// }
     /// #
     /// # Ok(())
     /// # }
     /// ```
     #[doc(alias = "hidTouchRead")]
-    pub fn touch_position(&self) -> (u16, u16) {
+    pub fn touch_position(&self) -> Option<TouchPosition> {
+        // The position isn't updated while the screen isn't touched, so it would be stale.
+        if !self.keys_held().contains(KeyPad::TOUCH) {
+            return None;
+        }
+
         let mut res = ctru_sys::touchPosition { px: 0, py: 0 };
 
         unsafe {
             ctru_sys::hidTouchRead(&mut res);
         }
 
-        (res.px, res.py)
+        Some(TouchPosition {
+            x: res.px,
+            y: res.py,
+        })
     }
 
     /// Returns the current circle pad position in relative (x, y).
@@ -319,6 +785,7 @@ impl Hid {
     /// # Notes
     ///
     /// (0, 0) represents the center of the circle pad.
+    /// The position is the raw reading, see [`Hid::circle_position()`] to apply the dead zone and response curve.
     ///
     /// # Example
     ///
@@ -339,19 +806,133 @@ impl Hid {
     /// ```
     #[doc(alias = "hidCircleRead")]
     pub fn circlepad_position(&self) -> (i16, i16) {
+        self.circle_position_raw().into()
+    }
+
+    /// Returns the current position of the circle pad, as updated by [`Hid::scan_input()`].
+    ///
+    /// The dead zone and response curve set with [`Hid::set_circle_deadzone()`] and [`Hid::set_circle_response_curve()`]
+    /// are applied to the position, see [`Hid::circle_position_raw()`] to read it as is.
+    /// Have a look at [`CirclePosition`] for the conventions of the axes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// // Move faster when the circle pad is pushed further.
+    /// let (speed_x, speed_y) = hid.circle_position().as_f32();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidCircleRead")]
+    pub fn circle_position(&self) -> CirclePosition {
+        process_circle(
+            self.circle_position_raw(),
+            self.circle_deadzone,
+            self.circle_curve,
+        )
+    }
+
+    /// Returns the current position of the circle pad as read by the console, ignoring the dead zone and response curve.
+    #[doc(alias = "hidCircleRead")]
+    pub fn circle_position_raw(&self) -> CirclePosition {
         let mut res = ctru_sys::circlePosition { dx: 0, dy: 0 };
 
         unsafe {
             ctru_sys::hidCircleRead(&mut res);
         }
 
-        (res.dx, res.dy)
+        CirclePosition::new(res.dx, res.dy)
+    }
+
+    /// Set the radius of the dead zone of the circle pad, applied by [`Hid::circle_position()`].
+    ///
+    /// Positions closer than `radius` to the center read as (0, 0), which keeps the slight jitter of the circle pad at rest
+    /// (usually around ±10) from being read as movement. Past the dead zone, the position is rescaled so that it still
+    /// goes smoothly from 0 to the edge of the circle pad.
+    ///
+    /// The dead zone is circular: pushing the circle pad slightly in a diagonal direction moves along both axes,
+    /// even if each coordinate alone would be in the dead zone.
+    ///
+    /// The default radius is 0, and `radius` is clamped between 0 and the edge of the circle pad.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, ResponseCurve};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.set_circle_deadzone(16);
+    /// hid.set_circle_response_curve(ResponseCurve::Squared);
+    ///
+    /// hid.scan_input();
+    ///
+    /// // Characters don't drift while the circle pad is released.
+    /// let (speed_x, speed_y) = hid.circle_position().as_f32();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_circle_deadzone(&mut self, radius: i16) {
+        self.circle_deadzone = radius.clamp(0, CirclePosition::RADIUS - 1);
+    }
+
+    /// Set the response curve of the circle pad, applied by [`Hid::circle_position()`] past the dead zone.
+    ///
+    /// The default curve is [`ResponseCurve::Linear`].
+    pub fn set_circle_response_curve(&mut self, curve: ResponseCurve) {
+        self.circle_curve = curve;
+    }
+
+    /// Returns the current position of the stereoscopic 3D slider (between 0 and 1).
+    ///
+    /// This is the same as [`os::current_3d_slider_state()`](crate::os::current_3d_slider_state), and is `0.0` when the slider is all the way down.
+    ///
+    /// # Notes
+    ///
+    /// The position is read from memory shared with the system, which is kept up to date by the system itself.
+    /// As such, it doesn't depend on [`Hid::scan_input()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let hid = Hid::new()?;
+    ///
+    /// // Skip rendering the second eye if the 3D effect is disabled.
+    /// let render_stereo = hid.slider_3d() > 0.0;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "osGet3DSliderState")]
+    pub fn slider_3d(&self) -> f32 {
+        crate::os::current_3d_slider_state()
     }
 
     /// Returns the current volume slider position (between 0 and 1).
     ///
     /// # Notes
     ///
+    /// The position is requested from the HID service on each call, so it doesn't depend on [`Hid::scan_input()`].
+    ///
     /// The [`ndsp`](crate::services::ndsp) service automatically uses the volume slider's position to handle audio mixing.
     /// As such this method should not be used to programmatically change the volume.
     ///
@@ -386,6 +967,9 @@ impl Hid {
 
     /// Activate/deactivate the console's acceleration sensor.
     ///
+    /// The sensor is deactivated when the [`Hid`] service is dropped, but should be deactivated as soon as it's no longer needed
+    /// to save battery.
+    ///
     /// # Example
     ///
     /// ```
@@ -539,6 +1123,68 @@ impl Hid {
             yaw: res.z,
         })
     }
+
+    /// Returns the coefficient converting the raw readings of the gyroscope into degrees per second.
+    ///
+    /// The raw rates are divided by this coefficient, see [`AngularRate::to_dps()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.set_gyroscope(true)?;
+    /// let coefficient = hid.gyroscope_coefficient()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let (roll, pitch, yaw) = hid.gyroscope_rate()?.to_dps(coefficient);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "HIDUSER_GetGyroscopeRawToDpsCoefficient")]
+    pub fn gyroscope_coefficient(&self) -> crate::Result<f32> {
+        let mut coefficient = 0.0;
+
+        ResultCode(unsafe { ctru_sys::HIDUSER_GetGyroscopeRawToDpsCoefficient(&mut coefficient) })?;
+
+        Ok(coefficient)
+    }
+}
+
+// Apply the dead zone and the response curve to a raw position of the circle pad.
+fn process_circle(position: CirclePosition, deadzone: i16, curve: ResponseCurve) -> CirclePosition {
+    // Keep the raw position with the default settings, even past the edge of the circle pad.
+    if deadzone == 0 && curve == ResponseCurve::Linear {
+        return position;
+    }
+
+    let (x, y) = (f32::from(position.x), f32::from(position.y));
+    let magnitude = x.hypot(y);
+    let deadzone = f32::from(deadzone);
+
+    if magnitude <= deadzone {
+        return CirclePosition::default();
+    }
+
+    // Distance past the dead zone, from 0.0 to 1.0 at the edge of the circle pad.
+    let radius = f32::from(CirclePosition::RADIUS);
+    let distance = ((magnitude - deadzone) / (radius - deadzone)).min(1.0);
+
+    let distance = match curve {
+        ResponseCurve::Linear => distance,
+        ResponseCurve::Squared => distance * distance,
+    };
+
+    let scale = distance * radius / magnitude;
+
+    CirclePosition::new((x * scale).round() as i16, (y * scale).round() as i16)
 }
 
 impl From<Acceleration> for (i16, i16, i16) {
@@ -547,12 +1193,31 @@ impl From<Acceleration> for (i16, i16, i16) {
     }
 }
 
+impl From<TouchPosition> for (u16, u16) {
+    fn from(value: TouchPosition) -> (u16, u16) {
+        (value.x, value.y)
+    }
+}
+
+impl From<CirclePosition> for (i16, i16) {
+    fn from(value: CirclePosition) -> (i16, i16) {
+        (value.x, value.y)
+    }
+}
+
 impl From<AngularRate> for (i16, i16, i16) {
     fn from(value: AngularRate) -> (i16, i16, i16) {
         (value.roll, value.pitch, value.yaw)
     }
 }
 
+/// Prints the names of the keys, such as `A | START`, or nothing if no key is set.
+impl std::fmt::Display for KeyPad {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        bitflags::parser::to_writer(self, f)
+    }
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -563,3 +1228,128 @@ impl std::fmt::Display for Error {
 }
 
 impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_bits() {
+        // Bit of each key in the HID shared memory, as documented by `libctru`.
+        let keys = [
+            (KeyPad::A, 0),
+            (KeyPad::B, 1),
+            (KeyPad::SELECT, 2),
+            (KeyPad::START, 3),
+            (KeyPad::DPAD_RIGHT, 4),
+            (KeyPad::DPAD_LEFT, 5),
+            (KeyPad::DPAD_UP, 6),
+            (KeyPad::DPAD_DOWN, 7),
+            (KeyPad::R, 8),
+            (KeyPad::L, 9),
+            (KeyPad::X, 10),
+            (KeyPad::Y, 11),
+            (KeyPad::ZL, 14),
+            (KeyPad::ZR, 15),
+            (KeyPad::TOUCH, 20),
+            (KeyPad::CSTICK_RIGHT, 24),
+            (KeyPad::CSTICK_LEFT, 25),
+            (KeyPad::CSTICK_UP, 26),
+            (KeyPad::CSTICK_DOWN, 27),
+            (KeyPad::CPAD_RIGHT, 28),
+            (KeyPad::CPAD_LEFT, 29),
+            (KeyPad::CPAD_UP, 30),
+            (KeyPad::CPAD_DOWN, 31),
+        ];
+
+        for (key, bit) in keys {
+            assert_eq!(key.bits(), 1 << bit, "{key:?}");
+        }
+
+        // All of them are covered.
+        assert_eq!(
+            keys.iter()
+                .fold(KeyPad::empty(), |all, (key, _)| all | *key),
+            KeyPad::all()
+        );
+
+        assert_eq!(KeyPad::UP.bits(), ctru_sys::KEY_UP);
+        assert_eq!(KeyPad::DOWN.bits(), ctru_sys::KEY_DOWN);
+        assert_eq!(KeyPad::LEFT.bits(), ctru_sys::KEY_LEFT);
+        assert_eq!(KeyPad::RIGHT.bits(), ctru_sys::KEY_RIGHT);
+    }
+
+    #[test]
+    fn key_names() {
+        assert_eq!((KeyPad::A | KeyPad::START).to_string(), "A | START");
+        assert_eq!(KeyPad::UP.to_string(), "DPAD_UP | CPAD_UP");
+        assert_eq!(KeyPad::empty().to_string(), "");
+
+        let pressed: Vec<KeyPad> = (KeyPad::B | KeyPad::X).iter().collect();
+        assert_eq!(pressed, [KeyPad::B, KeyPad::X]);
+    }
+
+    #[test]
+    fn circle_deadzone() {
+        let process = |x, y, deadzone| {
+            process_circle(CirclePosition::new(x, y), deadzone, ResponseCurve::Linear)
+        };
+
+        // The default settings keep the raw position.
+        assert_eq!(process(-7, 170, 0), CirclePosition::new(-7, 170));
+
+        assert_eq!(process(8, -8, 12), CirclePosition::default());
+        assert_eq!(process(156, 0, 16), CirclePosition::new(156, 0));
+
+        // The dead zone is radial, so slow diagonal movements read on both axes.
+        let diagonal = process(10, 10, 12);
+        assert!(diagonal.x() > 0 && diagonal.x() == diagonal.y());
+
+        // Positions just out of the dead zone start from the center.
+        assert_eq!(process(0, 13, 12), CirclePosition::new(0, 1));
+    }
+
+    #[test]
+    fn circle_response_curve() {
+        let squared = |x, y| process_circle(CirclePosition::new(x, y), 0, ResponseCurve::Squared);
+
+        assert_eq!(squared(78, 0), CirclePosition::new(39, 0));
+        assert_eq!(squared(0, -156), CirclePosition::new(0, -156));
+        assert_eq!(squared(0, 0), CirclePosition::default());
+
+        // Positions past the edge are brought back onto it.
+        assert_eq!(squared(200, 0), CirclePosition::new(156, 0));
+    }
+
+    #[test]
+    fn snapshot_sensors() {
+        let mut hid = Hid::new().unwrap();
+
+        let input = hid.snapshot();
+        assert_eq!(input.acceleration(), None);
+        assert_eq!(input.angular_rate(), None);
+
+        hid.set_accelerometer(true).unwrap();
+
+        let input = hid.snapshot();
+        assert!(input.acceleration().is_some());
+        assert_eq!(input.angular_rate(), None);
+
+        hid.set_accelerometer(false).unwrap();
+    }
+
+    #[test]
+    fn sliders_in_range() {
+        let hid = Hid::new().unwrap();
+
+        let slider_3d = hid.slider_3d();
+        let volume = hid.volume_slider();
+
+        assert!((0.0..=1.0).contains(&slider_3d));
+        assert!((0.0..=1.0).contains(&volume));
+
+        // Nobody moves the sliders while testing.
+        assert_eq!(hid.slider_3d(), slider_3d);
+        assert_eq!(hid.volume_slider(), volume);
+    }
+}
@@ -0,0 +1,400 @@
+//! Touch screen gestures.
+
+use std::vec::Drain;
+
+use super::TouchPosition;
+
+/// Direction of a [`Gesture::Swipe`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SwipeDirection {
+    /// Towards the left edge of the screen.
+    Left,
+    /// Towards the right edge of the screen.
+    Right,
+    /// Towards the top edge of the screen.
+    Up,
+    /// Towards the bottom edge of the screen.
+    Down,
+}
+
+/// Gesture on the touch screen, recognized by a [`GestureRecognizer`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Gesture {
+    /// The screen was touched and released quickly, without moving.
+    TapUp {
+        /// Position where the screen was released.
+        pos: TouchPosition,
+    },
+    /// A touch moved far enough from where it started to become a drag.
+    DragStart {
+        /// Position where the touch started.
+        from: TouchPosition,
+        /// Current position of the touch.
+        to: TouchPosition,
+    },
+    /// A dragging touch moved.
+    DragMove {
+        /// Position where the touch started.
+        from: TouchPosition,
+        /// Current position of the touch.
+        to: TouchPosition,
+    },
+    /// A dragging touch was released.
+    DragEnd {
+        /// Position where the touch started.
+        from: TouchPosition,
+        /// Position where the screen was released.
+        to: TouchPosition,
+    },
+    /// A dragging touch was released while moving quickly, right before the matching [`Gesture::DragEnd`].
+    Swipe {
+        /// Main direction of the movement.
+        direction: SwipeDirection,
+        /// Speed of the movement when released, in pixels per frame.
+        velocity: f32,
+    },
+}
+
+/// Recognizer of taps, drags and swipes on the touch screen, fed with the touch position of every frame.
+///
+/// # Notes
+///
+/// The first frame of each touch is ignored, since resistive screens often report an inaccurate position while the stylus is being pressed.
+/// For the same reason, touches lasting a single frame aren't recognized at all.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::apt::Apt;
+/// use ctru::services::hid::{Gesture, GestureRecognizer, Hid};
+///
+/// let apt = Apt::new()?;
+/// let mut hid = Hid::new()?;
+///
+/// let mut gestures = GestureRecognizer::new();
+///
+/// while apt.main_loop() {
+///     hid.scan_input();
+///     gestures.update(hid.touch_position());
+///
+///     for gesture in gestures.events() {
+///         match gesture {
+///             Gesture::TapUp { pos } => println!("Tapped ({}, {})", pos.x(), pos.y()),
+///             Gesture::Swipe { direction, .. } => println!("Swiped {direction:?}"),
+///             _ => {}
+///         }
+///     }
+/// #   break;
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GestureRecognizer {
+    drag_threshold: u16,
+    max_tap_frames: u32,
+    swipe_velocity: f32,
+    touch: Touch,
+    events: Vec<Gesture>,
+}
+
+// State of the current touch.
+#[derive(Copy, Clone, Debug)]
+enum Touch {
+    Released,
+    // First frame of a touch, whose position isn't reliable.
+    Pressed,
+    Held {
+        start: TouchPosition,
+        previous: TouchPosition,
+        last: TouchPosition,
+        frames: u32,
+        dragging: bool,
+    },
+}
+
+impl GestureRecognizer {
+    /// Returns a recognizer with the default thresholds: touches moving by 8 pixels become drags,
+    /// touches shorter than 20 frames (a third of a second) are taps, and drags released at 8 pixels per frame are swipes.
+    pub fn new() -> Self {
+        Self {
+            drag_threshold: 8,
+            max_tap_frames: 20,
+            swipe_velocity: 8.0,
+            touch: Touch::Released,
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns this recognizer, with touches becoming drags once they move `pixels` away from where they started.
+    pub fn with_drag_threshold(self, pixels: u16) -> Self {
+        Self {
+            drag_threshold: pixels,
+            ..self
+        }
+    }
+
+    /// Returns this recognizer, with taps lasting at most `frames` frames.
+    pub fn with_max_tap_frames(self, frames: u32) -> Self {
+        Self {
+            max_tap_frames: frames,
+            ..self
+        }
+    }
+
+    /// Returns this recognizer, with drags released at `pixels_per_frame` or faster recognized as swipes.
+    pub fn with_swipe_velocity(self, pixels_per_frame: f32) -> Self {
+        Self {
+            swipe_velocity: pixels_per_frame,
+            ..self
+        }
+    }
+
+    /// Update the state of the current touch, with the position touched during this frame.
+    ///
+    /// This must be called once per frame, usually with [`Hid::touch_position()`](super::Hid::touch_position) after calling [`Hid::scan_input()`](super::Hid::scan_input).
+    /// The recognized gestures are kept until read with [`GestureRecognizer::events()`].
+    pub fn update(&mut self, touch: Option<TouchPosition>) {
+        self.touch = match (self.touch, touch) {
+            (Touch::Released, Some(_)) => Touch::Pressed,
+            (Touch::Pressed, Some(pos)) => Touch::Held {
+                start: pos,
+                previous: pos,
+                last: pos,
+                frames: 1,
+                dragging: false,
+            },
+            (
+                Touch::Held {
+                    start,
+                    last,
+                    frames,
+                    dragging,
+                    ..
+                },
+                Some(pos),
+            ) => {
+                let starts_dragging =
+                    !dragging && distance(start, pos) >= f32::from(self.drag_threshold);
+
+                if starts_dragging {
+                    self.events.push(Gesture::DragStart {
+                        from: start,
+                        to: pos,
+                    });
+                } else if dragging && pos != last {
+                    self.events.push(Gesture::DragMove {
+                        from: start,
+                        to: pos,
+                    });
+                }
+
+                Touch::Held {
+                    start,
+                    previous: last,
+                    last: pos,
+                    frames: frames.saturating_add(1),
+                    dragging: dragging || starts_dragging,
+                }
+            }
+            (
+                Touch::Held {
+                    start,
+                    previous,
+                    last,
+                    frames,
+                    dragging,
+                },
+                None,
+            ) => {
+                if dragging {
+                    // The position of the last frame is used, since the screen doesn't report where it was released.
+                    let velocity = distance(previous, last);
+
+                    if velocity >= self.swipe_velocity {
+                        self.events.push(Gesture::Swipe {
+                            direction: swipe_direction(previous, last),
+                            velocity,
+                        });
+                    }
+
+                    self.events.push(Gesture::DragEnd {
+                        from: start,
+                        to: last,
+                    });
+                } else if frames <= self.max_tap_frames {
+                    self.events.push(Gesture::TapUp { pos: last });
+                }
+
+                Touch::Released
+            }
+            (Touch::Released | Touch::Pressed, None) => Touch::Released,
+        };
+    }
+
+    /// Returns the gestures recognized since the last call, in order.
+    ///
+    /// The gestures are removed from the recognizer as the iterator is consumed (or dropped).
+    pub fn events(&mut self) -> Drain<'_, Gesture> {
+        self.events.drain(..)
+    }
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn distance(from: TouchPosition, to: TouchPosition) -> f32 {
+    let dx = f32::from(to.x()) - f32::from(from.x());
+    let dy = f32::from(to.y()) - f32::from(from.y());
+
+    dx.hypot(dy)
+}
+
+fn swipe_direction(from: TouchPosition, to: TouchPosition) -> SwipeDirection {
+    let dx = i32::from(to.x()) - i32::from(from.x());
+    let dy = i32::from(to.y()) - i32::from(from.y());
+
+    // The vertical axis points down on the touch screen.
+    if dx.abs() >= dy.abs() {
+        if dx < 0 {
+            SwipeDirection::Left
+        } else {
+            SwipeDirection::Right
+        }
+    } else if dy < 0 {
+        SwipeDirection::Up
+    } else {
+        SwipeDirection::Down
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(x: u16, y: u16) -> Option<TouchPosition> {
+        Some(TouchPosition::new(x, y))
+    }
+
+    fn recognize(
+        recognizer: &mut GestureRecognizer,
+        frames: &[Option<TouchPosition>],
+    ) -> Vec<Gesture> {
+        frames
+            .iter()
+            .flat_map(|&frame| {
+                recognizer.update(frame);
+                recognizer.events().collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn taps() {
+        let mut recognizer = GestureRecognizer::new();
+
+        // The inaccurate first frame is ignored.
+        let gestures = recognize(
+            &mut recognizer,
+            &[touch(0, 0), touch(100, 50), touch(102, 51), None],
+        );
+        assert_eq!(
+            gestures,
+            [Gesture::TapUp {
+                pos: TouchPosition::new(102, 51)
+            }]
+        );
+
+        // Neither single frame touches nor long presses are taps.
+        assert!(recognize(&mut recognizer, &[touch(10, 10), None]).is_empty());
+
+        let mut long_press = vec![touch(10, 10); 30];
+        long_press.push(None);
+        assert!(recognize(&mut recognizer, &long_press).is_empty());
+    }
+
+    #[test]
+    fn drags() {
+        let mut recognizer = GestureRecognizer::new();
+        let from = TouchPosition::new(100, 100);
+
+        let gestures = recognize(
+            &mut recognizer,
+            &[
+                touch(0, 0),
+                touch(100, 100),
+                touch(104, 100),
+                touch(110, 100),
+                touch(110, 100),
+                touch(112, 101),
+                None,
+            ],
+        );
+
+        assert_eq!(
+            gestures,
+            [
+                Gesture::DragStart {
+                    from,
+                    to: TouchPosition::new(110, 100)
+                },
+                Gesture::DragMove {
+                    from,
+                    to: TouchPosition::new(112, 101)
+                },
+                Gesture::DragEnd {
+                    from,
+                    to: TouchPosition::new(112, 101)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn swipes() {
+        let mut recognizer = GestureRecognizer::new().with_swipe_velocity(10.0);
+
+        let gestures = recognize(
+            &mut recognizer,
+            &[
+                touch(0, 0),
+                touch(200, 120),
+                touch(200, 108),
+                touch(198, 90),
+                None,
+            ],
+        );
+
+        assert_eq!(
+            gestures[2],
+            Gesture::Swipe {
+                direction: SwipeDirection::Up,
+                velocity: distance(TouchPosition::new(200, 108), TouchPosition::new(198, 90)),
+            }
+        );
+        assert!(matches!(gestures[3], Gesture::DragEnd { .. }));
+
+        // Slow drags aren't swipes.
+        let gestures = recognize(
+            &mut recognizer,
+            &[
+                touch(0, 0),
+                touch(100, 120),
+                touch(110, 120),
+                touch(112, 120),
+                None,
+            ],
+        );
+        assert!(!gestures
+            .iter()
+            .any(|gesture| matches!(gesture, Gesture::Swipe { .. })));
+    }
+}
@@ -0,0 +1,64 @@
+//! Sources of user input.
+
+use super::{Hid, InputSnapshot};
+
+/// Source of the user input, read one frame at a time.
+///
+/// [`Hid`] reads the input of the console, but code written against this trait (such as [`Console::menu()`](crate::console::Console::menu))
+/// can also be driven by other sources, like the scripted input of a [`MockInput`](super::MockInput) in tests.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::hid::{Hid, InputSource, KeyPad};
+///
+/// // Count the frames until START is pressed, whatever the input comes from.
+/// fn frames_until_start(input: &mut impl InputSource) -> usize {
+///     let mut frames = 0;
+///
+///     while !input.is_finished() && !input.poll().keys_down().contains(KeyPad::START) {
+///         frames += 1;
+/// #       break;
+///     }
+///
+///     frames
+/// }
+///
+/// let mut hid = Hid::new()?;
+/// let frames = frames_until_start(&mut hid);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub trait InputSource {
+    /// Read the input of the next frame.
+    fn poll(&mut self) -> InputSnapshot;
+
+    /// Returns whether the source has no more input to give, in which case [`InputSource::poll()`] only returns input at rest.
+    ///
+    /// Code waiting for some input should give up once this is `true`. The input of the console never ends, so this is always `false` for [`Hid`].
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
+
+impl InputSource for Hid {
+    /// Scan the input of the console, see [`Hid::snapshot()`].
+    fn poll(&mut self) -> InputSnapshot {
+        self.snapshot()
+    }
+}
+
+impl<T: InputSource + ?Sized> InputSource for &mut T {
+    fn poll(&mut self) -> InputSnapshot {
+        (**self).poll()
+    }
+
+    fn is_finished(&self) -> bool {
+        (**self).is_finished()
+    }
+}
@@ -0,0 +1,151 @@
+//! Key repetition.
+
+use super::KeyPad;
+
+/// Key repetition helper, repeating the keys held down after a delay, like the keys of a keyboard.
+///
+/// Each key is tracked on its own, so holding a key down doesn't delay (or repeat) the presses of other keys.
+/// This includes the D-Pad and circle pad bits of the composite directions (such as [`KeyPad::DOWN`]), which are timed separately:
+/// check them with [`KeyPad::intersects()`] rather than [`KeyPad::contains()`], which requires both to repeat on the same frame.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::apt::Apt;
+/// use ctru::services::hid::{Hid, KeyPad, KeyRepeat};
+///
+/// let apt = Apt::new()?;
+/// let mut hid = Hid::new()?;
+///
+/// // Start repeating after half a second, then repeat 10 times per second.
+/// let mut repeat = KeyRepeat::new(30, 6);
+/// let mut selected: u32 = 0;
+///
+/// while apt.main_loop() {
+///     hid.scan_input();
+///     repeat.update(hid.keys_held());
+///
+///     if repeat.keys_repeating().intersects(KeyPad::DOWN) {
+///         selected += 1;
+///     }
+/// #   break;
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyRepeat {
+    delay: u32,
+    interval: u32,
+    // Number of frames each key (by bit index) has been held for.
+    held_frames: [u32; 32],
+    repeating: KeyPad,
+}
+
+impl KeyRepeat {
+    /// Returns a helper repeating the keys held for `delay` frames, then every `interval` frames.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `interval` is zero.
+    pub fn new(delay: u32, interval: u32) -> Self {
+        assert!(
+            interval > 0,
+            "the repeat interval must be at least one frame"
+        );
+
+        Self {
+            delay,
+            interval,
+            held_frames: [0; 32],
+            repeating: KeyPad::empty(),
+        }
+    }
+
+    /// Update the state of the keys, with the keys held during this frame.
+    ///
+    /// This must be called once per frame, usually with [`Hid::keys_held()`](super::Hid::keys_held) after calling [`Hid::scan_input()`](super::Hid::scan_input).
+    /// The keys can also come from the [`InputSnapshot`](super::InputSnapshot) of any [`InputSource`](super::InputSource).
+    pub fn update(&mut self, keys_held: KeyPad) {
+        self.repeating = KeyPad::empty();
+
+        for (bit, frames) in self.held_frames.iter_mut().enumerate() {
+            let key = KeyPad::from_bits_retain(1 << bit);
+
+            if !keys_held.contains(key) {
+                *frames = 0;
+                continue;
+            }
+
+            *frames = frames.saturating_add(1);
+
+            if is_repeat_frame(*frames, self.delay, self.interval) {
+                self.repeating |= key;
+            }
+        }
+    }
+
+    /// Returns the keys which were just pressed, or which are repeated during this frame.
+    pub fn keys_repeating(&self) -> KeyPad {
+        self.repeating
+    }
+}
+
+impl Default for KeyRepeat {
+    /// Returns a helper repeating the keys after half a second, then 10 times per second.
+    fn default() -> Self {
+        Self::new(30, 6)
+    }
+}
+
+// Whether a key held for `frames` frames (including this one) is repeated during this frame.
+fn is_repeat_frame(frames: u32, delay: u32, interval: u32) -> bool {
+    frames == 1 || (frames > delay && (frames - 1 - delay) % interval == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repeated_frames(repeat: &mut KeyRepeat, keys: &[KeyPad], key: KeyPad) -> Vec<usize> {
+        keys.iter()
+            .enumerate()
+            .filter_map(|(frame, &held)| {
+                repeat.update(held);
+                repeat.keys_repeating().contains(key).then_some(frame)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn repeat_timing() {
+        let mut repeat = KeyRepeat::new(3, 2);
+        let keys = [KeyPad::DPAD_DOWN; 9];
+
+        assert_eq!(
+            repeated_frames(&mut repeat, &keys, KeyPad::DPAD_DOWN),
+            [0, 3, 5, 7]
+        );
+    }
+
+    #[test]
+    fn independent_keys() {
+        let mut repeat = KeyRepeat::new(2, 1);
+        let down = KeyPad::DPAD_DOWN;
+        let both = KeyPad::DPAD_DOWN | KeyPad::A;
+
+        // Tapping A while holding Down doesn't reset Down...
+        let keys = [down, both, down, down, down];
+        assert_eq!(repeated_frames(&mut repeat, &keys, down), [0, 2, 3, 4]);
+
+        // ...which starts again from the first press once released.
+        let keys = [KeyPad::empty(), both, both, KeyPad::A];
+        assert_eq!(repeated_frames(&mut repeat.clone(), &keys, down), [1]);
+        assert_eq!(repeated_frames(&mut repeat, &keys, KeyPad::A), [1, 3]);
+    }
+}
@@ -0,0 +1,111 @@
+//! Scripted user input.
+
+use std::collections::VecDeque;
+
+use super::{InputSnapshot, InputSource, KeyPad};
+
+/// Input source replaying a scripted sequence of frames, to test code written against [`InputSource`].
+///
+/// Each call to [`InputSource::poll()`] returns the next frame of the script. Once all of them have been read,
+/// the source [is finished](InputSource::is_finished) and only returns input at rest.
+///
+/// This type is only available with the `mock-input` feature.
+///
+/// # Example
+///
+/// ```
+/// use ctru::services::hid::{InputSource, KeyPad, KeyRepeat, MockInput};
+///
+/// // Hold Down for a second.
+/// let mut input = MockInput::from_keys_held([KeyPad::DPAD_DOWN; 60]);
+/// let mut repeat = KeyRepeat::default();
+/// let mut selected = 0;
+///
+/// while !input.is_finished() {
+///     repeat.update(input.poll().keys_held());
+///
+///     if repeat.keys_repeating().contains(KeyPad::DPAD_DOWN) {
+///         selected += 1;
+///     }
+/// }
+///
+/// assert_eq!(selected, 6);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MockInput {
+    frames: VecDeque<InputSnapshot>,
+    last_keys_held: KeyPad,
+}
+
+impl MockInput {
+    /// Returns a source replaying `frames`, in order.
+    pub fn new(frames: impl IntoIterator<Item = InputSnapshot>) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+            last_keys_held: KeyPad::empty(),
+        }
+    }
+
+    /// Returns a source replaying frames in which the given keys are held down, one set of keys per frame.
+    ///
+    /// The keys pressed and released on each frame are deduced from the keys held on the previous one, see [`InputSnapshot::new()`].
+    pub fn from_keys_held(frames: impl IntoIterator<Item = KeyPad>) -> Self {
+        let mut input = Self::default();
+
+        for keys_held in frames {
+            input.push_keys_held(keys_held);
+        }
+
+        input
+    }
+
+    /// Add `frame` at the end of the script.
+    pub fn push(&mut self, frame: InputSnapshot) {
+        self.last_keys_held = frame.keys_held();
+        self.frames.push_back(frame);
+    }
+
+    /// Add a frame in which `keys_held` are held down at the end of the script, following its last frame.
+    pub fn push_keys_held(&mut self, keys_held: KeyPad) {
+        self.push(InputSnapshot::new(keys_held, self.last_keys_held));
+    }
+
+    /// Returns the number of frames left to read.
+    pub fn remaining(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl InputSource for MockInput {
+    fn poll(&mut self) -> InputSnapshot {
+        self.frames.pop_front().unwrap_or_default()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_keys() {
+        let mut input = MockInput::from_keys_held([KeyPad::A, KeyPad::A | KeyPad::B, KeyPad::B]);
+
+        assert_eq!(input.remaining(), 3);
+        assert_eq!(input.poll().keys_down(), KeyPad::A);
+
+        let frame = input.poll();
+        assert_eq!(frame.keys_down(), KeyPad::B);
+        assert_eq!(frame.keys_held(), KeyPad::A | KeyPad::B);
+
+        let frame = input.poll();
+        assert_eq!(frame.keys_up(), KeyPad::A);
+        assert!(input.is_finished());
+
+        // Past the end of the script, nothing is held anymore.
+        assert_eq!(input.poll(), InputSnapshot::default());
+    }
+}
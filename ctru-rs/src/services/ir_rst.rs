@@ -0,0 +1,113 @@
+//! IR (Infrared) Rst Service.
+//!
+//! The ir:rst service gives access to the C-stick and the ZL/ZR buttons of New 3DS (and New 2DS) systems.
+//! While an [`IrRst`] handle is alive, [`Hid::scan_input()`](crate::services::hid::Hid::scan_input) also updates their state: the ZL/ZR buttons
+//! and the C-stick directions show up in [`KeyPad`](crate::services::hid::KeyPad) like any other button,
+//! while [`IrRst::cstick_position()`] returns the analog position of the C-stick.
+//!
+//! Old 3DS systems don't have these inputs: there, either the service can't be initialized or its readings stay neutral.
+#![doc(alias = "input")]
+#![doc(alias = "controller")]
+#![doc(alias = "gamepad")]
+
+use std::sync::Mutex;
+
+use crate::error::ResultCode;
+use crate::services::hid::CirclePosition;
+use crate::services::ServiceReference;
+
+static IR_RST_ACTIVE: Mutex<()> = Mutex::new(());
+
+/// Handle to the ir:rst service.
+///
+/// # Notes
+///
+/// This service and [`IrUser`](crate::services::ir_user::IrUser) both use the same hardware, so they shouldn't be used at the same time.
+pub struct IrRst {
+    _service_handler: ServiceReference,
+}
+
+impl IrRst {
+    /// Initialize a new service handle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// use ctru::services::ir_rst::IrRst;
+    ///
+    /// let mut hid = Hid::new()?;
+    /// let ir_rst = IrRst::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// if hid.keys_held().contains(KeyPad::ZL) {
+    ///     println!("You are holding the ZL button!");
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "irrstInit")]
+    pub fn new() -> crate::Result<Self> {
+        let handler = ServiceReference::new(
+            &IR_RST_ACTIVE,
+            || {
+                ResultCode(unsafe { ctru_sys::irrstInit() })?;
+
+                Ok(())
+            },
+            || unsafe {
+                ctru_sys::irrstExit();
+            },
+        )?;
+
+        Ok(Self {
+            _service_handler: handler,
+        })
+    }
+
+    /// Returns the current position of the C-stick, as updated by [`Hid::scan_input()`](crate::services::hid::Hid::scan_input).
+    ///
+    /// The axes follow the same conventions as the circle pad (see [`CirclePosition`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// use ctru::services::ir_rst::IrRst;
+    ///
+    /// let mut hid = Hid::new()?;
+    /// let ir_rst = IrRst::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let cstick = ir_rst.cstick_position();
+    /// println!("C-stick: ({}, {})", cstick.x(), cstick.y());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "irrstCstickRead")]
+    pub fn cstick_position(&self) -> CirclePosition {
+        let mut res = ctru_sys::circlePosition { dx: 0, dy: 0 };
+
+        unsafe {
+            ctru_sys::irrstCstickRead(&mut res);
+        }
+
+        CirclePosition::new(res.dx, res.dy)
+    }
+}
@@ -19,6 +19,7 @@ pub mod fs;
 pub mod gfx;
 pub mod gspgpu;
 pub mod hid;
+pub mod ir_rst;
 pub mod ir_user;
 pub mod ndsp;
 pub mod ps;
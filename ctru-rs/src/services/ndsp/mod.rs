@@ -7,26 +7,57 @@
 //! To use NDSP audio, you will need to dump DSP firmware from a real 3DS using
 //! something like [DSP1](https://www.gamebrew.org/wiki/DSP1_3DS).
 //!
-//! `libctru` expects to find it at `sdmc:/3ds/dspfirm.cdc` when initializing the NDSP service.
+//! `libctru` expects to find it at `sdmc:/3ds/dspfirm.cdc` when initializing the NDSP service,
+//! unless the firmware is provided directly via [`Ndsp::with_component()`].
+//!
+//! Failures of the service (such as the missing firmware or a channel already in use) can be told apart as a [`ServiceError`],
+//! through [`crate::Error::service_error()`] and [`Error::service_error()`].
 #![doc(alias = "audio")]
 
 // As a result of requiring DSP firmware to initialize, all of the doctests in
 // this module are `no_run`, since Citra doesn't provide a stub for the DSP firmware:
 // https://github.com/citra-emu/citra/issues/6111
 
+pub mod stream;
+mod wav;
 pub mod wave;
-use wave::{Status, Wave};
+use wave::Wave;
 
-use crate::error::ResultCode;
+pub use crate::error::ServiceError;
+use crate::error::{codes, ResultCode};
 use crate::linear::LinearAllocation;
 use crate::services::ServiceReference;
 
-use std::cell::{RefCell, RefMut};
+use std::cell::{RefCell, RefMut, UnsafeCell};
+use std::collections::VecDeque;
 use std::error;
 use std::fmt;
-use std::sync::Mutex;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
 
 const NUMBER_OF_CHANNELS: u8 = 24;
+// Range of sample rates (in Hz) supported by the DSP.
+const SAMPLE_RATE_RANGE: std::ops::RangeInclusive<f32> = 1.0..=96000.0;
+// Rate at which the DSP outputs samples (`NDSP_SAMPLE_RATE` in `libctru`).
+const DSP_SAMPLE_RATE: f32 = 16756991. / 512.;
+// Duration of a DSP frame, made of 160 samples.
+const DSP_FRAME_DURATION: Duration = Duration::from_nanos((160. / DSP_SAMPLE_RATE * 1e9) as u64);
+// Longest delay time supported by the aux bus delay effect.
+const MAX_DELAY_TIME: Duration = Duration::from_secs(1);
+// Delays (in samples at 44.1 kHz) of the comb and all-pass filters of the aux bus reverb effect, from Freeverb.
+const REVERB_COMB_TUNING: [usize; 4] = [1116, 1188, 1277, 1356];
+const REVERB_ALL_PASS_TUNING: [usize; 2] = [556, 441];
+// Extra delay (in samples at 44.1 kHz) of the right outputs, to widen the stereo image of the reverb.
+const REVERB_STEREO_SPREAD: usize = 23;
+
+type FrameCallback = dyn FnMut() + Send;
+
+// Amount of times the queue of each channel has been cleared.
+// Since `libctru` doesn't update the status of cleared waves, this is used by `Wave`s to know whether they're still queued.
+static QUEUE_GENERATIONS: [AtomicU32; NUMBER_OF_CHANNELS as usize] =
+    [const { AtomicU32::new(0) }; NUMBER_OF_CHANNELS as usize];
 
 /// Audio output mode.
 #[doc(alias = "ndspOutputMode")]
@@ -41,7 +72,31 @@ pub enum OutputMode {
     Surround = ctru_sys::NDSP_OUTPUT_SURROUND,
 }
 
-/// PCM formats supported by the audio engine.
+/// Clipping applied to the final audio output.
+#[doc(alias = "ndspClippingMode")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ClippingMode {
+    /// Samples exceeding the output range are cut off.
+    Normal = ctru_sys::NDSP_CLIP_NORMAL,
+    /// Samples approaching the limits of the output range are smoothly compressed.
+    Soft = ctru_sys::NDSP_CLIP_SOFT,
+}
+
+/// Position of the speakers used by [`OutputMode::Surround`].
+#[doc(alias = "ndspSpeakerPos")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SpeakerPosition {
+    /// Speakers placed in a square.
+    Square = ctru_sys::NDSP_SPKPOS_SQUARE,
+    /// Speakers placed wide apart.
+    Wide = ctru_sys::NDSP_SPKPOS_WIDE,
+    /// Speakers placed next to each other.
+    Num = ctru_sys::NDSP_SPKPOS_NUM,
+}
+
+/// Audio formats supported by the audio engine.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum AudioFormat {
@@ -49,13 +104,41 @@ pub enum AudioFormat {
     PCM8Mono = ctru_sys::NDSP_FORMAT_MONO_PCM8,
     /// PCM 16bit single-channel.
     PCM16Mono = ctru_sys::NDSP_FORMAT_MONO_PCM16,
+    /// DSP-ADPCM 4bit single-channel.
+    ///
+    /// Channels playing this format need their decoding coefficients set via [`Channel::set_adpcm_coefficients()`].
+    ADPCMMono = ctru_sys::NDSP_FORMAT_MONO_ADPCM,
     /// PCM 8bit interleaved dual-channel.
     PCM8Stereo = ctru_sys::NDSP_FORMAT_STEREO_PCM8,
     /// PCM 16bit interleaved dual-channel.
     PCM16Stereo = ctru_sys::NDSP_FORMAT_STEREO_PCM16,
 }
 
+/// Encoding of the samples of an [`AudioFormat`], regardless of the amount of channels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SampleEncoding {
+    /// PCM 8bit.
+    Pcm8,
+    /// PCM 16bit.
+    Pcm16,
+    /// DSP-ADPCM 4bit.
+    Adpcm,
+}
+
 /// Representation of the volume mix for a channel.
+///
+/// The mix is made up of 12 volumes, one for each output of the channel, stored in this order:
+///
+/// | Index | Output                             |
+/// |-------|------------------------------------|
+/// | 0-1   | Front left and right               |
+/// | 2-3   | Back left and right                |
+/// | 4-5   | Auxiliary device 0 front left and right |
+/// | 6-7   | Auxiliary device 0 back left and right  |
+/// | 8-9   | Auxiliary device 1 front left and right |
+/// | 10-11 | Auxiliary device 1 back left and right  |
+///
+/// Use the getters and setters to edit the mix without handling the raw layout.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct AudioMix {
     raw: [f32; 12],
@@ -96,6 +179,37 @@ pub enum Error {
     WaveBusy(u8),
     /// The sample amount requested was larger than the maximum.
     SampleCountOutOfBounds(usize, usize),
+    /// Stereo audio data was provided with an odd amount of samples, so it can't be split between the left and right channels.
+    InvalidStereoSampleCount(usize),
+    /// The sample rate requested is not supported by the DSP.
+    InvalidSampleRate,
+    /// The specified aux bus is already being used.
+    AuxBusAlreadyInUse(AuxDevice),
+    /// The parameters of the requested effect are out of their valid range.
+    InvalidEffectParameters,
+    /// The parameters of the requested filter are out of their valid range.
+    InvalidFilterParameters,
+    /// The requested start offset doesn't match the start of an ADPCM frame.
+    UnalignedStartOffset(usize),
+    /// The provided data isn't a valid WAV file.
+    InvalidWavData,
+    /// The requested sample type doesn't match the wave's audio format.
+    InvalidSampleType(AudioFormat),
+    /// The audio format of the WAV file isn't supported by the DSP.
+    UnsupportedWavFormat,
+    /// The requested operation isn't supported for waves in the specified audio format.
+    UnsupportedFormat(AudioFormat),
+    /// There isn't enough free LINEAR memory to allocate the wave's data.
+    OutOfLinearMemory,
+    /// The audio format of the wave doesn't match the one set on the channel.
+    FormatMismatch {
+        /// ID of the channel.
+        channel: u8,
+        /// Audio format set on the channel.
+        expected: AudioFormat,
+        /// Audio format of the wave.
+        found: AudioFormat,
+    },
 }
 
 /// NDSP Channel representation.
@@ -112,20 +226,357 @@ pub enum Error {
 /// - Default interpolation type is set to [`InterpolationType::Polyphase`].
 /// - Default mix is set to [`AudioMix::default()`]
 ///
+/// # Notes
+///
+/// Since NDSP doesn't provide a way to read back the channel's parameters, [`Channel`] keeps a copy of the values
+/// last set through it (returned by getters such as [`Channel::format()`]). Parameters changed directly via `ctru_sys` can't be tracked.
+///
+/// # Thread safety
+///
+/// [`Channel`] is [`Send`], so it can be moved to a dedicated audio thread while the [`Ndsp`] handle stays on the main thread
+/// (the thread must not outlive the handle, for example by using [`std::thread::scope()`]).
+/// `libctru` guards the parameters and queue of every channel with a lock, which the NDSP thread also takes before reading them.
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::ndsp::{AudioFormat, Ndsp};
+/// let ndsp = Ndsp::new()?;
+/// let mut channel_0 = ndsp.channel(0)?;
+///
+/// std::thread::scope(|s| {
+///     s.spawn(move || {
+///         channel_0.set_format(AudioFormat::PCM16Stereo);
+///     });
+/// });
+/// #
+/// # Ok(())
+/// # }
+/// ```
+///
 /// The handle to a channel can be retrieved with [`Ndsp::channel()`]
 pub struct Channel<'ndsp> {
     id: u8,
+    // Released once the channel is dropped.
+    in_use: &'ndsp AtomicBool,
+    state: &'ndsp mut ChannelState,
+}
+
+// Exclusive access to a channel's state, handed out by `Ndsp::channel()`.
+//
+// A `RefCell` isn't used, since its borrow flag isn't thread safe and would prevent `Channel` from being sent to other threads.
+#[derive(Default)]
+struct ChannelSlot {
+    in_use: AtomicBool,
+    state: UnsafeCell<ChannelState>,
+}
+
+// Copy of the parameters set on a channel, since `libctru` doesn't provide getters for them.
+struct ChannelState {
+    format: AudioFormat,
+    sample_rate: f32,
+    interpolation: InterpolationType,
+    mix: AudioMix,
+}
+
+/// Complete configuration of a [`Channel`], to be applied with [`Channel::configure()`].
+///
+/// # Default
+///
+/// The default configuration matches the one set by [`Channel::init_parameters()`].
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::services::ndsp::{AudioFormat, ChannelConfig, InterpolationType};
+///
+/// let config = ChannelConfig::new()
+///     .format(AudioFormat::PCM16Stereo)
+///     .sample_rate(44100.)
+///     .interpolation(InterpolationType::Linear)
+///     .volume(0.8);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[must_use]
+pub struct ChannelConfig {
+    format: AudioFormat,
+    sample_rate: f32,
+    interpolation: InterpolationType,
+    mix: AudioMix,
+}
+
+// Copy of the global output settings, since `libctru` doesn't provide getters for them.
+struct OutputState {
+    mode: OutputMode,
+    clipping_mode: ClippingMode,
+    master_volume: f32,
+}
+
+// State shared with the NDSP thread, which runs the frame callback, and with the `FrameWaiter`s.
+struct FrameState {
+    callback: Mutex<Option<Box<FrameCallback>>>,
+    frames: Mutex<FrameCount>,
+    // Notified at the end of every frame, and when the service is closed.
+    frame_ended: Condvar,
+}
+
+#[derive(Default)]
+struct FrameCount {
+    count: u64,
+    closed: bool,
+}
+
+/// Handle waiting for the audio frames processed by the DSP, obtained with [`Ndsp::frame_waiter()`].
+///
+/// Unlike [`Ndsp`], this handle can be sent to (and shared with) other threads, such as a thread refilling the audio buffers.
+/// It stays valid after the [`Ndsp`] service is dropped, but then stops waiting (see [`FrameWaiter::wait()`]).
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::ndsp::Ndsp;
+///
+/// let ndsp = Ndsp::new()?;
+/// let waiter = ndsp.frame_waiter();
+///
+/// let audio_thread = std::thread::spawn(move || {
+///     // Stops once the service is dropped.
+///     while waiter.wait() {
+///         // Refill the audio buffers here.
+///     }
+/// });
+///
+/// // ...
+///
+/// drop(ndsp);
+/// audio_thread.join().unwrap();
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct FrameWaiter {
+    state: Arc<FrameState>,
+}
+
+impl FrameWaiter {
+    /// Block the current thread until the DSP has processed the next audio frame (roughly every 4.8 ms).
+    ///
+    /// Returns `true` once the frame was processed, or `false` if the [`Ndsp`] service was dropped (either before or during the wait).
+    pub fn wait(&self) -> bool {
+        let frames = self.state.lock_frames();
+        let start = frames.count;
+
+        let frames = self
+            .state
+            .frame_ended
+            .wait_while(frames, |frames| frames.count == start && !frames.closed)
+            .unwrap_or_else(PoisonError::into_inner);
+
+        !frames.closed
+    }
+
+    /// Returns `true` if the [`Ndsp`] service was dropped, in which case [`FrameWaiter::wait()`] returns right away.
+    pub fn is_closed(&self) -> bool {
+        self.state.lock_frames().closed
+    }
+}
+
+impl FrameState {
+    fn lock_frames(&self) -> MutexGuard<'_, FrameCount> {
+        self.frames.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Wake up all the waiters, either because a frame ended or because the service is closed.
+    fn end_frame(&self, closed: bool) {
+        let mut frames = self.lock_frames();
+
+        frames.count = frames.count.wrapping_add(1);
+        frames.closed |= closed;
+
+        drop(frames);
+        self.frame_ended.notify_all();
+    }
+}
+
+/// Ownership-tracking wave queue of a [`Channel`].
+///
+/// Unlike [`Channel::queue_wave()`], which leaves it up to the caller to keep the queued [`Wave`]s alive (and in place) during playback,
+/// a [`ChannelQueue`] takes ownership of every queued [`Wave`] and only hands it back once the DSP has finished reading it.
+/// This is the suggested way to play audio with [`ndsp`](self).
+///
+/// All other [`Channel`] functionality remains available through [`Deref`].
+///
+/// # Example
+///
+/// ```no_run
+/// # #![feature(allocator_api)]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// # use ctru::linear::LinearAllocator;
+/// use ctru::services::ndsp::wave::Wave;
+/// use ctru::services::ndsp::{AudioFormat, ChannelQueue, Ndsp};
+/// let ndsp = Ndsp::new()?;
+///
+/// let mut queue = ChannelQueue::new(ndsp.channel(0)?);
+/// queue.set_format(AudioFormat::PCM16Stereo);
+///
+/// # let audio_data: Box<[_], _> = Box::new_in([0u8; 96], LinearAllocator);
+/// // The queue takes ownership of the wave.
+/// queue.queue(Wave::new(audio_data, AudioFormat::PCM16Stereo, false))?;
+///
+/// // Wait for the wave to finish, then take it back.
+/// let wave = loop {
+///     if let Some(wave) = queue.retrieve_done() {
+///         break wave;
+///     }
+/// };
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct ChannelQueue<'ndsp, Buffer: LinearAllocation + AsRef<[u8]>> {
+    channel: Channel<'ndsp>,
+    // The waves are boxed to keep the address of their `ndspWaveBuf` stable while `libctru` holds a pointer to it.
+    waves: VecDeque<Box<Wave<Buffer>>>,
+}
+
+/// Auxiliary output bus of the DSP.
+///
+/// Channels send audio to the aux buses via the aux volumes of their [`AudioMix`].
+/// The audio of each bus goes through its effect (if any) before being mixed into the main output.
+///
+/// Each bus can run either a [`Delay`] (see [`AuxBus::set_delay()`]) or a [`Reverb`] (see [`AuxBus::set_reverb()`]) effect.
+///
+/// # Notes
+///
+/// `libctru` doesn't expose the DSP firmware's own effects, so the effects of the aux buses are processed on the CPU, by the NDSP service thread.
+///
+/// The handle to an aux bus can be retrieved with [`Ndsp::aux_bus()`].
+#[doc(alias = "ndspAuxSetEnable")]
+pub struct AuxBus<'ndsp> {
+    device: AuxDevice,
+    effect: &'ndsp Mutex<Option<AuxEffect>>,
     _rf: RefMut<'ndsp, ()>, // we don't need to hold any data
 }
 
+/// Echo effect applied to the audio of an [`AuxBus`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Delay {
+    /// Time between the input audio and its echo. Must be greater than zero and up to one second.
+    pub time: Duration,
+    /// Portion of the echo fed back into the delay, producing repeated echoes. Must be between `0.0` (single echo) and `1.0` (excluded).
+    pub feedback: f32,
+}
+
+/// Reverberation effect applied to the audio of an [`AuxBus`], simulating the reflections of a room.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Reverb {
+    /// Size of the simulated room, which sets how long the reverberation lasts. Must be between `0.0` and `1.0`.
+    pub room_size: f32,
+    /// Amount of high frequencies absorbed by the room on every reflection. Must be between `0.0` (bright) and `1.0` (dark).
+    pub damping: f32,
+}
+
+// Effect processed by the NDSP thread on the audio of an aux bus.
+enum AuxEffect {
+    Delay(DelayLine),
+    Reverb(Box<[ReverbLine; 4]>),
+}
+
+// Ring buffer holding the delayed samples of the 4 aux outputs (front left and right, back left and right).
+struct DelayLine {
+    buffer: Box<[[i32; 4]]>,
+    position: usize,
+    feedback: f32,
+}
+
+// Freeverb-style reverberation of a single aux output: parallel comb filters followed by all-pass filters.
+struct ReverbLine {
+    combs: [CombFilter; 4],
+    all_passes: [AllPassFilter; 2],
+}
+
+// Feedback comb filter with a low-pass filter in its feedback loop.
+struct CombFilter {
+    buffer: Box<[f32]>,
+    position: usize,
+    feedback: f32,
+    damping: f32,
+    filter_state: f32,
+}
+
+// Schroeder all-pass filter, diffusing the echoes of the comb filters.
+struct AllPassFilter {
+    buffer: Box<[f32]>,
+    position: usize,
+}
+
+/// Handle to a [`Wave`] queued on a [`ChannelQueue`], used to know when it has finished playing.
+///
+/// Returned by [`ChannelQueue::queue()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WaveHandle {
+    channel_id: u8,
+    sequence_id: u16,
+}
+
+/// Pause of all channels, returned by [`Ndsp::pause_all()`].
+///
+/// Dropping the guard resumes the channels which were playing (not paused) when it was created.
+/// Channels paused beforehand are left paused.
+#[must_use = "dropping the guard immediately resumes the channels"]
+pub struct PauseGuard<'ndsp> {
+    _ndsp: &'ndsp Ndsp,
+    // Bit mask of the channels to resume.
+    resumed: u32,
+}
+
 static NDSP_ACTIVE: Mutex<()> = Mutex::new(());
 
 /// Handle to the DSP service.
 ///
-/// Only one handle for this service can exist at a time.
+/// Only one handle for this service can exist at a time. Trying to create another one returns
+/// [`Error::ServiceAlreadyActive`](crate::Error::ServiceAlreadyActive), leaving the active instance untouched,
+/// so the service is only closed once the single live handle is dropped.
+///
+/// Subsystems which need audio should share the same handle (or [`Channel`]s borrowed from it) instead.
+///
+/// # Thread safety
+///
+/// [`Ndsp`] is neither [`Send`] nor [`Sync`]: the service must be closed by the same thread which initialized it,
+/// and the handle's own state isn't synchronized. Use [`Channel`]s to play audio from other threads instead.
+///
+/// ```compile_fail
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::ndsp::Ndsp;
+/// let ndsp = Ndsp::new()?;
+///
+/// // Moving the handle to another thread doesn't compile.
+/// std::thread::spawn(move || drop(ndsp));
+/// #
+/// # Ok(())
+/// # }
+/// ```
 pub struct Ndsp {
     _service_handler: ServiceReference,
-    channel_flags: [RefCell<()>; NUMBER_OF_CHANNELS as usize],
+    // Used by the NDSP service until it's closed (dropped after `_service_handler`).
+    _component: Option<Box<[u8]>>,
+    channel_slots: [ChannelSlot; NUMBER_OF_CHANNELS as usize],
+    output_state: OutputState,
+    // Accessed by the NDSP thread on every frame, so it must outlive the service itself (dropped after `_service_handler`).
+    frame_state: Arc<FrameState>,
+    aux_flags: [RefCell<()>; 2],
+    // Accessed by the NDSP thread like `frame_state`.
+    aux_effects: [Box<Mutex<Option<AuxEffect>>>; 2],
 }
 
 impl Ndsp {
@@ -134,8 +585,10 @@ impl Ndsp {
     /// # Errors
     ///
     /// This function will return an error if an instance of the [`Ndsp`] struct already exists
-    /// or if there are any issues during initialization (for example, DSP firmware
-    /// cannot be found. See [module documentation](super::ndsp) for more details.).
+    /// or if there are any issues during initialization.
+    ///
+    /// If the DSP firmware cannot be found, [`Error::MissingDspFirmware`](crate::Error::MissingDspFirmware) is returned.
+    /// See [module documentation](super::ndsp) for more details.
     ///
     /// # Example
     ///
@@ -152,29 +605,121 @@ impl Ndsp {
     /// ```
     #[doc(alias = "ndspInit")]
     pub fn new() -> crate::Result<Self> {
+        Self::init(None)
+    }
+
+    /// Initialize the DSP service and audio units using the provided DSP firmware,
+    /// instead of loading it from `sdmc:/3ds/dspfirm.cdc`.
+    ///
+    /// The firmware is kept in memory for as long as the service is active, since it must be reloaded
+    /// when the console wakes up from sleep mode.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if an instance of the [`Ndsp`] struct already exists
+    /// or if there are any issues during initialization (for example, if the firmware is invalid).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    ///
+    /// // Read the firmware from a custom location.
+    /// let component = std::fs::read("sdmc:/my-app/dspfirm.cdc")?;
+    ///
+    /// let ndsp = Ndsp::with_component(component)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspUseComponent")]
+    #[doc(alias = "ndspInit")]
+    pub fn with_component(component: impl Into<Box<[u8]>>) -> crate::Result<Self> {
+        Self::init(Some(component.into()))
+    }
+
+    fn init(component: Option<Box<[u8]>>) -> crate::Result<Self> {
         let _service_handler = ServiceReference::new(
             &NDSP_ACTIVE,
             || {
-                ResultCode(unsafe { ctru_sys::ndspInit() })?;
+                if let Some(component) = &component {
+                    // Mask values taken from `libctru`'s default firmware loader.
+                    unsafe {
+                        ctru_sys::ndspUseComponent(
+                            component.as_ptr().cast(),
+                            component.len() as u32,
+                            0xFF,
+                            0xFF,
+                        )
+                    };
+                }
+
+                let result = unsafe { ctru_sys::ndspInit() };
+
+                if ctru_sys::R_FAILED(result) {
+                    // Don't let `libctru` keep a pointer to the component after it's dropped.
+                    unsafe { ctru_sys::ndspUseComponent(std::ptr::null(), 0, 0, 0) };
+
+                    if ResultCode(result).matches(codes::DSP_FIRMWARE_NOT_FOUND) {
+                        return Err(crate::Error::MissingDspFirmware);
+                    }
+                }
+
+                ResultCode(result).named("ndspInit")?;
 
                 Ok(())
             },
             || unsafe {
                 ctru_sys::ndspExit();
+                ctru_sys::ndspUseComponent(std::ptr::null(), 0, 0, 0);
             },
         )?;
 
+        let frame_state = Arc::new(FrameState {
+            callback: Mutex::new(None),
+            frames: Mutex::default(),
+            frame_ended: Condvar::new(),
+        });
+
+        // The trampoline is registered once and then forwards every frame to the currently set callback (if any).
+        unsafe {
+            ctru_sys::ndspSetCallback(
+                Some(frame_callback_trampoline),
+                Arc::as_ptr(&frame_state).cast_mut().cast(),
+            )
+        };
+
+        let aux_effects: [Box<Mutex<Option<AuxEffect>>>; 2] = Default::default();
+
+        for (id, effect) in aux_effects.iter().enumerate() {
+            unsafe {
+                ctru_sys::ndspAuxSetCallback(
+                    id as _,
+                    Some(aux_callback_trampoline),
+                    std::ptr::from_ref(&**effect).cast_mut().cast(),
+                )
+            };
+        }
+
         Ok(Self {
             _service_handler,
-            channel_flags: Default::default(),
+            _component: component,
+            channel_slots: Default::default(),
+            output_state: OutputState::default(),
+            frame_state,
+            aux_flags: Default::default(),
+            aux_effects,
         })
     }
 
-    /// Return a representation of the specified channel.
+    /// Return a representation of the specified aux bus.
     ///
     /// # Errors
     ///
-    /// An error will be returned if the channel ID is not between 0 and 23 or if the specified channel is already being used.
+    /// An error will be returned if the specified aux bus is already being used.
     ///
     /// # Example
     ///
@@ -182,76 +727,51 @@ impl Ndsp {
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
-    /// use ctru::services::ndsp::Ndsp;
+    /// use ctru::services::ndsp::{AuxDevice, Ndsp};
     /// let ndsp = Ndsp::new()?;
     ///
-    /// let channel_0 = ndsp.channel(0)?;
+    /// let aux_0 = ndsp.aux_bus(AuxDevice::Zero)?;
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn channel(&self, id: u8) -> std::result::Result<Channel, Error> {
-        let in_bounds = self.channel_flags.get(id as usize);
-
-        match in_bounds {
-            Some(ref_cell) => {
-                let flag = ref_cell.try_borrow_mut();
-                match flag {
-                    Ok(_rf) => Ok(Channel { id, _rf }),
-                    Err(_) => Err(Error::ChannelAlreadyInUse(id)),
-                }
-            }
-            None => Err(Error::InvalidChannel(id)),
+    pub fn aux_bus(&self, device: AuxDevice) -> std::result::Result<AuxBus<'_>, Error> {
+        match self.aux_flags[device as usize].try_borrow_mut() {
+            Ok(_rf) => Ok(AuxBus {
+                device,
+                effect: &self.aux_effects[device as usize],
+                _rf,
+            }),
+            Err(_) => Err(Error::AuxBusAlreadyInUse(device)),
         }
     }
 
-    /// Set the audio output mode. Defaults to [`OutputMode::Stereo`].
+    /// Return a representation of the specified channel.
     ///
-    /// # Example
+    /// # Errors
     ///
-    /// ```no_run
-    /// # use std::error::Error;
-    /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// #
-    /// use ctru::services::ndsp::{Ndsp, OutputMode};
-    /// let mut ndsp = Ndsp::new()?;
+    /// An error will be returned if the channel ID is not between 0 and 23 or if the specified channel is already being used.
     ///
-    /// // Use dual-channel output.
-    /// ndsp.set_output_mode(OutputMode::Stereo);
-    /// #
-    /// # Ok(())
-    /// # }
-    /// ```
-    #[doc(alias = "ndspSetOutputMode")]
-    pub fn set_output_mode(&mut self, mode: OutputMode) {
-        unsafe { ctru_sys::ndspSetOutputMode(mode.into()) };
-    }
-}
-
-impl Channel<'_> {
-    /// Reset the channel (clear the queue and reset parameters).
+    /// # Notes
     ///
-    /// # Example
+    /// The returned [`Channel`] borrows the [`Ndsp`] handle, so it can't outlive the service:
     ///
-    /// ```no_run
+    /// ```compile_fail
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
-    /// use ctru::services::ndsp::Ndsp;
+    /// use ctru::services::ndsp::{AudioFormat, Ndsp};
     /// let ndsp = Ndsp::new()?;
     /// let mut channel_0 = ndsp.channel(0)?;
     ///
-    /// channel_0.reset();
+    /// // Closing the service while a channel is still in use doesn't compile.
+    /// drop(ndsp);
+    ///
+    /// channel_0.set_format(AudioFormat::PCM16Stereo);
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "ndspChnReset")]
-    pub fn reset(&mut self) {
-        unsafe { ctru_sys::ndspChnReset(self.id.into()) };
-    }
-
-    /// Initialize the channel's parameters with default values.
     ///
     /// # Example
     ///
@@ -261,19 +781,38 @@ impl Channel<'_> {
     /// #
     /// use ctru::services::ndsp::Ndsp;
     /// let ndsp = Ndsp::new()?;
-    /// let mut channel_0 = ndsp.channel(0)?;
     ///
-    /// channel_0.init_parameters();
+    /// let channel_0 = ndsp.channel(0)?;
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "ndspChnInitParams")]
-    pub fn init_parameters(&mut self) {
-        unsafe { ctru_sys::ndspChnInitParams(self.id.into()) };
+    pub fn channel(&self, id: u8) -> std::result::Result<Channel<'_>, Error> {
+        let Some(slot) = self.channel_slots.get(id as usize) else {
+            return Err(Error::InvalidChannel(id));
+        };
+
+        if slot
+            .in_use
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(Error::ChannelAlreadyInUse(id));
+        }
+
+        Ok(Channel {
+            id,
+            in_use: &slot.in_use,
+            // SAFETY: The flag guarantees that no other `Channel` is accessing the state until this one is dropped.
+            state: unsafe { &mut *slot.state.get() },
+        })
     }
 
-    /// Returns whether the channel is playing any audio.
+    /// Returns an iterator over all channels which aren't already being used.
+    ///
+    /// # Notes
+    ///
+    /// Channels currently borrowed via [`Ndsp::channel()`] (or a previous call to this function) are skipped.
     ///
     /// # Example
     ///
@@ -281,22 +820,24 @@ impl Channel<'_> {
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
-    /// use ctru::services::ndsp::Ndsp;
+    /// use ctru::services::ndsp::{AudioFormat, Ndsp};
     /// let ndsp = Ndsp::new()?;
-    /// let mut channel_0 = ndsp.channel(0)?;
     ///
-    /// // The channel is not playing any audio.
-    /// assert!(!channel_0.is_playing());
+    /// // Take the first 16 channels to play sound effects.
+    /// let mut sfx_channels: Vec<_> = ndsp.channels().take(16).collect();
+    ///
+    /// for channel in &mut sfx_channels {
+    ///     channel.set_format(AudioFormat::PCM16Mono);
+    /// }
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "ndspChnIsPlaying")]
-    pub fn is_playing(&self) -> bool {
-        unsafe { ctru_sys::ndspChnIsPlaying(self.id.into()) }
+    pub fn channels(&self) -> impl Iterator<Item = Channel<'_>> {
+        (0..NUMBER_OF_CHANNELS).filter_map(|id| self.channel(id).ok())
     }
 
-    /// Returns whether the channel's playback is currently paused.
+    /// Reset all channels (clear their queues and reset their parameters).
     ///
     /// # Example
     ///
@@ -305,21 +846,22 @@ impl Channel<'_> {
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
     /// use ctru::services::ndsp::Ndsp;
-    /// let ndsp = Ndsp::new()?;
-    /// let mut channel_0 = ndsp.channel(0)?;
+    /// let mut ndsp = Ndsp::new()?;
     ///
-    /// // The channel is not paused.
-    /// assert!(!channel_0.is_paused());
+    /// ndsp.reset_all();
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "ndspChnIsPaused")]
-    pub fn is_paused(&self) -> bool {
-        unsafe { ctru_sys::ndspChnIsPaused(self.id.into()) }
+    #[doc(alias = "ndspChnReset")]
+    pub fn reset_all(&mut self) {
+        // Since `self` is borrowed mutably, no channel can currently be in use.
+        for mut channel in self.channels() {
+            channel.reset();
+        }
     }
 
-    /// Returns the channel's index.
+    /// Pause or un-pause the playback of all channels.
     ///
     /// # Example
     ///
@@ -328,34 +870,25 @@ impl Channel<'_> {
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
     /// use ctru::services::ndsp::Ndsp;
-    /// let ndsp = Ndsp::new()?;
-    /// let mut channel_0 = ndsp.channel(0)?;
+    /// let mut ndsp = Ndsp::new()?;
     ///
-    /// // The channel's index is 0.
-    /// assert_eq!(channel_0.id(), 0);
+    /// // Pause all audio.
+    /// ndsp.set_all_paused(true);
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn id(&self) -> u8 {
-        self.id
+    #[doc(alias = "ndspChnSetPaused")]
+    pub fn set_all_paused(&mut self, state: bool) {
+        for mut channel in self.channels() {
+            channel.set_paused(state);
+        }
     }
 
-    /// Returns the index of the currently played sample.
+    /// Pause the playback of all channels, until the returned [`PauseGuard`] is dropped.
     ///
-    /// Because of how fast this value changes, it should only be used as a rough estimate of the current progress.
-    #[doc(alias = "ndspChnGetSamplePos")]
-    pub fn sample_position(&self) -> usize {
-        (unsafe { ctru_sys::ndspChnGetSamplePos(self.id.into()) }) as usize
-    }
-
-    /// Returns the channel's current wave sequence's id.
-    #[doc(alias = "ndspChnGetWaveBufSeq")]
-    pub fn wave_sequence_id(&self) -> u16 {
-        unsafe { ctru_sys::ndspChnGetWaveBufSeq(self.id.into()) }
-    }
-
-    /// Pause or un-pause the channel's playback.
+    /// Unlike [`Ndsp::set_all_paused()`], channels currently in use elsewhere are paused too (for example, when the HOME menu is opened),
+    /// and only the channels which weren't already paused are resumed afterwards.
     ///
     /// # Example
     ///
@@ -365,24 +898,1036 @@ impl Channel<'_> {
     /// #
     /// use ctru::services::ndsp::Ndsp;
     /// let ndsp = Ndsp::new()?;
-    /// let mut channel_0 = ndsp.channel(0)?;
     ///
-    /// channel_0.set_paused(true);
+    /// let music = ndsp.channel(0)?;
+    /// let mut sfx = ndsp.channel(1)?;
+    /// sfx.set_paused(true);
     ///
-    /// // The channel is paused.
+    /// {
+    ///     let _pause = ndsp.pause_all();
+    ///
+    ///     // Both channels are paused here.
+    /// }
+    ///
+    /// // Only the music is resumed.
+    /// assert!(!music.is_paused());
+    /// assert!(sfx.is_paused());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnSetPaused")]
+    pub fn pause_all(&self) -> PauseGuard<'_> {
+        let mut resumed = 0;
+
+        // The channels are accessed directly by their ID, since `libctru` takes care of locking them.
+        for id in 0..NUMBER_OF_CHANNELS {
+            unsafe {
+                if !ctru_sys::ndspChnIsPaused(id.into()) {
+                    ctru_sys::ndspChnSetPaused(id.into(), true);
+                    resumed |= 1 << id;
+                }
+            }
+        }
+
+        PauseGuard {
+            _ndsp: self,
+            resumed,
+        }
+    }
+
+    /// Set the audio output mode. Defaults to [`OutputMode::Stereo`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{Ndsp, OutputMode};
+    /// let mut ndsp = Ndsp::new()?;
+    ///
+    /// // Use dual-channel output.
+    /// ndsp.set_output_mode(OutputMode::Stereo);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspSetOutputMode")]
+    pub fn set_output_mode(&mut self, mode: OutputMode) {
+        unsafe { ctru_sys::ndspSetOutputMode(mode.into()) };
+
+        self.output_state.mode = mode;
+    }
+
+    /// Returns the audio output mode.
+    pub fn output_mode(&self) -> OutputMode {
+        self.output_state.mode
+    }
+
+    /// Set the clipping mode of the audio output. Defaults to [`ClippingMode::Soft`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{ClippingMode, Ndsp};
+    /// let mut ndsp = Ndsp::new()?;
+    ///
+    /// ndsp.set_clipping_mode(ClippingMode::Normal);
+    ///
+    /// assert_eq!(ndsp.clipping_mode(), ClippingMode::Normal);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspSetClippingMode")]
+    pub fn set_clipping_mode(&mut self, mode: ClippingMode) {
+        unsafe { ctru_sys::ndspSetClippingMode(mode.into()) };
+
+        self.output_state.clipping_mode = mode;
+    }
+
+    /// Returns the clipping mode of the audio output.
+    pub fn clipping_mode(&self) -> ClippingMode {
+        self.output_state.clipping_mode
+    }
+
+    /// Set the depth of the surround sound effect used by [`OutputMode::Surround`].
+    ///
+    /// # Notes
+    ///
+    /// The depth is clamped between `0.0` (no effect) and `1.0` (strongest effect).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{Ndsp, OutputMode};
+    /// let mut ndsp = Ndsp::new()?;
+    ///
+    /// ndsp.set_output_mode(OutputMode::Surround);
+    /// ndsp.set_surround_depth(0.5);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspSurroundSetDepth")]
+    pub fn set_surround_depth(&mut self, depth: f32) {
+        unsafe { ctru_sys::ndspSurroundSetDepth(surround_value(depth)) };
+    }
+
+    /// Set the position of the speakers used by [`OutputMode::Surround`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{Ndsp, OutputMode, SpeakerPosition};
+    /// let mut ndsp = Ndsp::new()?;
+    ///
+    /// ndsp.set_output_mode(OutputMode::Surround);
+    /// ndsp.set_speaker_position(SpeakerPosition::Wide);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspSurroundSetPos")]
+    pub fn set_speaker_position(&mut self, position: SpeakerPosition) {
+        unsafe { ctru_sys::ndspSurroundSetPos(position.into()) };
+    }
+
+    /// Set the ratio between the "rear" and "front" outputs used by [`OutputMode::Surround`].
+    ///
+    /// # Notes
+    ///
+    /// The ratio is clamped between `0.0` and `1.0`.
+    #[doc(alias = "ndspSurroundSetRearRatio")]
+    pub fn set_surround_rear_ratio(&mut self, ratio: f32) {
+        unsafe { ctru_sys::ndspSurroundSetRearRatio(surround_value(ratio)) };
+    }
+
+    /// Returns the master volume of the audio output. Defaults to `1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    ///
+    /// assert_eq!(ndsp.master_volume(), 1.0);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn master_volume(&self) -> f32 {
+        self.output_state.master_volume
+    }
+
+    /// Set the master volume of the audio output, which scales the output of all channels.
+    ///
+    /// # Notes
+    ///
+    /// The volume is clamped between `0.0` (muted) and `1.0` (full volume).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let mut ndsp = Ndsp::new()?;
+    ///
+    /// // Halve the volume of all channels.
+    /// ndsp.set_master_volume(0.5);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspSetMasterVol")]
+    pub fn set_master_volume(&mut self, volume: f32) {
+        // `f32::clamp` would keep a NaN value as is.
+        let volume = if volume.is_nan() {
+            0.0
+        } else {
+            volume.clamp(0.0, 1.0)
+        };
+
+        unsafe { ctru_sys::ndspSetMasterVol(volume) };
+
+        self.output_state.master_volume = volume;
+    }
+
+    /// Set a function to be called once every DSP frame (roughly every 4.8 ms).
+    ///
+    /// The new callback replaces any previously set one.
+    ///
+    /// # Notes
+    ///
+    /// The callback is run on the NDSP service thread, not on the thread which set it.
+    /// Since it delays the processing of the audio frames, the callback should avoid any blocking or long-running work.
+    ///
+    /// The callback doesn't interfere with [`Ndsp::wait_for_frame()`] and [`FrameWaiter::wait()`], which return after the callback has run.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// use ctru::services::ndsp::Ndsp;
+    /// let mut ndsp = Ndsp::new()?;
+    ///
+    /// let frames = Arc::new(AtomicUsize::new(0));
+    /// let frames_clone = Arc::clone(&frames);
+    ///
+    /// // Count the amount of audio frames processed.
+    /// ndsp.set_frame_callback(move || {
+    ///     frames_clone.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspSetCallback")]
+    pub fn set_frame_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        *self.lock_frame_callback() = Some(Box::new(callback));
+    }
+
+    /// Remove the function set with [`Ndsp::set_frame_callback()`], if any.
+    #[doc(alias = "ndspSetCallback")]
+    pub fn clear_frame_callback(&mut self) {
+        // Take the callback out first, so that it gets dropped without holding the lock.
+        let _callback = self.lock_frame_callback().take();
+    }
+
+    /// Block the current thread until the DSP has processed the next audio frame (roughly every 4.8 ms).
+    ///
+    /// This is useful to refill audio buffers in sync with the DSP, instead of polling them.
+    /// Look at [`Ndsp::frame_waiter()`] to wait from another thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    ///
+    /// for _ in 0..100 {
+    ///     ndsp.wait_for_frame();
+    ///
+    ///     // Refill the audio buffers here.
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wait_for_frame(&self) {
+        // The service can't be closed while it's borrowed.
+        let _ = self.frame_waiter().wait();
+    }
+
+    /// Returns a handle waiting for the audio frames processed by the DSP, which can be used from other threads.
+    ///
+    /// Have a look at [`FrameWaiter`] for more information.
+    pub fn frame_waiter(&self) -> FrameWaiter {
+        FrameWaiter {
+            state: Arc::clone(&self.frame_state),
+        }
+    }
+
+    /// Returns the amount of audio frames processed by the DSP since the service was initialized.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    ///
+    /// println!("{} frames played", ndsp.frame_count());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspGetFrameCount")]
+    pub fn frame_count(&self) -> u32 {
+        unsafe { ctru_sys::ndspGetFrameCount() }
+    }
+
+    /// Returns the amount of audio frames dropped since the service was initialized.
+    ///
+    /// Frames are dropped when the NDSP thread can't keep up with the DSP (for example, when it's starved by other threads),
+    /// which can be heard as crackling audio.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    ///
+    /// let dropped = ndsp.dropped_frames();
+    ///
+    /// // Do some heavy work...
+    ///
+    /// if ndsp.dropped_frames() > dropped {
+    ///     println!("The audio skipped");
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspGetDroppedFrames")]
+    pub fn dropped_frames(&self) -> u32 {
+        unsafe { ctru_sys::ndspGetDroppedFrames() }
+    }
+
+    fn lock_frame_callback(&self) -> MutexGuard<'_, Option<Box<FrameCallback>>> {
+        // A panicking callback aborts the NDSP thread, so poisoning isn't a concern.
+        self.frame_state
+            .callback
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Converts a value between 0 and 1 to the fixed point range used by the surround settings.
+fn surround_value(value: f32) -> u16 {
+    // Float to integer casts saturate, and NaN is converted to 0.
+    (value * 0x7FFF as f32).clamp(0., 0x7FFF as f32) as u16
+}
+
+// Forwards the `libctru` callback to the Rust closure stored in `Ndsp`, then wakes up any thread waiting for the frame.
+unsafe extern "C" fn frame_callback_trampoline(data: *mut libc::c_void) {
+    let frame_state = unsafe { &*data.cast::<FrameState>() };
+
+    if let Ok(mut callback) = frame_state.callback.lock() {
+        if let Some(callback) = callback.as_mut() {
+            callback();
+        }
+    }
+
+    frame_state.end_frame(false);
+}
+
+// Forwards the audio of an aux bus to its effect.
+unsafe extern "C" fn aux_callback_trampoline(
+    data: *mut libc::c_void,
+    nsamples: libc::c_int,
+    sampledata: *mut *mut libc::c_void,
+) {
+    let effect = unsafe { &*data.cast::<Mutex<Option<AuxEffect>>>() };
+
+    if let Ok(mut effect) = effect.lock() {
+        if let Some(effect) = effect.as_mut() {
+            // `libctru` provides 4 buffers of 32 bit samples: front left and right, back left and right.
+            let buffers = unsafe { std::slice::from_raw_parts(sampledata, 4) };
+            let mut samples: [&mut [i32]; 4] = std::array::from_fn(|i| unsafe {
+                std::slice::from_raw_parts_mut(buffers[i].cast(), nsamples as usize)
+            });
+
+            effect.process(&mut samples);
+        }
+    }
+}
+
+impl AuxBus<'_> {
+    /// Returns the aux bus' index.
+    pub fn device(&self) -> AuxDevice {
+        self.device
+    }
+
+    /// Enable or disable the aux bus. Aux buses are disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{AuxDevice, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut aux_0 = ndsp.aux_bus(AuxDevice::Zero)?;
+    ///
+    /// aux_0.set_enabled(true);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspAuxSetEnable")]
+    pub fn set_enabled(&mut self, enable: bool) {
+        unsafe { ctru_sys::ndspAuxSetEnable(self.device as _, enable) };
+    }
+
+    /// Set whether the "front" audio of the aux bus bypasses the surround sound processing.
+    #[doc(alias = "ndspAuxSetFrontBypass")]
+    pub fn set_front_bypass(&mut self, bypass: bool) {
+        unsafe { ctru_sys::ndspAuxSetFrontBypass(self.device as _, bypass) };
+    }
+
+    /// Set the output volume of the aux bus.
+    #[doc(alias = "ndspAuxSetVolume")]
+    pub fn set_volume(&mut self, volume: f32) {
+        unsafe { ctru_sys::ndspAuxSetVolume(self.device as _, volume) };
+    }
+
+    /// Set a [`Delay`] effect on the aux bus (replacing the current effect), or remove the current effect with `None`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the delay parameters are out of their valid range.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use ctru::services::ndsp::{AudioMix, AuxDevice, Delay, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    /// let mut aux_0 = ndsp.aux_bus(AuxDevice::Zero)?;
+    ///
+    /// // Echo the audio once every 300 ms.
+    /// aux_0.set_delay(Some(Delay {
+    ///     time: Duration::from_millis(300),
+    ///     feedback: 0.5,
+    /// }))?;
+    /// aux_0.set_enabled(true);
+    ///
+    /// // Send the channel's audio to the aux bus, besides the main output.
+    /// let mut mix = AudioMix::default();
+    /// mix.set_aux_front(0.5, 0.5, AuxDevice::Zero);
+    /// channel_0.set_mix(&mix);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspAuxSetCallback")]
+    pub fn set_delay(&mut self, delay: Option<Delay>) -> std::result::Result<(), Error> {
+        let effect = match delay {
+            Some(delay) => Some(AuxEffect::Delay(DelayLine::new(delay)?)),
+            None => None,
+        };
+
+        self.replace_effect(effect);
+
+        Ok(())
+    }
+
+    /// Set a [`Reverb`] effect on the aux bus (replacing the current effect), or remove the current effect with `None`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the reverb parameters are out of their valid range.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{AudioMix, AuxDevice, Ndsp, Reverb};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    /// let mut aux_1 = ndsp.aux_bus(AuxDevice::One)?;
+    ///
+    /// // A large, slightly dark hall.
+    /// aux_1.set_reverb(Some(Reverb {
+    ///     room_size: 0.8,
+    ///     damping: 0.4,
+    /// }))?;
+    /// aux_1.set_enabled(true);
+    ///
+    /// let mut mix = AudioMix::default();
+    /// mix.set_aux_front(0.3, 0.3, AuxDevice::One);
+    /// channel_0.set_mix(&mix);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspAuxSetCallback")]
+    pub fn set_reverb(&mut self, reverb: Option<Reverb>) -> std::result::Result<(), Error> {
+        let effect = match reverb {
+            Some(reverb) => Some(AuxEffect::Reverb(ReverbLine::new_outputs(reverb)?)),
+            None => None,
+        };
+
+        self.replace_effect(effect);
+
+        Ok(())
+    }
+
+    fn replace_effect(&mut self, effect: Option<AuxEffect>) {
+        // Replace the effect first, so that the old one gets dropped without holding the lock.
+        let _old = std::mem::replace(
+            &mut *self
+                .effect
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+            effect,
+        );
+    }
+}
+
+impl AuxEffect {
+    fn process(&mut self, samples: &mut [&mut [i32]; 4]) {
+        match self {
+            Self::Delay(delay_line) => delay_line.process(samples),
+            Self::Reverb(outputs) => {
+                for (output, reverb) in samples.iter_mut().zip(outputs.iter_mut()) {
+                    reverb.process(output);
+                }
+            }
+        }
+    }
+}
+
+impl DelayLine {
+    fn new(delay: Delay) -> std::result::Result<Self, Error> {
+        if delay.time.is_zero()
+            || delay.time > MAX_DELAY_TIME
+            || !(0.0..1.0).contains(&delay.feedback)
+        {
+            return Err(Error::InvalidEffectParameters);
+        }
+
+        let length = ((delay.time.as_secs_f32() * DSP_SAMPLE_RATE) as usize).max(1);
+
+        Ok(Self {
+            buffer: vec![[0; 4]; length].into_boxed_slice(),
+            position: 0,
+            feedback: delay.feedback,
+        })
+    }
+
+    // Replaces the input samples with the delayed ones, while storing the input (plus feedback) for later.
+    fn process(&mut self, samples: &mut [&mut [i32]; 4]) {
+        for i in 0..samples[0].len() {
+            let delayed = &mut self.buffer[self.position];
+
+            for (output, stored) in samples.iter_mut().zip(delayed.iter_mut()) {
+                let echo = *stored;
+
+                *stored = output[i].saturating_add((echo as f32 * self.feedback) as i32);
+                output[i] = echo;
+            }
+
+            self.position = (self.position + 1) % self.buffer.len();
+        }
+    }
+}
+
+impl ReverbLine {
+    // Build the reverberation of the 4 aux outputs, using the same tuning as Freeverb.
+    fn new_outputs(reverb: Reverb) -> std::result::Result<Box<[Self; 4]>, Error> {
+        if !(0.0..=1.0).contains(&reverb.room_size) || !(0.0..=1.0).contains(&reverb.damping) {
+            return Err(Error::InvalidEffectParameters);
+        }
+
+        // The tuning is meant for 44.1 kHz audio, so it's scaled to the DSP's sample rate.
+        let scale = |length: usize| ((length as f32 * DSP_SAMPLE_RATE / 44100.) as usize).max(1);
+        // Even outputs are on the left, odd ones on the right.
+        let output = |right: bool| {
+            let spread = if right { REVERB_STEREO_SPREAD } else { 0 };
+
+            Self {
+                combs: REVERB_COMB_TUNING.map(|length| CombFilter {
+                    buffer: vec![0.; scale(length + spread)].into_boxed_slice(),
+                    position: 0,
+                    feedback: reverb.room_size * 0.28 + 0.7,
+                    damping: reverb.damping * 0.4,
+                    filter_state: 0.,
+                }),
+                all_passes: REVERB_ALL_PASS_TUNING.map(|length| AllPassFilter {
+                    buffer: vec![0.; scale(length + spread)].into_boxed_slice(),
+                    position: 0,
+                }),
+            }
+        };
+
+        Ok(Box::new([
+            output(false),
+            output(true),
+            output(false),
+            output(true),
+        ]))
+    }
+
+    // Replaces the input samples with their reverberation.
+    fn process(&mut self, samples: &mut [i32]) {
+        for sample in samples {
+            // Scaled down to keep the sum of the comb filters in range.
+            let input = *sample as f32 * 0.25;
+
+            let mut output: f32 = self.combs.iter_mut().map(|comb| comb.process(input)).sum();
+
+            for all_pass in &mut self.all_passes {
+                output = all_pass.process(output);
+            }
+
+            // Float to int casts saturate.
+            *sample = output as i32;
+        }
+    }
+}
+
+impl CombFilter {
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.buffer[self.position];
+
+        self.filter_state = output * (1. - self.damping) + self.filter_state * self.damping;
+        self.buffer[self.position] = input + self.filter_state * self.feedback;
+        self.position = (self.position + 1) % self.buffer.len();
+
+        output
+    }
+}
+
+impl AllPassFilter {
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.buffer[self.position];
+
+        self.buffer[self.position] = input + delayed * 0.5;
+        self.position = (self.position + 1) % self.buffer.len();
+
+        delayed - input
+    }
+}
+
+impl Channel<'_> {
+    /// Reset the channel (clear the queue and reset parameters).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// channel_0.reset();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnReset")]
+    pub fn reset(&mut self) {
+        unsafe { ctru_sys::ndspChnReset(self.id.into()) };
+        bump_queue_generation(self.id);
+
+        *self.state = ChannelState::default();
+    }
+
+    /// Initialize the channel's parameters with default values.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// channel_0.init_parameters();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnInitParams")]
+    pub fn init_parameters(&mut self) {
+        unsafe { ctru_sys::ndspChnInitParams(self.id.into()) };
+
+        *self.state = ChannelState::default();
+    }
+
+    /// Returns whether the channel is playing any audio.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// // The channel is not playing any audio.
+    /// assert!(!channel_0.is_playing());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnIsPlaying")]
+    pub fn is_playing(&self) -> bool {
+        unsafe { ctru_sys::ndspChnIsPlaying(self.id.into()) }
+    }
+
+    /// Returns whether the channel's playback is currently paused.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// // The channel is not paused.
+    /// assert!(!channel_0.is_paused());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnIsPaused")]
+    pub fn is_paused(&self) -> bool {
+        unsafe { ctru_sys::ndspChnIsPaused(self.id.into()) }
+    }
+
+    /// Block the current thread until the channel stops playing audio, or until `timeout` has passed.
+    ///
+    /// Returns `true` if the channel stopped playing and `false` if the timeout expired first.
+    ///
+    /// # Notes
+    ///
+    /// The channel's state is checked once per DSP frame, starting after the next one (so that freshly queued audio is picked up).
+    /// Looping waves never finish playing, so a timeout should be used with them, to avoid blocking forever.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// // Let the current sound finish, but cut it after 2 seconds.
+    /// if !channel_0.wait_until_done(Some(Duration::from_secs(2))) {
+    ///     channel_0.stop();
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnIsPlaying")]
+    pub fn wait_until_done(&self, timeout: Option<Duration>) -> bool {
+        let start = Instant::now();
+
+        loop {
+            std::thread::sleep(DSP_FRAME_DURATION);
+
+            if !self.is_playing() {
+                return true;
+            }
+
+            if timeout.is_some_and(|timeout| start.elapsed() >= timeout) {
+                return false;
+            }
+        }
+    }
+
+    /// Stop the channel's playback right away, clearing its queue and resetting its parameters.
+    ///
+    /// This is equivalent to [`Channel::reset()`].
+    #[doc(alias = "ndspChnReset")]
+    pub fn stop(&mut self) {
+        self.reset();
+    }
+
+    /// Returns the channel's index.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// // The channel's index is 0.
+    /// assert_eq!(channel_0.id(), 0);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Returns the index of the currently played sample.
+    ///
+    /// Because of how fast this value changes, it should only be used as a rough estimate of the current progress.
+    #[doc(alias = "ndspChnGetSamplePos")]
+    pub fn sample_position(&self) -> usize {
+        (unsafe { ctru_sys::ndspChnGetSamplePos(self.id.into()) }) as usize
+    }
+
+    /// Returns the playback position within the currently played wave, as a time span.
+    ///
+    /// This is the [sample position](Channel::sample_position) converted with the channel's [sample rate](Channel::sample_rate).
+    ///
+    /// # Notes
+    ///
+    /// The conversion always uses the current sample rate, so the position is only exact if the rate didn't change while playing the wave.
+    /// If the channel isn't playing any audio, a zero duration is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// channel_0.set_sample_rate(44100.);
+    ///
+    /// println!("Elapsed: {:.1} s", channel_0.position().as_secs_f32());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnGetSamplePos")]
+    pub fn position(&self) -> Duration {
+        if !self.is_playing() {
+            return Duration::ZERO;
+        }
+
+        // The sample rate is always positive when set through `Channel`.
+        Duration::try_from_secs_f64(self.sample_position() as f64 / f64::from(self.sample_rate()))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Returns the channel's current wave sequence's id.
+    #[doc(alias = "ndspChnGetWaveBufSeq")]
+    pub fn wave_sequence_id(&self) -> u16 {
+        unsafe { ctru_sys::ndspChnGetWaveBufSeq(self.id.into()) }
+    }
+
+    /// Pause or un-pause the channel's playback.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// channel_0.set_paused(true);
+    ///
+    /// // The channel is paused.
     /// assert!(channel_0.is_paused());
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "ndspChnSetPaused")]
-    pub fn set_paused(&mut self, state: bool) {
-        unsafe { ctru_sys::ndspChnSetPaused(self.id.into(), state) };
+    #[doc(alias = "ndspChnSetPaused")]
+    pub fn set_paused(&mut self, state: bool) {
+        unsafe { ctru_sys::ndspChnSetPaused(self.id.into(), state) };
+    }
+
+    /// Set the channel's output format.
+    ///
+    /// Change this setting based on the used wave's format.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// // Use the PCM16 interleaved dual-channel audio format.
+    /// channel_0.set_format(AudioFormat::PCM16Stereo);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    // TODO: Channels treat all waves as equal and do not read their format when playing them. Another good reason to re-write the service.
+    #[doc(alias = "ndspChnSetFormat")]
+    pub fn set_format(&mut self, format: AudioFormat) {
+        unsafe { ctru_sys::ndspChnSetFormat(self.id.into(), format.into()) };
+
+        self.state.format = format;
+    }
+
+    /// Returns the channel's output format, as last set with [`Channel::set_format()`].
+    pub fn format(&self) -> AudioFormat {
+        self.state.format
+    }
+
+    /// Set the channel's interpolation mode.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{InterpolationType, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// // Use linear interpolation within frames.
+    /// channel_0.set_interpolation(InterpolationType::Linear);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnSetInterp")]
+    pub fn set_interpolation(&mut self, interp_type: InterpolationType) {
+        unsafe { ctru_sys::ndspChnSetInterp(self.id.into(), interp_type.into()) };
+
+        self.state.interpolation = interp_type;
+    }
+
+    /// Returns the channel's interpolation mode, as last set with [`Channel::set_interpolation()`].
+    pub fn interpolation(&self) -> InterpolationType {
+        self.state.interpolation
+    }
+
+    /// Set the channel's volume mix.
+    ///
+    /// Look at [`AudioMix`] for more information on the volume mix.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use std::default::Default;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{AudioMix, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// // Front-left and front-right channel maxed.
+    /// channel_0.set_mix(&AudioMix::default());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnSetMix")]
+    pub fn set_mix(&mut self, mix: &AudioMix) {
+        unsafe { ctru_sys::ndspChnSetMix(self.id.into(), mix.as_raw().as_ptr().cast_mut()) };
+
+        self.state.mix = *mix;
+    }
+
+    /// Returns the channel's volume mix, as last set with [`Channel::set_mix()`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use std::default::Default;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{AudioMix, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let channel_0 = ndsp.channel(0)?;
+    ///
+    /// // The default mix is set on initialization.
+    /// assert_eq!(channel_0.mix(), AudioMix::default());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn mix(&self) -> AudioMix {
+        self.state.mix
     }
 
-    /// Set the channel's output format.
+    /// Set the volume of the "front" left and right outputs, leaving the rest of the volume mix unchanged.
     ///
-    /// Change this setting based on the used wave's format.
+    /// # Notes
+    ///
+    /// The balance between the left and right outputs (as set by [`Channel::set_panning()`]) is kept.
+    /// Here, the volume of the outputs is their RMS level, so a centered channel has both outputs set to `volume`.
     ///
     /// # Example
     ///
@@ -390,23 +1935,41 @@ impl Channel<'_> {
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
-    /// use ctru::services::ndsp::{AudioFormat, Ndsp};
+    /// use ctru::services::ndsp::Ndsp;
     /// let ndsp = Ndsp::new()?;
     /// let mut channel_0 = ndsp.channel(0)?;
     ///
-    /// // Use the PCM16 interleaved dual-channel audio format.
-    /// channel_0.set_format(AudioFormat::PCM16Stereo);
+    /// // Fade out the channel.
+    /// for step in (0..=10).rev() {
+    ///     channel_0.set_volume(step as f32 / 10.);
+    /// }
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    // TODO: Channels treat all waves as equal and do not read their format when playing them. Another good reason to re-write the service.
-    #[doc(alias = "ndspChnSetFormat")]
-    pub fn set_format(&mut self, format: AudioFormat) {
-        unsafe { ctru_sys::ndspChnSetFormat(self.id.into(), format.into()) };
+    #[doc(alias = "ndspChnSetMix")]
+    pub fn set_volume(&mut self, volume: f32) {
+        let mut mix = self.state.mix;
+        let (left, right) = mix.front();
+        let current = ((left * left + right * right) / 2.).sqrt();
+
+        if current > 0. {
+            let scale = volume / current;
+            mix.set_front(left * scale, right * scale);
+        } else {
+            mix.set_front(volume, volume);
+        }
+
+        self.set_mix(&mix);
     }
 
-    /// Set the channel's interpolation mode.
+    /// Set the balance of the "front" left and right outputs, leaving the rest of the volume mix unchanged.
+    ///
+    /// `pan` goes from `-1.0` (left only) to `1.0` (right only), with `0.0` being centered. Values outside of this range are clamped.
+    ///
+    /// # Notes
+    ///
+    /// Constant-power panning is used, so the channel's volume (as set by [`Channel::set_volume()`]) is kept at any position.
     ///
     /// # Example
     ///
@@ -414,45 +1977,58 @@ impl Channel<'_> {
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
-    /// use ctru::services::ndsp::{InterpolationType, Ndsp};
+    /// use ctru::services::ndsp::Ndsp;
     /// let ndsp = Ndsp::new()?;
     /// let mut channel_0 = ndsp.channel(0)?;
     ///
-    /// // Use linear interpolation within frames.
-    /// channel_0.set_interpolation(InterpolationType::Linear);
+    /// // Slightly to the left.
+    /// channel_0.set_panning(-0.25);
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "ndspChnSetInterp")]
-    pub fn set_interpolation(&mut self, interp_type: InterpolationType) {
-        unsafe { ctru_sys::ndspChnSetInterp(self.id.into(), interp_type.into()) };
+    #[doc(alias = "ndspChnSetMix")]
+    pub fn set_panning(&mut self, pan: f32) {
+        let mut mix = self.state.mix;
+        let (left, right) = mix.front();
+        let volume = ((left * left + right * right) / 2.).sqrt();
+
+        // Map the position to an angle between 0 (left) and PI/2 (right).
+        // `NaN` is treated as centered.
+        let pan = if pan.is_nan() { 0. } else { pan.clamp(-1., 1.) };
+        let angle = (pan + 1.) * std::f32::consts::FRAC_PI_4;
+
+        let gain = volume * std::f32::consts::SQRT_2;
+        mix.set_front(gain * angle.cos(), gain * angle.sin());
+
+        self.set_mix(&mix);
     }
 
-    /// Set the channel's volume mix.
-    ///
-    /// Look at [`AudioMix`] for more information on the volume mix.
+    /// Set the volume at which the channel's audio is sent to the "front" outputs of the specified aux bus,
+    /// leaving the rest of the volume mix unchanged.
     ///
     /// # Example
     ///
     /// ```no_run
     /// # use std::error::Error;
-    /// # use std::default::Default;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
-    /// use ctru::services::ndsp::{AudioMix, Ndsp};
+    /// use ctru::services::ndsp::{AuxDevice, Ndsp};
     /// let ndsp = Ndsp::new()?;
     /// let mut channel_0 = ndsp.channel(0)?;
     ///
-    /// // Front-left and front-right channel maxed.
-    /// channel_0.set_mix(&AudioMix::default());
+    /// // Send part of the channel's audio through the effect of the first aux bus.
+    /// channel_0.set_aux_send(AuxDevice::Zero, 0.5);
     /// #
     /// # Ok(())
     /// # }
     /// ```
     #[doc(alias = "ndspChnSetMix")]
-    pub fn set_mix(&mut self, mix: &AudioMix) {
-        unsafe { ctru_sys::ndspChnSetMix(self.id.into(), mix.as_raw().as_ptr().cast_mut()) }
+    pub fn set_aux_send(&mut self, device: AuxDevice, volume: f32) {
+        let mut mix = self.state.mix;
+        mix.set_aux_front(volume, volume, device);
+
+        self.set_mix(&mix);
     }
 
     /// Set the channel's rate of sampling in hertz.
@@ -476,9 +2052,105 @@ impl Channel<'_> {
     #[doc(alias = "ndspChnSetRate")]
     pub fn set_sample_rate(&mut self, rate: f32) {
         unsafe { ctru_sys::ndspChnSetRate(self.id.into(), rate) };
+
+        self.state.sample_rate = rate;
+    }
+
+    /// Returns the channel's rate of sampling in hertz, as last set with [`Channel::set_sample_rate()`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// channel_0.set_sample_rate(44100.);
+    ///
+    /// assert_eq!(channel_0.sample_rate(), 44100.);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sample_rate(&self) -> f32 {
+        self.state.sample_rate
+    }
+
+    /// Set the channel's coefficients used to decode [`AudioFormat::ADPCMMono`] audio.
+    ///
+    /// The coefficients are usually stored in the header of DSP-ADPCM encoded files.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// // Provide the coefficients of your own audio data.
+    /// let coefficients = [0u16; 16];
+    ///
+    /// channel_0.set_format(AudioFormat::ADPCMMono);
+    /// channel_0.set_adpcm_coefficients(&coefficients);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnSetAdpcmCoefs")]
+    pub fn set_adpcm_coefficients(&mut self, coefficients: &[u16; 16]) {
+        // `libctru` only copies the coefficients, the mutable pointer is a quirk of the C API.
+        unsafe { ctru_sys::ndspChnSetAdpcmCoefs(self.id.into(), coefficients.as_ptr().cast_mut()) };
     }
 
-    // TODO: wrap ADPCM format helpers.
+    /// Apply a full [`ChannelConfig`] to the channel.
+    ///
+    /// All parameters are reset to their default values beforehand (like [`Channel::init_parameters()`] does),
+    /// while the wave queue is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configured sample rate isn't supported by the DSP.
+    /// In that case the channel is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, ChannelConfig, InterpolationType, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// channel_0.configure(
+    ///     &ChannelConfig::new()
+    ///         .format(AudioFormat::PCM16Stereo)
+    ///         .sample_rate(44100.)
+    ///         .interpolation(InterpolationType::Linear),
+    /// )?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn configure(&mut self, config: &ChannelConfig) -> std::result::Result<(), Error> {
+        if !SAMPLE_RATE_RANGE.contains(&config.sample_rate) {
+            return Err(Error::InvalidSampleRate);
+        }
+
+        self.init_parameters();
+        self.set_format(config.format);
+        self.set_interpolation(config.interpolation);
+        self.set_sample_rate(config.sample_rate);
+        self.set_mix(&config.mix);
+
+        Ok(())
+    }
 
     /// Clear the wave buffer queue and stop playback.
     ///
@@ -492,24 +2164,283 @@ impl Channel<'_> {
     /// let ndsp = Ndsp::new()?;
     /// let mut channel_0 = ndsp.channel(0)?;
     ///
-    /// // Clear the audio queue and stop playback.
-    /// channel_0.clear_queue();
-    /// #
-    /// # Ok(())
-    /// # }
-    /// ```
-    #[doc(alias = "ndspChnWaveBufClear")]
-    pub fn clear_queue(&mut self) {
-        unsafe { ctru_sys::ndspChnWaveBufClear(self.id.into()) };
+    /// // Clear the audio queue and stop playback.
+    /// channel_0.clear_queue();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnWaveBufClear")]
+    pub fn clear_queue(&mut self) {
+        unsafe { ctru_sys::ndspChnWaveBufClear(self.id.into()) };
+        bump_queue_generation(self.id);
+    }
+
+    /// Add a wave buffer to the channel's queue.
+    /// If there are no other buffers in queue, playback for this buffer will start.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the wave is already busy playing,
+    /// or if its audio format doesn't match the [format set on the channel](Channel::set_format).
+    /// Use [`Channel::queue_wave_unchecked()`] to skip the format check.
+    ///
+    /// # Warning
+    ///
+    /// `libctru` expects the user to manually keep the info data (in this case [`Wave`]) alive during playback.
+    /// To ensure safety, checks within [`Wave`] will clear the whole channel queue if any queued [`Wave`] is dropped prematurely
+    /// (and panic, in builds with debug assertions).
+    ///
+    /// Have a look at [`ChannelQueue`] for an alternative which takes care of the [`Wave`]s' lifetime.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #![feature(allocator_api)]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::linear::LinearAllocator;
+    /// use ctru::services::ndsp::wave::Wave;
+    /// use ctru::services::ndsp::{AudioFormat, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// # let audio_data: Box<[_], _> = Box::new_in([0u8; 96], LinearAllocator);
+    ///
+    /// // Provide your own audio data.
+    /// let mut wave = Wave::new(audio_data, AudioFormat::PCM16Stereo, false);
+    ///
+    /// channel_0.set_format(AudioFormat::PCM16Stereo);
+    /// channel_0.queue_wave(&mut wave)?;
+    ///
+    /// // The wave must not be dropped while it's still queued.
+    /// channel_0.clear_queue();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    // TODO: Find a better way to handle the wave lifetime problem.
+    //       These "alive wave" shenanigans are the most substantial reason why I'd like to fully re-write this service in Rust.
+    #[doc(alias = "ndspChnWaveBufAdd")]
+    pub fn queue_wave<Buffer: LinearAllocation + AsRef<[u8]>>(
+        &mut self,
+        wave: &mut Wave<Buffer>,
+    ) -> std::result::Result<(), Error> {
+        if wave.format() != self.state.format {
+            return Err(Error::FormatMismatch {
+                channel: self.id,
+                expected: self.state.format,
+                found: wave.format(),
+            });
+        }
+
+        self.queue_wave_unchecked(wave)
+    }
+
+    /// Add a wave buffer to the channel's queue, without checking whether its audio format matches the channel's.
+    ///
+    /// This is useful when the channel's format is changed while the wave is queued (for example, to switch format mid-stream).
+    /// Waves played with a format other than their own produce garbage audio.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the wave is already busy playing.
+    ///
+    /// # Warning
+    ///
+    /// The same lifetime requirements of [`Channel::queue_wave()`] apply.
+    #[doc(alias = "ndspChnWaveBufAdd")]
+    pub fn queue_wave_unchecked<Buffer: LinearAllocation + AsRef<[u8]>>(
+        &mut self,
+        wave: &mut Wave<Buffer>,
+    ) -> std::result::Result<(), Error> {
+        if wave.is_busy() {
+            return Err(Error::WaveBusy(self.id));
+        }
+
+        wave.set_channel(self.id);
+
+        unsafe { ctru_sys::ndspChnWaveBufAdd(self.id.into(), &mut wave.raw_data) };
+
+        Ok(())
+    }
+}
+
+impl<'ndsp, Buffer> ChannelQueue<'ndsp, Buffer>
+where
+    Buffer: LinearAllocation + AsRef<[u8]>,
+{
+    /// Create an empty queue which takes control of the given [`Channel`].
+    pub fn new(channel: Channel<'ndsp>) -> Self {
+        Self {
+            channel,
+            waves: VecDeque::new(),
+        }
+    }
+
+    /// Add a wave to the channel's queue, taking ownership of it.
+    /// If there are no other buffers in queue, playback for this buffer will start.
+    ///
+    /// The wave can be retrieved back with [`ChannelQueue::retrieve_done()`] once it has finished playing.
+    /// The returned [`WaveHandle`] can be used to check when that happens.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the wave is busy being played on another channel,
+    /// or if its audio format doesn't match the [format set on the channel](Channel::set_format).
+    #[doc(alias = "ndspChnWaveBufAdd")]
+    pub fn queue(&mut self, wave: Wave<Buffer>) -> std::result::Result<WaveHandle, Error> {
+        let mut wave = Box::new(wave);
+
+        self.channel.queue_wave(&mut wave)?;
+
+        // The sequence ID is assigned by `libctru` when queueing.
+        let handle = WaveHandle {
+            channel_id: self.channel.id,
+            sequence_id: wave.sequence_id(),
+        };
+
+        self.waves.push_back(wave);
+
+        Ok(handle)
+    }
+
+    /// Add a sequence of waves to the channel's queue, taking ownership of them.
+    ///
+    /// The waves are played back-to-back in the given order, without gaps between them.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any of the waves is busy being played on another channel.
+    /// The waves preceding it remain queued.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #![feature(allocator_api)]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::wave::Wave;
+    /// use ctru::services::ndsp::{AudioFormat, ChannelQueue, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    ///
+    /// let mut queue = ChannelQueue::new(ndsp.channel(0)?);
+    /// queue.set_format(AudioFormat::PCM16Stereo);
+    ///
+    /// # let chunks = (0..4).map(|_| Wave::zeroed(4096, AudioFormat::PCM16Stereo, false));
+    /// // Keep a few chunks of music in flight.
+    /// queue.queue_all(chunks)?;
+    ///
+    /// loop {
+    ///     // Loop the whole playlist by queueing every chunk again once it finishes.
+    ///     while let Some(chunk) = queue.retrieve_done() {
+    ///         queue.queue(chunk)?;
+    ///     }
+    /// #   break;
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnWaveBufAdd")]
+    pub fn queue_all<I>(&mut self, waves: I) -> std::result::Result<Vec<WaveHandle>, Error>
+    where
+        I: IntoIterator<Item = Wave<Buffer>>,
+    {
+        waves.into_iter().map(|wave| self.queue(wave)).collect()
+    }
+
+    /// Returns the amount of queued waves which haven't finished playing yet.
+    #[doc(alias = "pending_buffers")]
+    pub fn queued_count(&self) -> usize {
+        self.pending_waves().count()
+    }
+
+    /// Returns the amount of queued samples which haven't been played yet.
+    ///
+    /// This is useful to decide when to decode the next chunk of a stream, for example to keep a minimum length of audio queued.
+    pub fn buffered_samples(&self) -> usize {
+        let queued: usize = self.pending_waves().map(|wave| wave.sample_count()).sum();
+
+        // The position is relative to the wave currently playing.
+        queued.saturating_sub(self.sample_position())
+    }
+
+    // Returns the queued waves which haven't finished playing yet.
+    fn pending_waves(&self) -> impl Iterator<Item = &Wave<Buffer>> {
+        self.waves
+            .iter()
+            .map(|wave| &**wave)
+            .filter(|wave| wave.is_busy())
+    }
+
+    /// Returns the oldest queued wave if it has finished playing, giving back its ownership.
+    ///
+    /// Waves are played in the same order they were queued, so this function should be called repeatedly
+    /// to retrieve all finished waves.
+    pub fn retrieve_done(&mut self) -> Option<Wave<Buffer>> {
+        if self.waves.front()?.is_busy() {
+            return None;
+        }
+
+        self.waves.pop_front().map(|wave| *wave)
+    }
+
+    /// Clear the wave buffer queue and stop playback, giving back all of the queued waves.
+    #[doc(alias = "ndspChnWaveBufClear")]
+    pub fn clear_queue(&mut self) -> Vec<Wave<Buffer>> {
+        self.channel.clear_queue();
+
+        self.drain_waves()
+    }
+
+    /// Reset the channel (clear the queue and reset parameters), giving back all of the queued waves.
+    #[doc(alias = "ndspChnReset")]
+    pub fn reset(&mut self) -> Vec<Wave<Buffer>> {
+        self.channel.reset();
+
+        self.drain_waves()
+    }
+
+    /// Stop the channel's playback right away, clearing its queue and resetting its parameters, giving back all of the queued waves.
+    ///
+    /// This is equivalent to [`ChannelQueue::reset()`].
+    #[doc(alias = "ndspChnReset")]
+    pub fn stop(&mut self) -> Vec<Wave<Buffer>> {
+        self.reset()
     }
 
-    /// Add a wave buffer to the channel's queue.
-    /// If there are no other buffers in queue, playback for this buffer will start.
+    // Take back all tracked waves after the channel's queue has been cleared.
+    fn drain_waves(&mut self) -> Vec<Wave<Buffer>> {
+        self.waves
+            .drain(..)
+            .map(|mut wave| {
+                wave.mark_unqueued();
+                *wave
+            })
+            .collect()
+    }
+}
+
+impl WaveHandle {
+    /// Returns the ID of the channel on which the wave was queued.
+    pub fn channel_id(&self) -> u8 {
+        self.channel_id
+    }
+
+    /// Returns the sequence ID assigned to the wave by the NDSP service.
+    pub fn sequence_id(&self) -> u16 {
+        self.sequence_id
+    }
+
+    /// Returns whether the wave has finished playing (or has been removed from the queue).
     ///
-    /// # Warning
+    /// # Notes
     ///
-    /// `libctru` expects the user to manually keep the info data (in this case [`Wave`]) alive during playback.
-    /// To ensure safety, checks within [`Wave`] will clear the whole channel queue if any queued [`Wave`] is dropped prematurely.
+    /// The handle is checked against the waves owned by `queue`. Handles obtained from another [`ChannelQueue`]
+    /// are always reported as done.
     ///
     /// # Example
     ///
@@ -518,45 +2449,94 @@ impl Channel<'_> {
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
-    /// # use ctru::linear::LinearAllocator;
     /// use ctru::services::ndsp::wave::Wave;
-    /// use ctru::services::ndsp::{AudioFormat, Ndsp};
+    /// use ctru::services::ndsp::{AudioFormat, ChannelQueue, Ndsp};
     /// let ndsp = Ndsp::new()?;
-    /// let mut channel_0 = ndsp.channel(0)?;
-    ///
-    /// # let audio_data: Box<[_], _> = Box::new_in([0u8; 96], LinearAllocator);
+    /// let mut queue = ChannelQueue::new(ndsp.channel(0)?);
     ///
-    /// // Provide your own audio data.
-    /// let mut wave = Wave::new(audio_data, AudioFormat::PCM16Stereo, false);
+    /// let handle = queue.queue(Wave::zeroed(4096, AudioFormat::PCM16Mono, false))?;
     ///
-    /// // Clear the audio queue and stop playback.
-    /// channel_0.queue_wave(&mut wave);
+    /// if !handle.is_done(&queue) {
+    ///     println!("Still playing...");
+    /// }
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    // TODO: Find a better way to handle the wave lifetime problem.
-    //       These "alive wave" shenanigans are the most substantial reason why I'd like to fully re-write this service in Rust.
-    #[doc(alias = "ndspChnWaveBufAdd")]
-    pub fn queue_wave<Buffer: LinearAllocation + AsRef<[u8]>>(
-        &mut self,
-        wave: &mut Wave<Buffer>,
-    ) -> std::result::Result<(), Error> {
-        match wave.status() {
-            Status::Playing | Status::Queued => return Err(Error::WaveBusy(self.id)),
-            _ => (),
+    pub fn is_done<Buffer>(&self, queue: &ChannelQueue<'_, Buffer>) -> bool
+    where
+        Buffer: LinearAllocation + AsRef<[u8]>,
+    {
+        if queue.channel.id != self.channel_id {
+            return true;
         }
 
-        wave.set_channel(self.id);
+        !queue
+            .waves
+            .iter()
+            .any(|wave| wave.sequence_id() == self.sequence_id && wave.is_busy())
+    }
 
-        unsafe { ctru_sys::ndspChnWaveBufAdd(self.id.into(), &mut wave.raw_data) };
+    /// Block the current thread until the wave has finished playing (or has been removed from the queue).
+    ///
+    /// Look at [`WaveHandle::is_done()`] for more information.
+    pub fn wait<Buffer>(&self, queue: &ChannelQueue<'_, Buffer>)
+    where
+        Buffer: LinearAllocation + AsRef<[u8]>,
+    {
+        while !self.is_done(queue) {
+            std::thread::sleep(DSP_FRAME_DURATION);
+        }
+    }
+}
 
-        Ok(())
+impl<'ndsp, Buffer> Deref for ChannelQueue<'ndsp, Buffer>
+where
+    Buffer: LinearAllocation + AsRef<[u8]>,
+{
+    type Target = Channel<'ndsp>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.channel
+    }
+}
+
+impl<Buffer> DerefMut for ChannelQueue<'_, Buffer>
+where
+    Buffer: LinearAllocation + AsRef<[u8]>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.channel
+    }
+}
+
+impl Drop for PauseGuard<'_> {
+    #[doc(alias = "ndspChnSetPaused")]
+    fn drop(&mut self) {
+        for id in 0..NUMBER_OF_CHANNELS {
+            if self.resumed & (1 << id) != 0 {
+                unsafe { ctru_sys::ndspChnSetPaused(id.into(), false) };
+            }
+        }
+    }
+}
+
+impl<Buffer> Drop for ChannelQueue<'_, Buffer>
+where
+    Buffer: LinearAllocation + AsRef<[u8]>,
+{
+    fn drop(&mut self) {
+        // Stop any playback before the queued waves get dropped.
+        self.channel.clear_queue();
+        self.drain_waves();
     }
 }
 
 /// Functions to handle audio filtering.
 ///
+/// The `iir_*` functions map directly to the ones of `libctru`, without validating the parameters.
+/// The biquad filter is also available through [`Channel::set_lowpass()`] and its siblings, which validate the parameters first.
+///
 /// Refer to [`libctru`](https://libctru.devkitpro.org/channel_8h.html#a1da3b363c2edfd318c92276b527daae6) for more info.
 impl Channel<'_> {
     /// Enables/disables monopole filters.
@@ -640,6 +2620,148 @@ impl Channel<'_> {
             )
         };
     }
+
+    /// Enables/disables the biquad filter set with [`Channel::set_lowpass()`] and its siblings.
+    ///
+    /// # Notes
+    ///
+    /// Setting the parameters of a filter already enables it.
+    #[doc(alias = "ndspChnIirBiquadSetEnable")]
+    pub fn set_filter_enabled(&mut self, enabled: bool) {
+        self.iir_biquad_set_enabled(enabled);
+    }
+
+    /// Set a biquad low pass filter, cutting off the frequencies above `cutoff_hz`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the parameters are out of their valid range:
+    /// the frequency must be positive and lower than half of the channel's [sample rate](Channel::set_sample_rate) (and of the DSP's output rate,
+    /// roughly 32728 Hz), and the quality factor (`q`) must be positive. An error is also returned if the DSP can't represent the resulting filter.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// channel_0.set_sample_rate(44100.);
+    ///
+    /// // Muffle the audio by cutting off high frequencies, as if it came from underwater.
+    /// channel_0.set_lowpass(800., 0.707)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnIirBiquadSetParamsLowPassFilter")]
+    pub fn set_lowpass(&mut self, cutoff_hz: f32, q: f32) -> std::result::Result<(), Error> {
+        self.check_filter_params(cutoff_hz, q)?;
+
+        filter_result(unsafe {
+            ctru_sys::ndspChnIirBiquadSetParamsLowPassFilter(self.id.into(), cutoff_hz, q)
+        })
+    }
+
+    /// Set a biquad high pass filter, cutting off the frequencies below `cutoff_hz`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the parameters are out of their valid range.
+    /// Look at [`Channel::set_lowpass()`] for more information.
+    #[doc(alias = "ndspChnIirBiquadSetParamsHighPassFilter")]
+    pub fn set_highpass(&mut self, cutoff_hz: f32, q: f32) -> std::result::Result<(), Error> {
+        self.check_filter_params(cutoff_hz, q)?;
+
+        filter_result(unsafe {
+            ctru_sys::ndspChnIirBiquadSetParamsHighPassFilter(self.id.into(), cutoff_hz, q)
+        })
+    }
+
+    /// Set a biquad band pass filter, keeping only the frequencies around `center_hz`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the parameters are out of their valid range.
+    /// Look at [`Channel::set_lowpass()`] for more information.
+    #[doc(alias = "ndspChnIirBiquadSetParamsBandPassFilter")]
+    pub fn set_bandpass(&mut self, center_hz: f32, q: f32) -> std::result::Result<(), Error> {
+        self.check_filter_params(center_hz, q)?;
+
+        filter_result(unsafe {
+            ctru_sys::ndspChnIirBiquadSetParamsBandPassFilter(self.id.into(), center_hz, q)
+        })
+    }
+
+    /// Set a biquad notch filter, removing the frequencies around `center_hz`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the parameters are out of their valid range.
+    /// Look at [`Channel::set_lowpass()`] for more information.
+    #[doc(alias = "ndspChnIirBiquadSetParamsNotchFilter")]
+    pub fn set_notch(&mut self, center_hz: f32, q: f32) -> std::result::Result<(), Error> {
+        self.check_filter_params(center_hz, q)?;
+
+        filter_result(unsafe {
+            ctru_sys::ndspChnIirBiquadSetParamsNotchFilter(self.id.into(), center_hz, q)
+        })
+    }
+
+    /// Set a biquad peaking equalizer, amplifying (or attenuating) the frequencies around `center_hz` by `gain`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the parameters are out of their valid range, or if `gain` isn't a positive number.
+    /// Look at [`Channel::set_lowpass()`] for more information.
+    #[doc(alias = "ndspChnIirBiquadSetParamsPeakingEqualizer")]
+    pub fn set_peaking_eq(
+        &mut self,
+        center_hz: f32,
+        q: f32,
+        gain: f32,
+    ) -> std::result::Result<(), Error> {
+        self.check_filter_params(center_hz, q)?;
+
+        if !(gain > 0. && gain.is_finite()) {
+            return Err(Error::InvalidFilterParameters);
+        }
+
+        filter_result(unsafe {
+            ctru_sys::ndspChnIirBiquadSetParamsPeakingEqualizer(self.id.into(), center_hz, q, gain)
+        })
+    }
+
+    // Checks the parameters of a filter against the channel's audio.
+    //
+    // The DSP filters the audio after resampling it to its own output rate, but the channel's audio can't hold frequencies
+    // above half of its own sample rate (the Nyquist frequency) either.
+    fn check_filter_params(&self, frequency: f32, quality: f32) -> std::result::Result<(), Error> {
+        let nyquist = self.state.sample_rate.min(DSP_SAMPLE_RATE) / 2.;
+
+        if frequency.is_finite()
+            && frequency > 0.
+            && frequency < nyquist
+            && quality.is_finite()
+            && quality > 0.
+        {
+            Ok(())
+        } else {
+            Err(Error::InvalidFilterParameters)
+        }
+    }
+}
+
+// `libctru` returns `false` if the resulting filter coefficients don't fit in the DSP's fixed point format.
+fn filter_result(success: bool) -> std::result::Result<(), Error> {
+    if success {
+        Ok(())
+    } else {
+        Err(Error::InvalidFilterParameters)
+    }
 }
 
 impl AudioFormat {
@@ -649,13 +2771,173 @@ impl AudioFormat {
     ///
     /// - 8 bit mono formats return 1 (byte)
     /// - 16 bit stereo (dual-channel) formats return 4 (bytes)
+    ///
+    /// # Panics
+    ///
+    /// [`AudioFormat::ADPCMMono`] samples take half a byte each, so this function panics for that format.
+    /// Use [`AudioFormat::frame_size()`] for a non-panicking alternative.
     pub const fn size(self) -> usize {
+        match self.frame_size() {
+            Some(size) => size,
+            None => panic!("ADPCM samples are smaller than a byte"),
+        }
+    }
+
+    /// Returns the amount of bytes needed to store one sample for all channels, if it's a whole amount.
+    ///
+    /// [`AudioFormat::ADPCMMono`] samples take half a byte each (without accounting for the frame headers), so `None` is returned for that format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use ctru::services::ndsp::AudioFormat;
+    ///
+    /// assert_eq!(AudioFormat::PCM16Stereo.frame_size(), Some(4));
+    /// assert_eq!(AudioFormat::ADPCMMono.frame_size(), None);
+    /// ```
+    pub const fn frame_size(self) -> Option<usize> {
+        match self {
+            Self::ADPCMMono => None,
+            _ => Some(self.channel_count() * self.bits_per_sample() / 8),
+        }
+    }
+
+    /// Returns the audio format made up of the given amount of channels and sample encoding, if it's supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use ctru::services::ndsp::{AudioFormat, SampleEncoding};
+    ///
+    /// assert_eq!(
+    ///     AudioFormat::from_parts(2, SampleEncoding::Pcm16),
+    ///     Some(AudioFormat::PCM16Stereo)
+    /// );
+    ///
+    /// // Stereo ADPCM isn't supported by the DSP.
+    /// assert_eq!(AudioFormat::from_parts(2, SampleEncoding::Adpcm), None);
+    /// ```
+    pub const fn from_parts(channel_count: usize, encoding: SampleEncoding) -> Option<Self> {
+        match (channel_count, encoding) {
+            (1, SampleEncoding::Pcm8) => Some(Self::PCM8Mono),
+            (1, SampleEncoding::Pcm16) => Some(Self::PCM16Mono),
+            (1, SampleEncoding::Adpcm) => Some(Self::ADPCMMono),
+            (2, SampleEncoding::Pcm8) => Some(Self::PCM8Stereo),
+            (2, SampleEncoding::Pcm16) => Some(Self::PCM16Stereo),
+            _ => None,
+        }
+    }
+
+    /// Returns the encoding of the samples.
+    pub const fn encoding(self) -> SampleEncoding {
+        match self {
+            Self::PCM8Mono | Self::PCM8Stereo => SampleEncoding::Pcm8,
+            Self::PCM16Mono | Self::PCM16Stereo => SampleEncoding::Pcm16,
+            Self::ADPCMMono => SampleEncoding::Adpcm,
+        }
+    }
+
+    /// Returns the amount of audio channels (1 for mono, 2 for stereo).
+    pub const fn channel_count(self) -> usize {
+        match self {
+            Self::PCM8Mono | Self::PCM16Mono | Self::ADPCMMono => 1,
+            Self::PCM8Stereo | Self::PCM16Stereo => 2,
+        }
+    }
+
+    /// Returns the amount of bits used to store a single channel's sample.
+    pub const fn bits_per_sample(self) -> usize {
+        match self.encoding() {
+            SampleEncoding::Adpcm => 4,
+            SampleEncoding::Pcm8 => 8,
+            SampleEncoding::Pcm16 => 16,
+        }
+    }
+
+    // Returns the amount of whole samples stored in a buffer of the given length.
+    pub(crate) const fn sample_count(self, bytes: usize) -> usize {
+        match self {
+            // DSP-ADPCM data is split into 8 byte frames, each made up of a 1 byte header and 14 4bit samples.
+            Self::ADPCMMono => {
+                let remainder = bytes % 8;
+                let partial = if remainder > 1 { remainder - 1 } else { 0 };
+
+                (bytes / 8) * 14 + partial * 2
+            }
+            _ => bytes / self.size(),
+        }
+    }
+
+    // Returns the amount of bytes needed to store the given amount of samples.
+    pub(crate) const fn byte_count(self, samples: usize) -> usize {
         match self {
-            Self::PCM8Mono => 1,
-            Self::PCM16Mono | Self::PCM8Stereo => 2,
-            Self::PCM16Stereo => 4,
+            Self::ADPCMMono => {
+                let remainder = samples % 14;
+                let partial = if remainder > 0 {
+                    1 + remainder.div_ceil(2)
+                } else {
+                    0
+                };
+
+                (samples / 14) * 8 + partial
+            }
+            _ => samples * self.size(),
+        }
+    }
+}
+
+impl ChannelConfig {
+    /// Creates a new [`ChannelConfig`] with default values.
+    pub fn new() -> Self {
+        Self {
+            format: AudioFormat::PCM16Mono,
+            sample_rate: 1.,
+            interpolation: InterpolationType::Polyphase,
+            mix: AudioMix::default(),
         }
     }
+
+    /// Set the audio format of the waves played by the channel.
+    pub fn format(mut self, format: AudioFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set the rate of sampling in hertz.
+    ///
+    /// # Notes
+    ///
+    /// The DSP supports sample rates between 1 Hz and 96 kHz. The value is checked by [`Channel::configure()`].
+    pub fn sample_rate(mut self, rate: f32) -> Self {
+        self.sample_rate = rate;
+        self
+    }
+
+    /// Set the interpolation mode.
+    pub fn interpolation(mut self, interp_type: InterpolationType) -> Self {
+        self.interpolation = interp_type;
+        self
+    }
+
+    /// Set the whole volume mix.
+    pub fn mix(mut self, mix: AudioMix) -> Self {
+        self.mix = mix;
+        self
+    }
+
+    /// Set the volume of the "front" left and right outputs, leaving the rest of the mix unchanged.
+    pub fn volume(mut self, volume: f32) -> Self {
+        self.mix.set_front(volume, volume);
+        self
+    }
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AudioMix {
@@ -747,6 +3029,29 @@ impl AudioMix {
     }
 }
 
+impl Default for ChannelState {
+    // Values set by `libctru` on initialization.
+    fn default() -> Self {
+        Self {
+            format: AudioFormat::PCM16Mono,
+            sample_rate: 1.,
+            interpolation: InterpolationType::Polyphase,
+            mix: AudioMix::default(),
+        }
+    }
+}
+
+impl Default for OutputState {
+    // Values set by `libctru` on initialization.
+    fn default() -> Self {
+        Self {
+            mode: OutputMode::Stereo,
+            clipping_mode: ClippingMode::Soft,
+            master_volume: 1.0,
+        }
+    }
+}
+
 impl Default for AudioMix {
     /// Returns an [`AudioMix`] object with "front left" and "front right" volumes set to 100%, and all other volumes set to 0%.
     fn default() -> Self {
@@ -763,6 +3068,28 @@ impl From<[f32; 12]> for AudioMix {
     }
 }
 
+impl From<AudioMix> for [f32; 12] {
+    fn from(value: AudioMix) -> Self {
+        value.raw
+    }
+}
+
+impl Error {
+    /// Returns the common service failure described by the error, if any.
+    ///
+    /// Channels and aux buses in use are reported as [`ServiceError::Busy`], and channel IDs out of range as [`ServiceError::NotFound`].
+    pub fn service_error(&self) -> Option<ServiceError> {
+        match self {
+            Self::InvalidChannel(_) => Some(ServiceError::NotFound),
+            Self::ChannelAlreadyInUse(_) | Self::WaveBusy(_) | Self::AuxBusAlreadyInUse(_) => {
+                Some(ServiceError::Busy)
+            }
+            Self::OutOfLinearMemory => Some(ServiceError::OutOfMemory),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -770,21 +3097,181 @@ impl fmt::Display for Error {
             Self::ChannelAlreadyInUse(id) => write!(f, "audio Channel with ID {id} is already being used. Drop the other instance if you want to use it here"),
             Self::WaveBusy(id) => write!(f, "the selected Wave is busy playing on channel {id}"),
             Self::SampleCountOutOfBounds(samples_requested, max_samples) => write!(f, "the sample count requested is too big (requested = {samples_requested}, maximum = {max_samples})"),
+            Self::AuxBusAlreadyInUse(device) => write!(f, "aux bus {} is already being used. Drop the other instance if you want to use it here", *device as usize),
+            Self::InvalidEffectParameters => write!(f, "the effect parameters are out of their valid range"),
+            Self::InvalidSampleType(format) => write!(f, "the requested sample type doesn't match the wave's audio format ({format:?})"),
+            Self::InvalidWavData => write!(f, "the provided data isn't a valid WAV file"),
+            Self::UnsupportedWavFormat => write!(f, "the WAV file's audio format isn't supported. Only 8 and 16 bit PCM audio with 1 or 2 channels can be played"),
+            Self::UnalignedStartOffset(offset) => write!(f, "the start offset {offset} doesn't match the start of an ADPCM frame. ADPCM offsets must be a multiple of 14"),
+            Self::InvalidFilterParameters => write!(f, "the filter parameters are out of their valid range. Frequencies must be lower than half the channel's sample rate"),
+            Self::InvalidSampleRate => write!(f, "the sample rate requested is not supported. Valid sample rates are between 1 Hz and 96 kHz"),
+            Self::InvalidStereoSampleCount(samples) => write!(f, "stereo audio data must hold an even amount of samples (provided = {samples})"),
+            Self::OutOfLinearMemory => write!(f, "there isn't enough free LINEAR memory to allocate the wave's data"),
+            Self::UnsupportedFormat(format) => write!(f, "the requested operation isn't supported for waves in the {format:?} format"),
+            Self::FormatMismatch { channel, expected, found } => write!(f, "the wave's audio format ({found:?}) doesn't match the format set on channel {channel} ({expected:?})"),
         }
     }
 }
 
 impl error::Error for Error {}
 
+// Returns the amount of times the queue of the specified channel has been cleared.
+pub(crate) fn queue_generation(id: u8) -> u32 {
+    QUEUE_GENERATIONS[id as usize].load(Ordering::Acquire)
+}
+
+// Signal that the queue of the specified channel has been cleared, along with all of the waves in it.
+pub(crate) fn bump_queue_generation(id: u8) {
+    QUEUE_GENERATIONS[id as usize].fetch_add(1, Ordering::Release);
+}
+
+// SAFETY: The state of a slot is only accessed by the single `Channel` which set its `in_use` flag.
+unsafe impl Sync for ChannelSlot {}
+
+impl Drop for Channel<'_> {
+    fn drop(&mut self) {
+        self.in_use.store(false, Ordering::Release);
+    }
+}
+
 impl Drop for Ndsp {
     #[doc(alias = "ndspExit")]
     fn drop(&mut self) {
-        for i in 0..NUMBER_OF_CHANNELS {
-            self.channel(i).unwrap().reset();
+        self.reset_all();
+
+        unsafe { ctru_sys::ndspSetCallback(None, std::ptr::null_mut()) };
+        self.clear_frame_callback();
+
+        // Don't leave the waiters blocked forever.
+        self.frame_state.end_frame(true);
+
+        for id in 0..self.aux_effects.len() {
+            unsafe { ctru_sys::ndspAuxSetCallback(id as _, None, std::ptr::null_mut()) };
         }
     }
 }
 
 from_impl!(InterpolationType, ctru_sys::ndspInterpType);
 from_impl!(OutputMode, ctru_sys::ndspOutputMode);
+from_impl!(SpeakerPosition, ctru_sys::ndspSpeakerPos);
+from_impl!(ClippingMode, ctru_sys::ndspClippingMode);
 from_impl!(AudioFormat, u16);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_FORMATS: [AudioFormat; 5] = [
+        AudioFormat::PCM8Mono,
+        AudioFormat::PCM16Mono,
+        AudioFormat::ADPCMMono,
+        AudioFormat::PCM8Stereo,
+        AudioFormat::PCM16Stereo,
+    ];
+
+    #[test]
+    fn frame_waiter_closing() {
+        let state = Arc::new(FrameState {
+            callback: Mutex::new(None),
+            frames: Mutex::default(),
+            frame_ended: Condvar::new(),
+        });
+        let waiter = FrameWaiter {
+            state: Arc::clone(&state),
+        };
+
+        assert!(!waiter.is_closed());
+
+        // Closing the service wakes up the waiters for good.
+        state.end_frame(true);
+
+        assert!(waiter.is_closed());
+        assert!(!waiter.wait());
+        assert!(!waiter.clone().wait());
+    }
+
+    #[test]
+    fn reverb_parameters() {
+        let reverb = |room_size, damping| ReverbLine::new_outputs(Reverb { room_size, damping });
+
+        assert!(reverb(0.0, 0.0).is_ok());
+        assert!(reverb(1.0, 1.0).is_ok());
+
+        for (room_size, damping) in [
+            (-0.1, 0.5),
+            (1.1, 0.5),
+            (0.5, -0.1),
+            (0.5, 1.1),
+            (f32::NAN, 0.5),
+        ] {
+            assert_eq!(
+                reverb(room_size, damping).err(),
+                Some(Error::InvalidEffectParameters)
+            );
+        }
+    }
+
+    #[test]
+    fn reverb_impulse_response() {
+        let mut effect = AuxEffect::Reverb(
+            ReverbLine::new_outputs(Reverb {
+                room_size: 0.5,
+                damping: 0.5,
+            })
+            .unwrap(),
+        );
+
+        // A single impulse, followed by silence for a whole second.
+        let mut outputs = [(); 4].map(|_| vec![0i32; DSP_SAMPLE_RATE as usize]);
+        for output in &mut outputs {
+            output[0] = 1 << 20;
+        }
+
+        let mut samples = outputs.each_mut().map(Vec::as_mut_slice);
+        effect.process(&mut samples);
+
+        for output in &outputs {
+            let energy = |range: std::ops::Range<usize>| {
+                output[range]
+                    .iter()
+                    .map(|&s| (s as f64).powi(2))
+                    .sum::<f64>()
+            };
+            let quarter = output.len() / 4;
+
+            // The reverberation keeps ringing after the impulse, then fades out.
+            assert!(energy(0..quarter) > 0.);
+            assert!(energy(3 * quarter..4 * quarter) < energy(0..quarter));
+        }
+
+        // The right outputs are delayed differently from the left ones.
+        assert_ne!(outputs[0], outputs[1]);
+        assert_eq!(outputs[0], outputs[2]);
+    }
+
+    #[test]
+    fn audio_format_round_trip() {
+        for format in ALL_FORMATS {
+            assert_eq!(
+                AudioFormat::from_parts(format.channel_count(), format.encoding()),
+                Some(format)
+            );
+        }
+    }
+
+    #[test]
+    fn audio_format_unsupported_parts() {
+        let encodings = [
+            SampleEncoding::Pcm8,
+            SampleEncoding::Pcm16,
+            SampleEncoding::Adpcm,
+        ];
+
+        for encoding in encodings {
+            assert_eq!(AudioFormat::from_parts(0, encoding), None);
+            assert_eq!(AudioFormat::from_parts(3, encoding), None);
+        }
+
+        assert_eq!(AudioFormat::from_parts(2, SampleEncoding::Adpcm), None);
+    }
+}
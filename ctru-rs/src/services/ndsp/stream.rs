@@ -0,0 +1,310 @@
+//! Audio streaming.
+//!
+//! This module holds [`AudioStream`], which plays audio produced on the fly (for example, by a decoder)
+//! by continuously refilling and re-queueing a set of [`Wave`]s.
+
+use super::wave::Wave;
+use super::{AudioFormat, Channel, ChannelQueue, Error};
+use crate::linear::LinearAllocator;
+
+use std::ops::{Deref, DerefMut};
+
+type StreamWave = Wave<Box<[u8], LinearAllocator>>;
+
+/// Playback state reported by [`AudioStream::poll()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StreamStatus {
+    /// Audio is playing.
+    Playing,
+    /// All queued audio finished playing before new data was provided, leaving a gap in the playback.
+    ///
+    /// Playback resumes with the newly provided data, if any.
+    Underrun,
+    /// The source has ended and all of its audio has been played.
+    Finished,
+}
+
+/// Continuous audio playback on a [`Channel`], fed by a source of audio data.
+///
+/// The stream owns a set of buffers on the [LINEAR memory](crate::linear), which are refilled as soon as they finish playing.
+/// All other [`Channel`] functionality remains available through [`Deref`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::ndsp::stream::{AudioStream, StreamStatus};
+/// use ctru::services::ndsp::{AudioFormat, Ndsp};
+/// let ndsp = Ndsp::new()?;
+///
+/// let mut stream = AudioStream::new(ndsp.channel(0)?, AudioFormat::PCM16Stereo, 3, 2048);
+/// stream.set_sample_rate(44100.);
+///
+/// # let mut decode_audio = |_: &mut [u8]| 0;
+/// // Refill the buffers until the source runs out of audio.
+/// while stream.poll(|buffer| decode_audio(buffer))? != StreamStatus::Finished {
+///     // Do other work while the audio plays.
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct AudioStream<'ndsp> {
+    queue: ChannelQueue<'ndsp, Box<[u8], LinearAllocator>>,
+    // Waves which aren't queued, ready to be refilled.
+    free_waves: Vec<StreamWave>,
+    // Trailing bytes of an incomplete sample written by the source, moved to the start of the next buffer.
+    pending: Vec<u8>,
+    started: bool,
+    ended: bool,
+}
+
+impl<'ndsp> AudioStream<'ndsp> {
+    /// Create a new stream playing on the given [`Channel`], with `buffer_count` buffers holding `samples_per_buffer` samples each.
+    ///
+    /// The channel's format is set to `audio_format`, while all other parameters are left untouched.
+    ///
+    /// # Notes
+    ///
+    /// At least 2 buffers are needed to play audio without gaps. Longer buffers make underruns less likely, but increase latency.
+    pub fn new(
+        mut channel: Channel<'ndsp>,
+        audio_format: AudioFormat,
+        buffer_count: usize,
+        samples_per_buffer: usize,
+    ) -> Self {
+        channel.set_format(audio_format);
+
+        let free_waves = (0..buffer_count)
+            .map(|_| Wave::zeroed(samples_per_buffer, audio_format, false))
+            .collect();
+
+        Self {
+            queue: ChannelQueue::new(channel),
+            free_waves,
+            pending: Vec::new(),
+            started: false,
+            ended: false,
+        }
+    }
+
+    /// Refill and queue all buffers which have finished playing, then report the playback state.
+    ///
+    /// `fill` is called with each free buffer and must return the amount of bytes written to it.
+    /// Returning 0 signals the end of the stream: the remaining queued audio is played, after which [`StreamStatus::Finished`] is returned.
+    ///
+    /// The source doesn't need to write whole samples: the bytes of an incomplete sample are kept and played along with the rest
+    /// of the sample, once it has been written by the next call to `fill`. An incomplete sample left at the end of the stream is discarded.
+    ///
+    /// This function should be called often enough (for example, once per frame) to keep the channel busy.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stream's buffers are altered while queued (for example, by clearing the channel's queue).
+    pub fn poll<F>(&mut self, mut fill: F) -> Result<StreamStatus, Error>
+    where
+        F: FnMut(&mut [u8]) -> usize,
+    {
+        while let Some(wave) = self.queue.retrieve_done() {
+            self.free_waves.push(wave);
+        }
+
+        // Everything queued has been played, but the source hasn't ended.
+        let underrun = self.started && !self.ended && self.queue.waves.is_empty();
+
+        while !self.ended {
+            let Some(mut wave) = self.free_waves.pop() else {
+                break;
+            };
+
+            let audio_format = wave.format();
+            let buffer = wave.get_buffer_mut()?;
+
+            let Some(len) = fill_buffer(buffer, &mut self.pending, audio_format, &mut fill) else {
+                self.ended = true;
+                self.free_waves.push(wave);
+
+                break;
+            };
+
+            // Not even a whole sample yet, so the buffer is filled again with the bytes written so far.
+            if len == 0 {
+                self.free_waves.push(wave);
+
+                continue;
+            }
+
+            wave.set_sample_count(audio_format.sample_count(len))?;
+            // The result is ignored, like in `Wave::new()`.
+            let _ = wave.flush_range(0..len);
+
+            self.queue.queue(wave)?;
+            self.started = true;
+        }
+
+        if self.ended && self.queue.waves.is_empty() {
+            Ok(StreamStatus::Finished)
+        } else if underrun {
+            Ok(StreamStatus::Underrun)
+        } else {
+            Ok(StreamStatus::Playing)
+        }
+    }
+
+    /// Returns the amount of samples queued on the channel which haven't been played yet.
+    pub fn buffered_samples(&self) -> usize {
+        self.queue.buffered_samples()
+    }
+
+    /// Returns whether the source has ended.
+    ///
+    /// The stream's audio may still be playing. Use [`AudioStream::poll()`] to know when playback has finished.
+    pub fn is_ended(&self) -> bool {
+        self.ended
+    }
+}
+
+// Fill `buffer` with the pending bytes of the last incomplete sample, followed by new data from `fill`.
+//
+// Returns the length of the whole samples at the start of the buffer, storing the bytes of the new incomplete sample (if any) in `pending`,
+// or `None` if the source has ended.
+fn fill_buffer(
+    buffer: &mut [u8],
+    pending: &mut Vec<u8>,
+    audio_format: AudioFormat,
+    fill: &mut impl FnMut(&mut [u8]) -> usize,
+) -> Option<usize> {
+    // The pending bytes are always shorter than a sample, and thus shorter than the buffer.
+    let start = pending.len().min(buffer.len());
+    buffer[..start].copy_from_slice(&pending[..start]);
+
+    let written = fill(&mut buffer[start..]).min(buffer.len() - start);
+
+    if written == 0 {
+        pending.clear();
+
+        return None;
+    }
+
+    let filled = start + written;
+    let len = audio_format.byte_count(audio_format.sample_count(filled));
+
+    pending.clear();
+    pending.extend_from_slice(&buffer[len..filled]);
+
+    Some(len)
+}
+
+impl<'ndsp> Deref for AudioStream<'ndsp> {
+    type Target = Channel<'ndsp>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.queue
+    }
+}
+
+impl DerefMut for AudioStream<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.queue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_buffer_partial_samples() {
+        let mut buffer = [0u8; 8];
+        let mut pending = Vec::new();
+
+        // Source producing 3 bytes at a time, which never lines up with the 4 byte stereo samples.
+        let mut source = (1..=9u8).collect::<Vec<_>>().into_iter();
+        let mut fill = |buffer: &mut [u8]| {
+            let mut written = 0;
+
+            for (byte, value) in buffer.iter_mut().zip(source.by_ref()).take(3) {
+                *byte = value;
+                written += 1;
+            }
+
+            written
+        };
+
+        // No whole sample yet, the 3 bytes are kept for later.
+        assert_eq!(
+            fill_buffer(
+                &mut buffer,
+                &mut pending,
+                AudioFormat::PCM16Stereo,
+                &mut fill
+            ),
+            Some(0)
+        );
+        assert_eq!(pending, [1, 2, 3]);
+
+        // The pending bytes are completed by the new ones.
+        assert_eq!(
+            fill_buffer(
+                &mut buffer,
+                &mut pending,
+                AudioFormat::PCM16Stereo,
+                &mut fill
+            ),
+            Some(4)
+        );
+        assert_eq!(buffer[..4], [1, 2, 3, 4]);
+        assert_eq!(pending, [5, 6]);
+
+        assert_eq!(
+            fill_buffer(
+                &mut buffer,
+                &mut pending,
+                AudioFormat::PCM16Stereo,
+                &mut fill
+            ),
+            Some(4)
+        );
+        assert_eq!(buffer[..4], [5, 6, 7, 8]);
+        assert_eq!(pending, [9]);
+
+        // The source has ended, so the incomplete sample is discarded.
+        assert_eq!(
+            fill_buffer(
+                &mut buffer,
+                &mut pending,
+                AudioFormat::PCM16Stereo,
+                &mut fill
+            ),
+            None
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn fill_buffer_whole_samples() {
+        let mut buffer = [0u8; 6];
+        let mut pending = Vec::new();
+
+        let mut fill = |buffer: &mut [u8]| {
+            buffer.fill(0x7F);
+            buffer.len()
+        };
+
+        assert_eq!(
+            fill_buffer(&mut buffer, &mut pending, AudioFormat::PCM16Mono, &mut fill),
+            Some(6)
+        );
+        assert!(pending.is_empty());
+
+        // Lengths returned past the end of the buffer are clamped to it.
+        let mut fill = |_: &mut [u8]| 100;
+
+        assert_eq!(
+            fill_buffer(&mut buffer, &mut pending, AudioFormat::PCM8Mono, &mut fill),
+            Some(6)
+        );
+    }
+}
@@ -0,0 +1,325 @@
+// Minimal RIFF/WAVE parser, used to load PCM audio into `Wave`s.
+
+use super::{AudioFormat, Error, SAMPLE_RATE_RANGE};
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+// Contents of a WAV file, borrowed from the file's bytes.
+pub(crate) struct WavData<'a> {
+    pub audio_format: AudioFormat,
+    pub sample_rate: u32,
+    // Raw sample data, trimmed to a whole amount of samples.
+    pub data: &'a [u8],
+}
+
+pub(crate) fn parse(bytes: &[u8]) -> Result<WavData<'_>, Error> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(Error::InvalidWavData);
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut chunks = &bytes[12..];
+
+    while chunks.len() >= 8 {
+        let id = &chunks[0..4];
+        let size = read_u32(chunks, 4) as usize;
+        let body = &chunks[8..];
+
+        // Some encoders write bogus sizes for the "data" chunk (e.g. when streaming), so it's cut to the end of the file.
+        let body = &body[..size.min(body.len())];
+
+        match id {
+            b"fmt " => format = Some(parse_format(body)?),
+            b"data" => data = Some(body),
+            _ => (),
+        }
+
+        // Chunks are padded to an even size.
+        let next = 8usize.saturating_add(size).saturating_add(size % 2);
+        chunks = chunks.get(next..).unwrap_or_default();
+    }
+
+    let (Some((audio_format, sample_rate)), Some(data)) = (format, data) else {
+        return Err(Error::InvalidWavData);
+    };
+
+    // The frame size is always known for PCM formats.
+    let frame_size = audio_format.size();
+
+    Ok(WavData {
+        audio_format,
+        sample_rate,
+        data: &data[..data.len() - data.len() % frame_size],
+    })
+}
+
+fn parse_format(body: &[u8]) -> Result<(AudioFormat, u32), Error> {
+    if body.len() < 16 {
+        return Err(Error::InvalidWavData);
+    }
+
+    let mut codec = read_u16(body, 0);
+    let channels = read_u16(body, 2);
+    let sample_rate = read_u32(body, 4);
+    let bits_per_sample = read_u16(body, 14);
+
+    // The actual codec of extensible formats is stored in the first 2 bytes of the sub-format GUID.
+    if codec == WAVE_FORMAT_EXTENSIBLE {
+        if body.len() < 26 {
+            return Err(Error::InvalidWavData);
+        }
+
+        codec = read_u16(body, 24);
+    }
+
+    if codec != WAVE_FORMAT_PCM {
+        return Err(Error::UnsupportedWavFormat);
+    }
+
+    let audio_format = match (channels, bits_per_sample) {
+        (1, 8) => AudioFormat::PCM8Mono,
+        (1, 16) => AudioFormat::PCM16Mono,
+        (2, 8) => AudioFormat::PCM8Stereo,
+        (2, 16) => AudioFormat::PCM16Stereo,
+        _ => return Err(Error::UnsupportedWavFormat),
+    };
+
+    // The sample rate is later set on a `Channel`, which only accepts rates in this range.
+    if !SAMPLE_RATE_RANGE.contains(&(sample_rate as f32)) {
+        return Err(Error::InvalidWavData);
+    }
+
+    Ok((audio_format, sample_rate))
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Build a WAV file out of its chunks, with a correct RIFF header.
+    fn riff(chunks: &[&[u8]]) -> Vec<u8> {
+        let body = chunks.concat();
+
+        let mut file = b"RIFF".to_vec();
+        file.extend_from_slice(&(body.len() as u32 + 4).to_le_bytes());
+        file.extend_from_slice(b"WAVE");
+        file.extend_from_slice(&body);
+        file
+    }
+
+    fn chunk(id: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut chunk = id.to_vec();
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(body);
+
+        // Chunks are padded to an even size.
+        if body.len() % 2 != 0 {
+            chunk.push(0);
+        }
+
+        chunk
+    }
+
+    fn fmt(codec: u16, channels: u16, sample_rate: u32, bits_per_sample: u16) -> Vec<u8> {
+        let block_align = channels * bits_per_sample / 8;
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&codec.to_le_bytes());
+        body.extend_from_slice(&channels.to_le_bytes());
+        body.extend_from_slice(&sample_rate.to_le_bytes());
+        body.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+        body.extend_from_slice(&block_align.to_le_bytes());
+        body.extend_from_slice(&bits_per_sample.to_le_bytes());
+        body
+    }
+
+    fn extensible_fmt(
+        channels: u16,
+        sample_rate: u32,
+        bits_per_sample: u16,
+        codec: u16,
+    ) -> Vec<u8> {
+        let mut body = fmt(
+            WAVE_FORMAT_EXTENSIBLE,
+            channels,
+            sample_rate,
+            bits_per_sample,
+        );
+        // Extension size, valid bits per sample and channel mask.
+        body.extend_from_slice(&22u16.to_le_bytes());
+        body.extend_from_slice(&bits_per_sample.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        // Sub-format GUID, starting with the actual codec.
+        body.extend_from_slice(&codec.to_le_bytes());
+        body.extend_from_slice(&[
+            0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+        ]);
+        body
+    }
+
+    #[test]
+    fn pcm16_stereo() {
+        let samples = [1, 2, 3, 4, 5, 6, 7, 8];
+        let file = riff(&[
+            &chunk(b"fmt ", &fmt(WAVE_FORMAT_PCM, 2, 44100, 16)),
+            &chunk(b"data", &samples),
+        ]);
+
+        let wav = parse(&file).unwrap();
+
+        assert_eq!(wav.audio_format, AudioFormat::PCM16Stereo);
+        assert_eq!(wav.sample_rate, 44100);
+        assert_eq!(wav.data, &samples);
+    }
+
+    #[test]
+    fn malformed_headers() {
+        let valid = riff(&[
+            &chunk(b"fmt ", &fmt(WAVE_FORMAT_PCM, 1, 8000, 8)),
+            &chunk(b"data", &[0x80; 4]),
+        ]);
+        assert!(parse(&valid).is_ok());
+
+        // Too short for the RIFF header.
+        assert!(matches!(parse(b"RIFF"), Err(Error::InvalidWavData)));
+        assert!(matches!(parse(&[]), Err(Error::InvalidWavData)));
+
+        // Wrong magic numbers.
+        let mut file = valid.clone();
+        file[0..4].copy_from_slice(b"RIFX");
+        assert!(matches!(parse(&file), Err(Error::InvalidWavData)));
+
+        let mut file = valid.clone();
+        file[8..12].copy_from_slice(b"AVI ");
+        assert!(matches!(parse(&file), Err(Error::InvalidWavData)));
+
+        // Missing "fmt " or "data" chunks.
+        let file = riff(&[&chunk(b"data", &[0x80; 4])]);
+        assert!(matches!(parse(&file), Err(Error::InvalidWavData)));
+
+        let file = riff(&[&chunk(b"fmt ", &fmt(WAVE_FORMAT_PCM, 1, 8000, 8))]);
+        assert!(matches!(parse(&file), Err(Error::InvalidWavData)));
+
+        // "fmt " chunk too short to hold the format.
+        let file = riff(&[&chunk(b"fmt ", &[1, 0, 1, 0]), &chunk(b"data", &[0x80; 4])]);
+        assert!(matches!(parse(&file), Err(Error::InvalidWavData)));
+
+        // Sample rates a `Channel` can't play.
+        for sample_rate in [0, 96001, u32::MAX] {
+            let file = riff(&[
+                &chunk(b"fmt ", &fmt(WAVE_FORMAT_PCM, 1, sample_rate, 8)),
+                &chunk(b"data", &[0x80; 4]),
+            ]);
+            assert!(matches!(parse(&file), Err(Error::InvalidWavData)));
+        }
+    }
+
+    #[test]
+    fn unsupported_formats() {
+        // IEEE float, 8 bit with 3 channels and 24 bit audio.
+        for format in [
+            fmt(0x0003, 1, 44100, 32),
+            fmt(WAVE_FORMAT_PCM, 3, 44100, 8),
+            fmt(WAVE_FORMAT_PCM, 2, 44100, 24),
+        ] {
+            let file = riff(&[&chunk(b"fmt ", &format), &chunk(b"data", &[0; 12])]);
+
+            assert!(matches!(parse(&file), Err(Error::UnsupportedWavFormat)));
+        }
+    }
+
+    #[test]
+    fn wave_format_extensible() {
+        let file = riff(&[
+            &chunk(b"fmt ", &extensible_fmt(1, 22050, 16, WAVE_FORMAT_PCM)),
+            &chunk(b"data", &[1, 2, 3, 4]),
+        ]);
+
+        let wav = parse(&file).unwrap();
+
+        assert_eq!(wav.audio_format, AudioFormat::PCM16Mono);
+        assert_eq!(wav.sample_rate, 22050);
+        assert_eq!(wav.data, &[1, 2, 3, 4]);
+
+        // Extensible IEEE float audio.
+        let file = riff(&[
+            &chunk(b"fmt ", &extensible_fmt(1, 22050, 32, 0x0003)),
+            &chunk(b"data", &[0; 4]),
+        ]);
+        assert!(matches!(parse(&file), Err(Error::UnsupportedWavFormat)));
+
+        // Extensible format without the sub-format GUID.
+        let file = riff(&[
+            &chunk(b"fmt ", &fmt(WAVE_FORMAT_EXTENSIBLE, 1, 22050, 16)),
+            &chunk(b"data", &[0; 4]),
+        ]);
+        assert!(matches!(parse(&file), Err(Error::InvalidWavData)));
+    }
+
+    #[test]
+    fn odd_sized_chunks() {
+        // The padding byte of the odd-sized "LIST" chunk must be skipped to find the "data" chunk.
+        let file = riff(&[
+            &chunk(b"fmt ", &fmt(WAVE_FORMAT_PCM, 1, 8000, 8)),
+            &chunk(b"LIST", b"INFOabc"),
+            &chunk(b"data", &[1, 2, 3]),
+        ]);
+
+        let wav = parse(&file).unwrap();
+
+        assert_eq!(wav.audio_format, AudioFormat::PCM8Mono);
+        assert_eq!(wav.data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn truncated_data() {
+        let mut file = riff(&[
+            &chunk(b"fmt ", &fmt(WAVE_FORMAT_PCM, 2, 44100, 16)),
+            &chunk(b"data", &[1, 2, 3, 4, 5, 6, 7, 8]),
+        ]);
+
+        // The file ends in the middle of the third sample.
+        file.truncate(file.len() - 3);
+
+        let wav = parse(&file).unwrap();
+
+        // Only whole frames are kept.
+        assert_eq!(wav.data, &[1, 2, 3, 4]);
+
+        // Data chunks with bogus sizes (e.g. from streaming encoders) are cut to the end of the file.
+        let mut file = riff(&[
+            &chunk(b"fmt ", &fmt(WAVE_FORMAT_PCM, 1, 8000, 8)),
+            &chunk(b"data", &[1, 2, 3, 4]),
+        ]);
+        let size = file.len() - 8;
+        file[size..size + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let wav = parse(&file).unwrap();
+        assert_eq!(wav.data, &[1, 2, 3, 4]);
+
+        // Chunk headers cut off by the end of the file are ignored.
+        let mut file = riff(&[
+            &chunk(b"fmt ", &fmt(WAVE_FORMAT_PCM, 1, 8000, 8)),
+            &chunk(b"data", &[1, 2]),
+        ]);
+        file.extend_from_slice(b"LIS");
+
+        let wav = parse(&file).unwrap();
+        assert_eq!(wav.data, &[1, 2]);
+    }
+}
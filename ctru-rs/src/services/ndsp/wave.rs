@@ -2,19 +2,76 @@
 //!
 //! This modules has all methods and structs required to work with audio waves meant to be played via the [`ndsp`](crate::services::ndsp) service.
 
-use super::{AudioFormat, Error};
-use crate::linear::LinearAllocation;
+use super::{
+    bump_queue_generation, queue_generation, wav, AudioFormat, Error, SampleEncoding,
+    SAMPLE_RATE_RANGE,
+};
+use crate::error::ResultCode;
+use crate::linear::{self, LinearAllocation, LinearAllocator};
+use crate::sealed::Sealed;
+use crate::services::gfx;
+
+use std::alloc::Layout;
+use std::io::{self, Read};
+use std::ops::{Deref, DerefMut, Range};
+use std::time::Duration;
+
+// Alignment of the audio data allocated by `Wave`, matching the default of `linearAlloc`.
+// This keeps the data aligned for cache maintenance operations, which work on whole cache lines.
+const DATA_ALIGNMENT: usize = 0x80;
+
+// Wave holding its own audio data on the LINEAR memory, built by the conversion functions.
+type LinearWave = Wave<Box<[u8], LinearAllocator>>;
 
 /// Informational struct holding the raw audio data and playback info.
 ///
 /// You can play audio [`Wave`]s by using [`Channel::queue_wave()`](super::Channel::queue_wave).
+///
+/// # Panics
+///
+/// Dropping a [`Wave`] which is still queued on a channel clears the channel's whole queue, since the DSP would otherwise keep reading freed memory.
+/// As this is always a bug, builds with debug assertions also panic afterwards, pointing to the offending drop.
 pub struct Wave<Buffer: LinearAllocation + AsRef<[u8]>> {
     /// Data block of the audio wave (and its format information).
     buffer: Buffer,
     audio_format: AudioFormat,
     // Holding the data with the raw format is necessary since `libctru` will access it.
     pub(crate) raw_data: ctru_sys::ndspWaveBuf,
+    // Boxed since `raw_data` points to it.
+    adpcm_data: Option<Box<ctru_sys::ndspAdpcmData>>,
+    start_offset: usize,
     played_on_channel: Option<u8>,
+    // Generation of the channel's queue at the time this wave was queued.
+    queue_generation: u32,
+}
+
+/// Decoder state needed to start playing a [`Wave`] in [`AudioFormat::ADPCMMono`] format.
+///
+/// These values are usually stored in the header of DSP-ADPCM encoded files.
+#[doc(alias = "ndspAdpcmData")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdpcmData {
+    /// Predictor and scale of the first frame.
+    pub index: u16,
+    /// First history sample.
+    pub history0: i16,
+    /// Second history sample.
+    pub history1: i16,
+}
+
+/// Mutable access to the audio data of a [`Wave`], which is [flushed](Wave::flush) when the guard is dropped.
+///
+/// Returned by [`Wave::edit_buffer()`].
+pub struct BufferGuard<'wave, Buffer: LinearAllocation + AsRef<[u8]> + AsMut<[u8]>> {
+    wave: &'wave mut Wave<Buffer>,
+}
+
+/// Type of a single PCM sample, used to access the audio data of a [`Wave`] without casting bytes.
+///
+/// This trait is implemented for [`i8`] (8 bit PCM formats) and [`i16`] (16 bit PCM formats).
+pub trait Sample: Sealed + Copy {
+    /// Returns whether audio in the given format is made up of samples of this type.
+    fn is_compatible(format: AudioFormat) -> bool;
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -54,14 +111,47 @@ where
     /// # }
     /// ```
     pub fn new(buffer: Buffer, audio_format: AudioFormat, looping: bool) -> Self {
-        let buf = buffer.as_ref();
-        let sample_count = buf.len() / audio_format.size();
+        let wave = Self::new_unflushed(buffer, audio_format, looping);
 
-        // Signal to the DSP processor the buffer's RAM sector.
-        // This step may seem delicate, but testing reports failure most of the time, while still having no repercussions on the resulting audio.
-        unsafe {
-            let _r = ctru_sys::DSP_FlushDataCache(buf.as_ptr().cast(), buf.len() as u32);
-        }
+        // See the notes of `flush()` on why the result is ignored.
+        let _ = wave.flush();
+
+        wave
+    }
+
+    /// Build a new playable wave object like [`Wave::new()`], without flushing the buffer's data.
+    ///
+    /// This is useful when the data is shared with other waves (for example, via [`LinearSlice`](crate::linear::LinearSlice)s
+    /// of a single allocation), so that it can be flushed just once.
+    ///
+    /// # Notes
+    ///
+    /// The data must be flushed before the wave is played (either by [`Wave::flush()`] or through another wave sharing it),
+    /// otherwise the DSP may read stale data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::linear::{LinearAllocator, LinearSlice};
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// // Sound effects preloaded in a single allocation.
+    /// let bank: Box<[u8], _> = Box::new_in([0u8; 4096], LinearAllocator);
+    ///
+    /// // Flush the whole bank once.
+    /// let bank_wave = Wave::new(LinearSlice::new(&bank).unwrap(), AudioFormat::PCM16Mono, false);
+    ///
+    /// let jump = Wave::new_unflushed(LinearSlice::new(&bank[..1024]).unwrap(), AudioFormat::PCM16Mono, false);
+    /// let coin = Wave::new_unflushed(LinearSlice::new(&bank[1024..]).unwrap(), AudioFormat::PCM16Mono, false);
+    /// # }
+    /// ```
+    pub fn new_unflushed(buffer: Buffer, audio_format: AudioFormat, looping: bool) -> Self {
+        let buf = buffer.as_ref();
+        let sample_count = audio_format.sample_count(buf.len());
 
         let address = ctru_sys::tag_ndspWaveBuf__bindgen_ty_1 {
             data_vaddr: buf.as_ptr().cast(),
@@ -83,7 +173,10 @@ where
             buffer,
             audio_format,
             raw_data,
+            adpcm_data: None,
+            start_offset: 0,
             played_on_channel: None,
+            queue_generation: 0,
         }
     }
 
@@ -98,19 +191,30 @@ where
     ///
     /// This function will return an error if the [`Wave`] is currently busy,
     /// with the id to the channel in which it's queued.
+    ///
+    /// # Notes
+    ///
+    /// Changes to the buffer aren't visible to the DSP until they are [flushed](Wave::flush).
+    /// Use [`Wave::edit_buffer()`] to have the buffer flushed automatically once done.
     pub fn get_buffer_mut(&mut self) -> Result<&mut [u8], Error>
     where
         Buffer: AsMut<[u8]>,
     {
-        match self.status() {
-            Status::Playing | Status::Queued => {
-                Err(Error::WaveBusy(self.played_on_channel.unwrap()))
-            }
-            _ => Ok(self.buffer.as_mut()),
+        if self.is_busy() {
+            return Err(Error::WaveBusy(self.played_on_channel.unwrap()));
         }
+
+        Ok(self.buffer.as_mut())
     }
 
-    /// Returns this wave's playback status.
+    /// Returns a guard giving mutable access to the audio data (on the LINEAR memory), which flushes it once dropped.
+    ///
+    /// Use [`BufferGuard::finish()`] to get the result of the flush, which is otherwise ignored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the [`Wave`] is currently busy,
+    /// with the id to the channel in which it's queued.
     ///
     /// # Example
     ///
@@ -119,112 +223,1180 @@ where
     /// # fn main() {
     /// # let _runner = test_runner::GdbRunner::default();
     /// #
-    /// # use ctru::linear::LinearAllocator;
-    /// # let _audio_data: Box<[_], _> = Box::new_in([0u8; 96], LinearAllocator);
-    /// #
-    /// use ctru::services::ndsp::{AudioFormat, wave::{Wave, Status}};
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
     ///
-    /// // Provide your own audio data.
-    /// let wave = Wave::new(_audio_data, AudioFormat::PCM16Stereo, false);
+    /// let mut wave = Wave::zeroed(100, AudioFormat::PCM8Mono, false);
     ///
-    /// // The `Wave` is free if never played before.
-    /// assert!(matches!(wave.status(), Status::Free));
+    /// // The new data is made visible to the DSP at the end of the statement.
+    /// wave.edit_buffer().unwrap().fill(10);
     /// # }
     /// ```
-    pub fn status(&self) -> Status {
-        self.raw_data.status.try_into().unwrap()
+    pub fn edit_buffer(&mut self) -> Result<BufferGuard<'_, Buffer>, Error>
+    where
+        Buffer: AsMut<[u8]>,
+    {
+        if self.is_busy() {
+            return Err(Error::WaveBusy(self.played_on_channel.unwrap()));
+        }
+
+        Ok(BufferGuard { wave: self })
     }
 
-    /// Returns the amount of samples *read* by the NDSP process.
+    /// Returns the audio data as a slice of samples, if their type matches the wave's format.
     ///
-    /// # Notes
+    /// Stereo samples are interleaved (left first, then right).
     ///
-    /// This value varies depending on [`Wave::set_sample_count`].
-    pub fn sample_count(&self) -> usize {
-        self.raw_data.nsamples as usize
-    }
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let wave = Wave::from_pcm16(&[100, -100], false, false).unwrap();
+    ///
+    /// assert_eq!(wave.samples::<i16>(), Some(&[100i16, -100][..]));
+    /// assert_eq!(wave.samples::<i8>(), None);
+    /// # }
+    /// ```
+    pub fn samples<T: Sample>(&self) -> Option<&[T]> {
+        if !T::is_compatible(self.audio_format) {
+            return None;
+        }
 
-    /// Returns the format of the audio data.
-    pub fn format(&self) -> AudioFormat {
-        self.audio_format
-    }
+        // Safety: both sample types are valid for any bit pattern.
+        let (prefix, samples, _) = unsafe { self.buffer.as_ref().align_to::<T>() };
 
-    // Set the internal flag for the id of the channel playing this wave.
-    //
-    // Internal Use Only.
-    pub(crate) fn set_channel(&mut self, id: u8) {
-        self.played_on_channel = Some(id)
+        // LINEAR allocations are always aligned enough, but it's better to make sure.
+        prefix.is_empty().then_some(samples)
     }
 
-    /// Set the amount of samples to be read.
+    /// Returns the audio data as a mutable slice of samples, if their type matches the wave's format.
     ///
-    /// # Note
+    /// Changes to the samples aren't visible to the DSP until they are [flushed](Wave::flush).
     ///
+    /// # Errors
     ///
-    /// This function doesn't resize the internal buffer. Operations of this kind are particularly useful to allocate memory pools
-    /// for VBR (Variable BitRate) formats, like OGG Vorbis.
+    /// This function will return an error if the [`Wave`] is currently busy, or if the sample type doesn't match the wave's format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let mut wave = Wave::zeroed(128, AudioFormat::PCM16Mono, false);
+    ///
+    /// // Write a square wave.
+    /// for (i, sample) in wave.samples_mut::<i16>().unwrap().iter_mut().enumerate() {
+    ///     *sample = if i % 32 < 16 { 8000 } else { -8000 };
+    /// }
+    ///
+    /// let _ = wave.flush();
+    /// # }
+    /// ```
+    pub fn samples_mut<T: Sample>(&mut self) -> Result<&mut [T], Error>
+    where
+        Buffer: AsMut<[u8]>,
+    {
+        let audio_format = self.audio_format;
+
+        if !T::is_compatible(audio_format) {
+            return Err(Error::InvalidSampleType(audio_format));
+        }
+
+        let buffer = self.get_buffer_mut()?;
+
+        // Safety: both sample types are valid for any bit pattern.
+        let (prefix, samples, _) = unsafe { buffer.align_to_mut::<T>() };
+
+        if prefix.is_empty() {
+            Ok(samples)
+        } else {
+            Err(Error::InvalidSampleType(audio_format))
+        }
+    }
+
+    /// Build a new wave holding this wave's audio, resampled from `from_hz` to `to_hz` with linear interpolation.
+    ///
+    /// The new wave is allocated on the [LINEAR memory](`crate::linear`) and flushed, ready to be played at `to_hz`.
+    /// Its audio format and looping setting are the same as this wave's, while the start offset is reset.
     ///
     /// # Errors
     ///
-    /// This function will return an error if the sample size exceeds the buffer's capacity
-    /// or if the [`Wave`] is currently queued.
-    pub fn set_sample_count(&mut self, sample_count: usize) -> Result<(), Error> {
-        match self.status() {
-            Status::Playing | Status::Queued => {
-                return Err(Error::WaveBusy(self.played_on_channel.unwrap()));
-            }
-            _ => (),
+    /// This function will return an error if either sample rate isn't supported by the DSP,
+    /// or if the wave's format isn't [`AudioFormat::PCM16Mono`] or [`AudioFormat::PCM16Stereo`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let voice = Wave::zeroed(1600, AudioFormat::PCM16Mono, false);
+    ///
+    /// // Match the sample rate of the rest of the audio.
+    /// let resampled = voice.resampled(16000., 44100.).unwrap();
+    ///
+    /// assert_eq!(resampled.sample_count(), 4410);
+    /// # }
+    /// ```
+    pub fn resampled(&self, from_hz: f32, to_hz: f32) -> Result<LinearWave, Error> {
+        if !SAMPLE_RATE_RANGE.contains(&from_hz) || !SAMPLE_RATE_RANGE.contains(&to_hz) {
+            return Err(Error::InvalidSampleRate);
         }
 
-        let max_count = self.buffer.as_ref().len() / self.audio_format.size();
+        let Some(input) = self.samples::<i16>() else {
+            return Err(Error::UnsupportedFormat(self.audio_format));
+        };
 
-        if sample_count > max_count {
-            return Err(Error::SampleCountOutOfBounds(sample_count, max_count));
+        let channels = self.audio_format.channel_count();
+        let input_frames = input.len() / channels;
+
+        let step = f64::from(from_hz) / f64::from(to_hz);
+        let output_frames = (input_frames as f64 / step).round() as usize;
+
+        let mut wave = Wave::zeroed(output_frames, self.audio_format, self.raw_data.looping);
+        let output = wave.samples_mut::<i16>()?;
+
+        for (frame, samples) in output.chunks_exact_mut(channels).enumerate() {
+            let position = frame as f64 * step;
+
+            // Rounding may place the last frames past the end of the input.
+            let current = (position as usize).min(input_frames - 1);
+            let next = (current + 1).min(input_frames - 1);
+            let fraction = (position - current as f64).clamp(0., 1.) as f32;
+
+            // Stereo samples stay interleaved, since each channel is interpolated on its own.
+            for (channel, sample) in samples.iter_mut().enumerate() {
+                let a = f32::from(input[current * channels + channel]);
+                let b = f32::from(input[next * channels + channel]);
+
+                *sample = (a + (b - a) * fraction) as i16;
+            }
         }
 
-        self.raw_data.nsamples = sample_count as u32;
+        let _ = wave.flush();
 
-        Ok(())
+        Ok(wave)
     }
-}
 
-impl TryFrom<u8> for Status {
-    type Error = &'static str;
+    /// Build a new wave holding this wave's audio converted to 16 bit PCM, keeping the amount of channels.
+    ///
+    /// The new wave is allocated on the [LINEAR memory](`crate::linear`) and flushed, keeping this wave's looping setting.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the wave's format is [`AudioFormat::ADPCMMono`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let wave = Wave::from_pcm8(&[64, -128], false, false).unwrap();
+    /// let converted = wave.to_pcm16().unwrap();
+    ///
+    /// assert_eq!(converted.format(), AudioFormat::PCM16Mono);
+    /// assert_eq!(converted.samples::<i16>(), Some(&[16384i16, -32768][..]));
+    /// # }
+    /// ```
+    pub fn to_pcm16(&self) -> Result<LinearWave, Error> {
+        let channels = self.audio_format.channel_count();
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        match value {
-            0 => Ok(Self::Free),
-            1 => Ok(Self::Queued),
-            2 => Ok(Self::Playing),
-            3 => Ok(Self::Done),
-            _ => Err("Invalid Wave Status code"),
+        match self.audio_format.encoding() {
+            SampleEncoding::Pcm8 => {
+                self.map_samples(channels, SampleEncoding::Pcm16, |s: i8| i16::from(s) << 8)
+            }
+            SampleEncoding::Pcm16 => self.map_samples(channels, SampleEncoding::Pcm16, |s: i16| s),
+            SampleEncoding::Adpcm => Err(Error::UnsupportedFormat(self.audio_format)),
         }
     }
-}
 
-impl<Buffer> Drop for Wave<Buffer>
-where
-    Buffer: LinearAllocation + AsRef<[u8]>,
-{
-    fn drop(&mut self) {
-        // This was the only way I found I could check for improper drops of `Wave`.
-        // A panic was considered, but it would cause issues with drop order against `Ndsp`.
-        match self.status() {
-            Status::Free | Status::Done => (),
-            // If the status flag is "unfinished"
-            _ => {
-                // The unwrap is safe, since it must have a value in the case the status is "unfinished".
-                unsafe { ctru_sys::ndspChnWaveBufClear(self.played_on_channel.unwrap().into()) };
+    /// Build a new wave holding this wave's audio converted to 8 bit PCM, keeping the amount of channels.
+    ///
+    /// Samples are rounded to the closest 8 bit value. The new wave is allocated on the [LINEAR memory](`crate::linear`) and flushed,
+    /// keeping this wave's looping setting.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the wave's format is [`AudioFormat::ADPCMMono`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let wave = Wave::from_pcm16(&[16384, 16400, 32767, -32768], true, false).unwrap();
+    /// let converted = wave.to_pcm8().unwrap();
+    ///
+    /// assert_eq!(converted.format(), AudioFormat::PCM8Stereo);
+    /// assert_eq!(converted.samples::<i8>(), Some(&[64i8, 64, 127, -128][..]));
+    /// # }
+    /// ```
+    pub fn to_pcm8(&self) -> Result<LinearWave, Error> {
+        let channels = self.audio_format.channel_count();
+
+        match self.audio_format.encoding() {
+            SampleEncoding::Pcm8 => self.map_samples(channels, SampleEncoding::Pcm8, |s: i8| s),
+            SampleEncoding::Pcm16 => self.map_samples(channels, SampleEncoding::Pcm8, |s: i16| {
+                ((i32::from(s) + 0x80) >> 8).clamp(i8::MIN.into(), i8::MAX.into()) as i8
+            }),
+            SampleEncoding::Adpcm => Err(Error::UnsupportedFormat(self.audio_format)),
+        }
+    }
+
+    /// Split the audio of a stereo wave into two mono waves (left, then right).
+    ///
+    /// The new waves are allocated on the [LINEAR memory](`crate::linear`) and flushed, keeping this wave's looping setting.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the wave's format isn't [`AudioFormat::PCM8Stereo`] or [`AudioFormat::PCM16Stereo`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let wave = Wave::from_pcm16(&[1, 2, 3, 4], true, false).unwrap();
+    /// let (left, right) = wave.deinterleave().unwrap();
+    ///
+    /// assert_eq!(left.samples::<i16>(), Some(&[1i16, 3][..]));
+    /// assert_eq!(right.samples::<i16>(), Some(&[2i16, 4][..]));
+    /// # }
+    /// ```
+    pub fn deinterleave(&self) -> Result<(LinearWave, LinearWave), Error> {
+        match self.audio_format {
+            AudioFormat::PCM8Stereo => {
+                Ok((self.split_channel::<i8>(0)?, self.split_channel::<i8>(1)?))
+            }
+            AudioFormat::PCM16Stereo => {
+                Ok((self.split_channel::<i16>(0)?, self.split_channel::<i16>(1)?))
             }
+            format => Err(Error::UnsupportedFormat(format)),
+        }
+    }
+
+    // Build a new wave in the specified encoding by converting every sample of this wave.
+    fn map_samples<T: Sample, U: Sample>(
+        &self,
+        channels: usize,
+        encoding: SampleEncoding,
+        convert: impl Fn(T) -> U,
+    ) -> Result<LinearWave, Error> {
+        let input = self
+            .samples::<T>()
+            .ok_or(Error::UnsupportedFormat(self.audio_format))?;
+
+        // The amount of channels is kept, and all PCM encodings support both mono and stereo.
+        let audio_format = AudioFormat::from_parts(channels, encoding).unwrap();
+
+        let mut wave = Wave::zeroed(input.len() / channels, audio_format, self.raw_data.looping);
+
+        for (output, &sample) in wave.samples_mut::<U>()?.iter_mut().zip(input) {
+            *output = convert(sample);
         }
 
-        unsafe {
-            // Flag the buffer's RAM sector as unused
-            // This step has no real effect in normal applications and is skipped even by devkitPRO's own examples.
-            let _r = ctru_sys::DSP_InvalidateDataCache(
-                self.get_buffer().as_ptr().cast(),
-                self.get_buffer().len().try_into().unwrap(),
-            );
+        let _ = wave.flush();
+
+        Ok(wave)
+    }
+
+    // Build a new mono wave from one of the channels of this (stereo) wave.
+    fn split_channel<T: Sample>(&self, channel: usize) -> Result<LinearWave, Error> {
+        let input = self
+            .samples::<T>()
+            .ok_or(Error::UnsupportedFormat(self.audio_format))?;
+
+        let audio_format = AudioFormat::from_parts(1, self.audio_format.encoding()).unwrap();
+        let mut wave = Wave::zeroed(input.len() / 2, audio_format, self.raw_data.looping);
+
+        for (output, frame) in wave
+            .samples_mut::<T>()?
+            .iter_mut()
+            .zip(input.chunks_exact(2))
+        {
+            *output = frame[channel];
         }
+
+        let _ = wave.flush();
+
+        Ok(wave)
+    }
+
+    /// Returns this wave's playback status.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// # use ctru::linear::LinearAllocator;
+    /// # let _audio_data: Box<[_], _> = Box::new_in([0u8; 96], LinearAllocator);
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::{Wave, Status}};
+    ///
+    /// // Provide your own audio data.
+    /// let wave = Wave::new(_audio_data, AudioFormat::PCM16Stereo, false);
+    ///
+    /// // The `Wave` is free if never played before.
+    /// assert!(matches!(wave.status(), Status::Free));
+    /// # }
+    /// ```
+    pub fn status(&self) -> Status {
+        // The NDSP thread updates the status asynchronously, so it must be freshly read every time.
+        let status = unsafe { std::ptr::addr_of!(self.raw_data.status).read_volatile() };
+
+        status.try_into().unwrap()
+    }
+
+    /// Returns the sequence ID assigned to this wave by the NDSP service when it was last queued.
+    ///
+    /// It can be compared with [`Channel::wave_sequence_id()`](super::Channel::wave_sequence_id) to know which wave a channel is playing.
+    pub fn sequence_id(&self) -> u16 {
+        unsafe { std::ptr::addr_of!(self.raw_data.sequence_id).read_volatile() }
+    }
+
+    /// Returns the amount of samples *read* by the NDSP process.
+    ///
+    /// # Notes
+    ///
+    /// This value varies depending on [`Wave::set_sample_count`].
+    pub fn sample_count(&self) -> usize {
+        self.raw_data.nsamples as usize
+    }
+
+    /// Returns the index of the first sample read by the NDSP process.
+    pub fn start_offset(&self) -> usize {
+        self.start_offset
+    }
+
+    /// Set the index of the first sample to be read, to start playback partway into the buffer.
+    ///
+    /// The playback still ends at the same sample, so the [sample count](Wave::sample_count) is adjusted accordingly.
+    /// Looping waves restart from the offset.
+    ///
+    /// # Notes
+    ///
+    /// [`AudioFormat::ADPCMMono`] data can only be played from the start of a frame, so the offset must be a multiple of 14.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the offset exceeds the last sample to be read,
+    /// if it doesn't match the start of an ADPCM frame, or if the [`Wave`] is currently queued.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let mut wave = Wave::zeroed(100, AudioFormat::PCM16Stereo, false);
+    ///
+    /// // Skip the first 40 samples.
+    /// wave.set_start_offset(40).unwrap();
+    ///
+    /// assert_eq!(wave.sample_count(), 60);
+    /// # }
+    /// ```
+    pub fn set_start_offset(&mut self, offset: usize) -> Result<(), Error> {
+        if self.is_busy() {
+            return Err(Error::WaveBusy(self.played_on_channel.unwrap()));
+        }
+
+        let end = self.start_offset + self.sample_count();
+
+        if offset > end {
+            return Err(Error::SampleCountOutOfBounds(offset, end));
+        }
+
+        if self.audio_format == AudioFormat::ADPCMMono && offset % 14 != 0 {
+            return Err(Error::UnalignedStartOffset(offset));
+        }
+
+        let buf = self.buffer.as_ref();
+
+        self.raw_data.__bindgen_anon_1.data_vaddr =
+            buf[self.audio_format.byte_count(offset)..].as_ptr().cast();
+        self.raw_data.nsamples = (end - offset) as u32;
+        self.start_offset = offset;
+
+        Ok(())
+    }
+
+    /// Returns the format of the audio data.
+    pub fn format(&self) -> AudioFormat {
+        self.audio_format
+    }
+
+    /// Returns the ADPCM decoder state used to start playing this wave, if any.
+    pub fn adpcm_data(&self) -> Option<AdpcmData> {
+        self.adpcm_data.as_deref().map(|data| AdpcmData {
+            index: data.index,
+            history0: data.history0,
+            history1: data.history1,
+        })
+    }
+
+    /// Set the ADPCM decoder state used to start playing this wave.
+    ///
+    /// # Notes
+    ///
+    /// This is only meaningful for waves in [`AudioFormat::ADPCMMono`] format. If no decoder state is set,
+    /// the DSP continues decoding from the state left by the previously played wave.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the [`Wave`] is currently queued.
+    pub fn set_adpcm_data(&mut self, data: Option<AdpcmData>) -> Result<(), Error> {
+        if self.is_busy() {
+            return Err(Error::WaveBusy(self.played_on_channel.unwrap()));
+        }
+
+        self.adpcm_data = data.map(|data| {
+            Box::new(ctru_sys::ndspAdpcmData {
+                index: data.index,
+                history0: data.history0,
+                history1: data.history1,
+            })
+        });
+
+        self.raw_data.adpcm_data = match self.adpcm_data.as_deref_mut() {
+            Some(data) => std::ptr::from_mut(data),
+            None => std::ptr::null_mut(),
+        };
+
+        Ok(())
+    }
+
+    /// Write the audio data back from the CPU cache to memory, so that the DSP reads its latest contents.
+    ///
+    /// The data is flushed when the [`Wave`] is built, but any later change made via [`Wave::get_buffer_mut()`] must be flushed
+    /// before queueing the wave again.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the DSP service fails to flush the data.
+    ///
+    /// # Notes
+    ///
+    /// Testing reports failure most of the time while still having no repercussions on the resulting audio,
+    /// which is why the constructors and conversions of [`Wave`] ignore the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let mut wave = Wave::zeroed(100, AudioFormat::PCM8Mono, false);
+    ///
+    /// wave.get_buffer_mut().unwrap().fill(10);
+    ///
+    /// // Make the new data visible to the DSP.
+    /// let _ = wave.flush();
+    /// # }
+    /// ```
+    #[doc(alias = "DSP_FlushDataCache")]
+    pub fn flush(&self) -> crate::Result<()> {
+        self.flush_range(0..self.buffer.as_ref().len())
+    }
+
+    /// Write part of the audio data back from the CPU cache to memory, so that the DSP reads its latest contents.
+    ///
+    /// This is useful to only flush the section of a buffer that was rewritten, for example when streaming audio.
+    /// Look at [`Wave::flush()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the DSP service fails to flush the data.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the byte range is out of the buffer's bounds.
+    #[doc(alias = "DSP_FlushDataCache")]
+    pub fn flush_range(&self, range: Range<usize>) -> crate::Result<()> {
+        let buf = &self.buffer.as_ref()[range];
+
+        // Signal to the DSP processor the buffer's RAM sector.
+        // The buffer is always in LINEAR memory, so it can only be rejected if it's empty.
+        if let Ok(Some((ptr, len))) = gfx::data_cache_region(buf) {
+            ResultCode(unsafe { ctru_sys::DSP_FlushDataCache(ptr, len) })?;
+        }
+
+        Ok(())
+    }
+
+    // Set the internal flag for the id of the channel playing this wave.
+    //
+    // Internal Use Only.
+    pub(crate) fn set_channel(&mut self, id: u8) {
+        self.played_on_channel = Some(id);
+        self.queue_generation = queue_generation(id);
+    }
+
+    // Whether the wave is still queued or playing on its channel.
+    // `libctru` doesn't update the status of cleared waves, so a wave is done if its channel's queue has been cleared since it was queued.
+    //
+    // Internal Use Only.
+    pub(crate) fn is_busy(&self) -> bool {
+        match self.status() {
+            Status::Playing | Status::Queued => self
+                .played_on_channel
+                .is_some_and(|id| queue_generation(id) == self.queue_generation),
+            _ => false,
+        }
+    }
+
+    // Flag the wave as not in use after its channel's queue was cleared, since `libctru` doesn't update the status of cleared waves.
+    //
+    // Internal Use Only.
+    pub(crate) fn mark_unqueued(&mut self) {
+        self.raw_data.status = ctru_sys::NDSP_WBUF_DONE;
+    }
+
+    /// Set the amount of samples to be read.
+    ///
+    /// # Note
+    ///
+    ///
+    /// This function doesn't resize the internal buffer. Operations of this kind are particularly useful to allocate memory pools
+    /// for VBR (Variable BitRate) formats, like OGG Vorbis.
+    ///
+    /// The samples are counted from the [start offset](Wave::set_start_offset).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the sample size exceeds the buffer's capacity
+    /// or if the [`Wave`] is currently queued.
+    pub fn set_sample_count(&mut self, sample_count: usize) -> Result<(), Error> {
+        if self.is_busy() {
+            return Err(Error::WaveBusy(self.played_on_channel.unwrap()));
+        }
+
+        let max_count =
+            self.audio_format.sample_count(self.buffer.as_ref().len()) - self.start_offset;
+
+        if sample_count > max_count {
+            return Err(Error::SampleCountOutOfBounds(sample_count, max_count));
+        }
+
+        self.raw_data.nsamples = sample_count as u32;
+
+        Ok(())
+    }
+}
+
+impl Wave<Box<[u8], LinearAllocator>> {
+    /// Build a new playable wave object holding the specified amount of silent samples.
+    ///
+    /// This is useful to pre-allocate buffers meant to be filled later, for example when streaming audio.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let wave = Wave::zeroed(2205, AudioFormat::PCM16Stereo, false);
+    ///
+    /// assert_eq!(wave.sample_count(), 2205);
+    /// # }
+    /// ```
+    pub fn zeroed(sample_count: usize, audio_format: AudioFormat, looping: bool) -> Self {
+        let len = audio_format.byte_count(sample_count);
+
+        // NDSP PCM8 data is signed, so zeroed memory is silence in every format.
+        let buffer = alloc_data(len).unwrap_or_else(|_| {
+            std::alloc::handle_alloc_error(Layout::from_size_align(len, DATA_ALIGNMENT).unwrap())
+        });
+
+        Self::new(buffer, audio_format, looping)
+    }
+
+    /// Build a new playable wave object holding the specified amount of silent samples, like [`Wave::zeroed()`],
+    /// returning an error instead of aborting if there isn't enough LINEAR memory left.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::OutOfLinearMemory`] if the data can't be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, Error, wave::Wave};
+    ///
+    /// match Wave::try_zeroed(1 << 28, AudioFormat::PCM16Stereo, false) {
+    ///     Ok(wave) => println!("Allocated {} samples", wave.sample_count()),
+    ///     // Evict some cached audio and try again.
+    ///     Err(Error::OutOfLinearMemory) => (),
+    ///     Err(e) => panic!("{e}"),
+    /// }
+    /// # }
+    /// ```
+    pub fn try_zeroed(
+        sample_count: usize,
+        audio_format: AudioFormat,
+        looping: bool,
+    ) -> Result<Self, Error> {
+        // NDSP PCM8 data is signed, so zeroed memory is silence in every format.
+        let buffer = alloc_data(audio_format.byte_count(sample_count))?;
+
+        Ok(Self::new(buffer, audio_format, looping))
+    }
+
+    /// Build a new non-looping wave holding the specified amount of silent samples, ready to be queued.
+    ///
+    /// This is useful to pad streams, pre-roll channels or generate gaps between sounds.
+    ///
+    /// # Notes
+    ///
+    /// Silence is stored as zeroed samples in every format. Unlike 8 bit WAV files (which are unsigned and centered at `0x80`),
+    /// the DSP expects signed PCM8 data, so its midpoint is 0 too.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let wave = Wave::silence(1024, AudioFormat::PCM8Mono);
+    ///
+    /// assert!(wave.get_buffer().iter().all(|&byte| byte == 0));
+    /// # }
+    /// ```
+    pub fn silence(sample_count: usize, audio_format: AudioFormat) -> Self {
+        Self::zeroed(sample_count, audio_format, false)
+    }
+
+    /// Build a new non-looping wave holding silence lasting `duration`, when played at `sample_rate` hertz.
+    ///
+    /// The amount of samples is rounded to the closest integer. Look at [`Wave::silence()`] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let wave = Wave::silence_for(Duration::from_millis(500), AudioFormat::PCM16Stereo, 44100.);
+    ///
+    /// assert_eq!(wave.sample_count(), 22050);
+    /// # }
+    /// ```
+    pub fn silence_for(duration: Duration, audio_format: AudioFormat, sample_rate: f32) -> Self {
+        // Invalid sample rates (negative or `NaN`) saturate to 0 samples.
+        let sample_count = (duration.as_secs_f64() * f64::from(sample_rate)).round() as usize;
+
+        Self::silence(sample_count, audio_format)
+    }
+
+    /// Build a new playable wave object by copying 16 bit PCM samples to the [LINEAR memory](`crate::linear`).
+    ///
+    /// Stereo samples must be interleaved (left first, then right).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `stereo` is `true` and the amount of samples is odd,
+    /// or if there isn't enough LINEAR memory left to hold the samples.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let samples = [0i16, 0, 1000, 1000, -1000, -1000];
+    ///
+    /// let wave = Wave::from_pcm16(&samples, true, false).unwrap();
+    ///
+    /// assert_eq!(wave.format(), AudioFormat::PCM16Stereo);
+    /// assert_eq!(wave.sample_count(), 3);
+    /// # }
+    /// ```
+    pub fn from_pcm16(samples: &[i16], stereo: bool, looping: bool) -> Result<Self, Error> {
+        let audio_format = if stereo {
+            AudioFormat::PCM16Stereo
+        } else {
+            AudioFormat::PCM16Mono
+        };
+
+        Self::from_samples(samples, audio_format, looping)
+    }
+
+    /// Build a new playable wave object by copying 8 bit PCM samples to the [LINEAR memory](`crate::linear`).
+    ///
+    /// Stereo samples must be interleaved (left first, then right).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `stereo` is `true` and the amount of samples is odd,
+    /// or if there isn't enough LINEAR memory left to hold the samples.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let samples = [0i8, 100, -100];
+    ///
+    /// let wave = Wave::from_pcm8(&samples, false, false).unwrap();
+    ///
+    /// assert_eq!(wave.format(), AudioFormat::PCM8Mono);
+    /// assert_eq!(wave.sample_count(), 3);
+    /// # }
+    /// ```
+    pub fn from_pcm8(samples: &[i8], stereo: bool, looping: bool) -> Result<Self, Error> {
+        let audio_format = if stereo {
+            AudioFormat::PCM8Stereo
+        } else {
+            AudioFormat::PCM8Mono
+        };
+
+        Self::from_samples(samples, audio_format, looping)
+    }
+
+    /// Build a new stereo wave by interleaving the audio of two mono waves (left and right).
+    ///
+    /// The new wave is as long as the longest of the two, with the shorter one being padded with silence.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the waves' formats aren't both [`AudioFormat::PCM8Mono`] or both [`AudioFormat::PCM16Mono`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::services::ndsp::{AudioFormat, wave::Wave};
+    ///
+    /// let left = Wave::from_pcm16(&[1, 3], false, false).unwrap();
+    /// let right = Wave::from_pcm16(&[2, 4], false, false).unwrap();
+    ///
+    /// let stereo = Wave::interleave(&left, &right, false).unwrap();
+    ///
+    /// assert_eq!(stereo.format(), AudioFormat::PCM16Stereo);
+    /// assert_eq!(stereo.samples::<i16>(), Some(&[1i16, 2, 3, 4][..]));
+    /// # }
+    /// ```
+    pub fn interleave<L, R>(left: &Wave<L>, right: &Wave<R>, looping: bool) -> Result<Self, Error>
+    where
+        L: LinearAllocation + AsRef<[u8]>,
+        R: LinearAllocation + AsRef<[u8]>,
+    {
+        match (left.format(), right.format()) {
+            (AudioFormat::PCM8Mono, AudioFormat::PCM8Mono) => {
+                Self::interleave_samples::<i8>(left, right, AudioFormat::PCM8Stereo, looping)
+            }
+            (AudioFormat::PCM16Mono, AudioFormat::PCM16Mono) => {
+                Self::interleave_samples::<i16>(left, right, AudioFormat::PCM16Stereo, looping)
+            }
+            (AudioFormat::PCM8Mono | AudioFormat::PCM16Mono, format) | (format, _) => {
+                Err(Error::UnsupportedFormat(format))
+            }
+        }
+    }
+
+    /// Build a new playable wave object from the contents of a WAV file, copying its audio data to the [LINEAR memory](`crate::linear`).
+    ///
+    /// Returns the wave and its sample rate (in hertz), which must be set on the channel playing it.
+    ///
+    /// # Notes
+    ///
+    /// Only uncompressed 8 and 16 bit PCM data with 1 or 2 channels is supported.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data isn't a valid WAV file (including sample rates a [`Channel`](super::Channel) can't play),
+    /// if its audio format isn't supported, or if there isn't enough LINEAR memory left to hold its audio.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #![feature(allocator_api)]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{wave::Wave, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// let file = std::fs::read("romfs:/sound.wav")?;
+    /// let (mut wave, sample_rate) = Wave::from_wav_bytes(&file, false)?;
+    ///
+    /// channel_0.set_format(wave.format());
+    /// channel_0.set_sample_rate(sample_rate as f32);
+    /// channel_0.queue_wave(&mut wave)?;
+    /// #
+    /// # channel_0.clear_queue();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_wav_bytes(bytes: &[u8], looping: bool) -> Result<(Self, u32), Error> {
+        let wav = wav::parse(bytes)?;
+
+        let mut buffer = copy_data(wav.data)?;
+
+        // 8 bit WAV samples are unsigned, while the DSP expects signed ones.
+        if let AudioFormat::PCM8Mono | AudioFormat::PCM8Stereo = wav.audio_format {
+            for byte in buffer.iter_mut() {
+                *byte ^= 0x80;
+            }
+        }
+
+        Ok((
+            Self::new(buffer, wav.audio_format, looping),
+            wav.sample_rate,
+        ))
+    }
+
+    /// Build a new playable wave object by reading a whole WAV file from `reader`.
+    ///
+    /// Look at [`Wave::from_wav_bytes()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading fails, or an error of kind [`io::ErrorKind::InvalidData`]
+    /// (holding an [`Error`]) if the data isn't a supported WAV file.
+    pub fn from_wav_reader(mut reader: impl Read, looping: bool) -> io::Result<(Self, u32)> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Self::from_wav_bytes(&bytes, looping)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn interleave_samples<T: Sample>(
+        left: &Wave<impl LinearAllocation + AsRef<[u8]>>,
+        right: &Wave<impl LinearAllocation + AsRef<[u8]>>,
+        audio_format: AudioFormat,
+        looping: bool,
+    ) -> Result<Self, Error> {
+        let left = left
+            .samples::<T>()
+            .ok_or(Error::UnsupportedFormat(left.format()))?;
+        let right = right
+            .samples::<T>()
+            .ok_or(Error::UnsupportedFormat(right.format()))?;
+
+        let mut wave = Self::zeroed(left.len().max(right.len()), audio_format, looping);
+        let output = wave.samples_mut::<T>()?;
+
+        for (frame, &sample) in output.chunks_exact_mut(2).zip(left) {
+            frame[0] = sample;
+        }
+
+        for (frame, &sample) in output.chunks_exact_mut(2).zip(right) {
+            frame[1] = sample;
+        }
+
+        let _ = wave.flush();
+
+        Ok(wave)
+    }
+
+    fn from_samples<T: Sample>(
+        samples: &[T],
+        audio_format: AudioFormat,
+        looping: bool,
+    ) -> Result<Self, Error> {
+        let stereo = matches!(
+            audio_format,
+            AudioFormat::PCM8Stereo | AudioFormat::PCM16Stereo
+        );
+
+        if stereo && samples.len() % 2 != 0 {
+            return Err(Error::InvalidStereoSampleCount(samples.len()));
+        }
+
+        // Safety: both sample types are plain integers without padding, so their memory is a valid byte slice.
+        // The DSP reads samples in the native (little endian) byte order.
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                samples.as_ptr().cast::<u8>(),
+                std::mem::size_of_val(samples),
+            )
+        };
+
+        let buffer = copy_data(bytes)?;
+
+        // The constructor takes care of flushing the data cache.
+        Ok(Self::new(buffer, audio_format, looping))
+    }
+}
+
+// Allocate a zeroed buffer for the audio data of a new wave.
+fn alloc_data(len: usize) -> Result<Box<[u8], LinearAllocator>, Error> {
+    linear::with_allocation_tag("ndsp", || {
+        LinearAllocator::alloc_aligned_slice(len, DATA_ALIGNMENT)
+    })
+    .map_err(|_| Error::OutOfLinearMemory)
+}
+
+// Copy existing audio data to a new buffer for a wave.
+fn copy_data(data: &[u8]) -> Result<Box<[u8], LinearAllocator>, Error> {
+    linear::with_allocation_tag("ndsp", || {
+        linear::try_copy_into_linear(data, DATA_ALIGNMENT)
+    })
+    .map_err(|_| Error::OutOfLinearMemory)
+}
+
+impl Sample for i8 {
+    fn is_compatible(format: AudioFormat) -> bool {
+        matches!(format, AudioFormat::PCM8Mono | AudioFormat::PCM8Stereo)
+    }
+}
+
+impl Sample for i16 {
+    fn is_compatible(format: AudioFormat) -> bool {
+        matches!(format, AudioFormat::PCM16Mono | AudioFormat::PCM16Stereo)
+    }
+}
+
+impl TryFrom<u8> for Status {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            ctru_sys::NDSP_WBUF_FREE => Ok(Self::Free),
+            ctru_sys::NDSP_WBUF_QUEUED => Ok(Self::Queued),
+            ctru_sys::NDSP_WBUF_PLAYING => Ok(Self::Playing),
+            ctru_sys::NDSP_WBUF_DONE => Ok(Self::Done),
+            _ => Err("Invalid Wave Status code"),
+        }
+    }
+}
+
+// SAFETY: The raw pointers held by `raw_data` only point to data owned by the wave itself,
+// which `libctru` accesses under the lock of the channel the wave is queued on.
+unsafe impl<Buffer> Send for Wave<Buffer> where Buffer: LinearAllocation + AsRef<[u8]> + Send {}
+
+// SAFETY: Shared references only allow reading the audio data and the status flag (which is read volatilely).
+unsafe impl<Buffer> Sync for Wave<Buffer> where Buffer: LinearAllocation + AsRef<[u8]> + Sync {}
+
+impl<Buffer> Drop for Wave<Buffer>
+where
+    Buffer: LinearAllocation + AsRef<[u8]>,
+{
+    fn drop(&mut self) {
+        // The wave was already removed if its channel's queue has been cleared since (e.g. when `Ndsp` is dropped).
+        if self.is_busy() {
+            // The unwrap is safe, since it must have a value in the case the wave is busy.
+            let id = self.played_on_channel.unwrap();
+
+            unsafe { ctru_sys::ndspChnWaveBufClear(id.into()) };
+            bump_queue_generation(id);
+
+            // Panicking while already unwinding would abort the program.
+            if cfg!(debug_assertions) && !std::thread::panicking() {
+                panic!("wave dropped while still queued on channel {id}");
+            }
+        }
+
+        // Flag the buffer's RAM sector as unused
+        // This step has no real effect in normal applications and is skipped even by devkitPRO's own examples.
+        if let Ok(Some((ptr, len))) = gfx::data_cache_region(self.get_buffer()) {
+            let _r = unsafe { ctru_sys::DSP_InvalidateDataCache(ptr, len) };
+        }
+    }
+}
+
+impl<Buffer> BufferGuard<'_, Buffer>
+where
+    Buffer: LinearAllocation + AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Flush the audio data, returning the result of [`Wave::flush()`].
+    pub fn finish(self) -> crate::Result<()> {
+        let result = self.wave.flush();
+
+        // The data was just flushed.
+        std::mem::forget(self);
+
+        result
+    }
+}
+
+impl<Buffer> Deref for BufferGuard<'_, Buffer>
+where
+    Buffer: LinearAllocation + AsRef<[u8]> + AsMut<[u8]>,
+{
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.wave.buffer.as_ref()
+    }
+}
+
+impl<Buffer> DerefMut for BufferGuard<'_, Buffer>
+where
+    Buffer: LinearAllocation + AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.wave.buffer.as_mut()
+    }
+}
+
+impl<Buffer> Drop for BufferGuard<'_, Buffer>
+where
+    Buffer: LinearAllocation + AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn drop(&mut self) {
+        // The result is only available through `finish()`.
+        let _ = self.wave.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_pcm16_copies_samples() {
+        let samples = [0i16, 1000, -1000, i16::MIN, i16::MAX, 1];
+
+        let wave = Wave::from_pcm16(&samples, true, false).unwrap();
+
+        assert_eq!(wave.format(), AudioFormat::PCM16Stereo);
+        assert_eq!(wave.sample_count(), 3);
+        assert_eq!(wave.samples::<i16>(), Some(&samples[..]));
+        assert_eq!(wave.get_buffer().as_ptr() as usize % DATA_ALIGNMENT, 0);
+    }
+
+    #[test]
+    fn from_pcm8_copies_samples() {
+        let samples = [0i8, 100, -100, i8::MIN, i8::MAX];
+
+        let wave = Wave::from_pcm8(&samples, false, true).unwrap();
+
+        assert_eq!(wave.format(), AudioFormat::PCM8Mono);
+        assert_eq!(wave.samples::<i8>(), Some(&samples[..]));
+        assert_eq!(wave.get_buffer().as_ptr() as usize % DATA_ALIGNMENT, 0);
+    }
+
+    #[test]
+    fn from_samples_odd_stereo() {
+        assert_eq!(
+            Wave::from_pcm16(&[0, 1, 2], true, false).err(),
+            Some(Error::InvalidStereoSampleCount(3))
+        );
+        assert_eq!(
+            Wave::from_pcm8(&[0], true, false).err(),
+            Some(Error::InvalidStereoSampleCount(1))
+        );
+    }
+
+    #[test]
+    fn from_samples_empty() {
+        let wave = Wave::from_pcm16(&[], false, false).unwrap();
+
+        assert_eq!(wave.sample_count(), 0);
+        assert_eq!(wave.samples::<i16>(), Some(&[][..]));
+    }
+
+    #[test]
+    fn from_wav_bytes_signs_pcm8() {
+        #[rustfmt::skip]
+        let file = [
+            b'R', b'I', b'F', b'F', 40, 0, 0, 0, b'W', b'A', b'V', b'E',
+            // PCM, 1 channel, 8000 Hz, 8 bits per sample.
+            b'f', b'm', b't', b' ', 16, 0, 0, 0,
+            1, 0, 1, 0, 0x40, 0x1F, 0, 0, 0x40, 0x1F, 0, 0, 1, 0, 8, 0,
+            b'd', b'a', b't', b'a', 4, 0, 0, 0,
+            0x00, 0x80, 0xFF, 0x7F,
+        ];
+
+        let (wave, sample_rate) = Wave::from_wav_bytes(&file, false).unwrap();
+
+        assert_eq!(sample_rate, 8000);
+        assert_eq!(wave.format(), AudioFormat::PCM8Mono);
+        assert_eq!(wave.samples::<i8>(), Some(&[i8::MIN, 0, 127, -1][..]));
+        assert_eq!(wave.get_buffer().as_ptr() as usize % DATA_ALIGNMENT, 0);
+    }
+
+    #[test]
+    fn edit_buffer_writes_data() {
+        let mut wave = Wave::zeroed(4, AudioFormat::PCM8Mono, false);
+
+        wave.edit_buffer().unwrap().copy_from_slice(&[1, 2, 3, 4]);
+
+        assert_eq!(wave.get_buffer(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn cleared_queue_frees_wave() {
+        // A channel which no other test uses, since its queue is never really touched.
+        const CHANNEL: u8 = 23;
+
+        let mut wave = Wave::zeroed(100, AudioFormat::PCM8Mono, false);
+
+        // Act as if the wave was queued, without involving the DSP.
+        wave.set_channel(CHANNEL);
+        wave.raw_data.status = ctru_sys::NDSP_WBUF_QUEUED;
+
+        assert_eq!(wave.get_buffer_mut().err(), Some(Error::WaveBusy(CHANNEL)));
+        assert_eq!(wave.set_sample_count(10), Err(Error::WaveBusy(CHANNEL)));
+
+        // `libctru` leaves the status as is when clearing the queue.
+        bump_queue_generation(CHANNEL);
+
+        assert!(matches!(wave.status(), Status::Queued));
+        assert!(!wave.is_busy());
+        assert!(wave.get_buffer_mut().is_ok());
+        assert_eq!(wave.set_sample_count(10), Ok(()));
+        assert_eq!(wave.set_start_offset(5), Ok(()));
     }
 }
@@ -0,0 +1,48 @@
+//! VRAM allocator.
+//!
+//! VRAM is a 6 MB memory sector dedicated to the GPU, which makes it the ideal place for textures, framebuffers and other GPU-resident data.
+//!
+//! # Notes
+//!
+//! VRAM isn't cached by the CPU, so reading and writing it from the CPU side is much slower than accessing regular memory.
+//! Data is usually prepared elsewhere (for example, in [LINEAR memory](crate::linear)) and then moved to VRAM with GX transfers.
+//!
+//! # Additional Resources
+//!
+//! - <https://github.com/devkitPro/libctru/blob/master/libctru/source/allocator/vram.cpp>
+//! - <https://www.3dbrew.org/wiki/Memory_layout>
+
+use std::alloc::{AllocError, Allocator, Layout};
+use std::ptr::NonNull;
+
+/// [`Allocator`] struct for VRAM.
+///
+/// To use this struct the main crate must activate the `allocator_api` unstable feature.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct VramAllocator;
+
+impl VramAllocator {
+    /// Returns the amount of free space left in VRAM.
+    #[doc(alias = "vramSpaceFree")]
+    pub fn free_space() -> u32 {
+        unsafe { ctru_sys::vramSpaceFree() }
+    }
+}
+
+unsafe impl Allocator for VramAllocator {
+    #[doc(alias = "vramAlloc", alias = "vramMemAlign")]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let pointer = unsafe { ctru_sys::vramMemAlign(layout.size(), layout.align()) };
+
+        NonNull::new(pointer.cast())
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, layout.size()))
+            .ok_or(AllocError)
+    }
+
+    #[doc(alias = "vramFree")]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, _layout: Layout) {
+        unsafe {
+            ctru_sys::vramFree(ptr.as_ptr().cast());
+        }
+    }
+}